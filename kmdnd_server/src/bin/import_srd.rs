@@ -0,0 +1,32 @@
+use std::env;
+use std::path::PathBuf;
+
+use kmdnd_server::Error;
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+// imports the 5e-database json files from the given directory
+fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_max_level(Level::INFO)
+        .with_span_events(FmtSpan::NEW)
+        .compact()
+        .init();
+
+    let directory = env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let report = kmdnd_server::import_srd(directory)?;
+
+    println!(
+        "imported {} items, {} spells and {} monsters",
+        report.items_imported, report.spells_imported, report.monsters_imported
+    );
+    for entry in &report.unmapped {
+        println!("skipped {} {}: {}", entry.kind, entry.name, entry.reason);
+    }
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use async_trait::async_trait;
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson;
 
+use crate::currency::Purse;
 use crate::database::MongoCampaignStore;
 use crate::error::Error;
 
@@ -17,6 +19,12 @@ pub trait CampaignStore {
         &self,
         campaign_id: CampaignId,
     ) -> Result<Option<Campaign>, Error>;
+
+    async fn update_campaign_treasury(
+        &self,
+        mut campaign: Campaign,
+        treasury: Purse,
+    ) -> Result<Campaign, Error>;
 }
 
 #[async_trait]
@@ -46,14 +54,46 @@ impl CampaignStore for MongoCampaignStore {
 
         Ok(campaign)
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_campaign_treasury(
+        &self,
+        mut campaign: Campaign,
+        treasury: Purse,
+    ) -> Result<Campaign, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(campaign.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_treasury = bson::to_document(&treasury)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": campaign.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "treasury": new_treasury, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        campaign.modified_at = now;
+        campaign.treasury = treasury;
+
+        Ok(campaign)
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::type_complexity)]
 pub struct MockCampaignStore {
     pub on_insert_campaign: Box<dyn Fn(&Campaign) -> Result<(), Error> + Send + Sync>,
     pub on_fetch_campaigns: Box<dyn Fn() -> Result<Vec<Campaign>, Error> + Send + Sync>,
     pub on_fetch_campaign_by_id:
         Box<dyn Fn(CampaignId) -> Result<Option<Campaign>, Error> + Send + Sync>,
+    pub on_update_campaign_treasury:
+        Box<dyn Fn(Campaign, Purse) -> Result<Campaign, Error> + Send + Sync>,
 }
 
 #[cfg(test)]
@@ -65,6 +105,9 @@ impl MockCampaignStore {
             on_fetch_campaign_by_id: Box::new(|_| {
                 unimplemented!("MockCampaignStore::fetch_campaign_by_id")
             }),
+            on_update_campaign_treasury: Box::new(|_, _| {
+                unimplemented!("MockCampaignStore::update_campaign_treasury")
+            }),
         }
     }
 }
@@ -86,4 +129,12 @@ impl CampaignStore for MockCampaignStore {
     ) -> Result<Option<Campaign>, Error> {
         (self.on_fetch_campaign_by_id)(campaign_id)
     }
+
+    async fn update_campaign_treasury(
+        &self,
+        campaign: Campaign,
+        treasury: Purse,
+    ) -> Result<Campaign, Error> {
+        (self.on_update_campaign_treasury)(campaign, treasury)
+    }
 }
@@ -5,6 +5,7 @@ use futures::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 
 use crate::character::{self, CharacterBody};
+use crate::currency::Purse;
 use crate::database::Database;
 use crate::encounter::{self, EncounterBody};
 use crate::error::Error;
@@ -14,12 +15,16 @@ use super::{manager, Campaign, CampaignId};
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CreateCampaignBody {
     pub name: String,
+    #[serde(default)]
+    pub variant_encumbrance: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CampaignBody {
     pub id: CampaignId,
     pub name: String,
+    pub variant_encumbrance: bool,
+    pub treasury: Purse,
     characters: Vec<CharacterBody>,
     current_encounter: Option<EncounterBody>,
     pub created_at: DateTime<Utc>,
@@ -33,13 +38,17 @@ impl CampaignBody {
             .then(|character| CharacterBody::render(db, character))
             .try_collect()
             .await?;
-        let current_encounter = encounter::manager::get_current_encounter(db, &campaign)
-            .await?
-            .map(EncounterBody::render);
+        let current_encounter =
+            match encounter::manager::get_current_encounter(db, &campaign).await? {
+                Some(encounter) => Some(EncounterBody::render(db, encounter).await?),
+                None => None,
+            };
 
         Ok(CampaignBody {
             id: campaign.id,
             name: campaign.name,
+            variant_encumbrance: campaign.variant_encumbrance,
+            treasury: campaign.treasury,
             created_at: campaign.created_at,
             modified_at: campaign.modified_at,
             characters,
@@ -56,11 +65,13 @@ async fn create_campaign(
 ) -> Result<Json<CampaignBody>, Error> {
     let body = body.into_inner();
 
-    let campaign = manager::create_campaign(&***db, body.name).await?;
+    let campaign = manager::create_campaign(&***db, body.name, body.variant_encumbrance).await?;
 
     let body = CampaignBody {
         id: campaign.id,
         name: campaign.name,
+        variant_encumbrance: campaign.variant_encumbrance,
+        treasury: campaign.treasury,
         created_at: campaign.created_at,
         modified_at: campaign.modified_at,
         characters: vec![],
@@ -1,16 +1,23 @@
 use chrono::Utc;
 
+use crate::currency::Purse;
 use crate::database::Database;
 use crate::error::Error;
 
 use super::{Campaign, CampaignId};
 
 #[tracing::instrument(skip(db))]
-pub async fn create_campaign(db: &dyn Database, name: String) -> Result<Campaign, Error> {
+pub async fn create_campaign(
+    db: &dyn Database,
+    name: String,
+    variant_encumbrance: bool,
+) -> Result<Campaign, Error> {
     let now = Utc::now();
     let campaign = Campaign {
         id: CampaignId::new(),
         name,
+        variant_encumbrance,
+        treasury: Purse::default(),
         created_at: now,
         modified_at: now,
     };
@@ -55,7 +62,9 @@ mod tests {
             Ok(())
         });
 
-        let campaign = create_campaign(&db, "Blue Man Group".into()).await.unwrap();
+        let campaign = create_campaign(&db, "Blue Man Group".into(), false)
+            .await
+            .unwrap();
 
         assert_eq!(campaign.name, "Blue Man Group".to_string());
         assert_eq!(campaign.created_at, campaign.modified_at);
@@ -78,6 +87,8 @@ mod tests {
             Ok(Some(Campaign {
                 id: campaign_id,
                 name: "Blue Man Group".to_string(),
+                variant_encumbrance: false,
+                treasury: Purse::default(),
                 created_at: now,
                 modified_at: now,
             }))
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::currency::Purse;
 use crate::typedid::{TypedId, TypedIdMarker};
 
 pub mod db;
@@ -15,6 +16,10 @@ pub struct Campaign {
     #[serde(rename = "_id")]
     pub id: CampaignId,
     pub name: String,
+    #[serde(default)]
+    pub variant_encumbrance: bool,
+    #[serde(default)]
+    pub treasury: Purse,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -26,3 +31,20 @@ impl TypedIdMarker for Campaign {
         "CPN"
     }
 }
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    pub fn campaign() -> Campaign {
+        let now = Utc::now();
+        Campaign {
+            id: CampaignId::new(),
+            name: "Blue Man Group".to_string(),
+            variant_encumbrance: false,
+            treasury: Purse::default(),
+            created_at: now,
+            modified_at: now,
+        }
+    }
+}
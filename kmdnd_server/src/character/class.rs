@@ -0,0 +1,538 @@
+use serde::{Deserialize, Serialize};
+
+use crate::item::{ArmorType, Dice};
+use crate::operation::{AbilityType, SkillType};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Class {
+    Barbarian,
+    Bard,
+    Cleric,
+    Druid,
+    Fighter,
+    Monk,
+    Paladin,
+    Ranger,
+    Rogue,
+    Sorcerer,
+    Warlock,
+    Wizard,
+}
+
+impl Class {
+    pub fn hit_die(&self) -> Dice {
+        match self {
+            Class::Barbarian => Dice::D12,
+            Class::Fighter | Class::Paladin | Class::Ranger => Dice::D10,
+            Class::Sorcerer | Class::Wizard => Dice::D6,
+            _ => Dice::D8,
+        }
+    }
+
+    pub fn saving_throws(&self) -> [AbilityType; 2] {
+        use AbilityType::*;
+
+        match self {
+            Class::Barbarian => [Strength, Constitution],
+            Class::Bard => [Dexterity, Charisma],
+            Class::Cleric => [Wisdom, Charisma],
+            Class::Druid => [Intelligence, Wisdom],
+            Class::Fighter => [Strength, Constitution],
+            Class::Monk => [Strength, Dexterity],
+            Class::Paladin => [Wisdom, Charisma],
+            Class::Ranger => [Strength, Dexterity],
+            Class::Rogue => [Dexterity, Intelligence],
+            Class::Sorcerer => [Constitution, Charisma],
+            Class::Warlock => [Wisdom, Charisma],
+            Class::Wizard => [Intelligence, Wisdom],
+        }
+    }
+
+    // the number of skills a character picks at first level and the skills
+    // they may pick from
+    pub fn skill_choices(&self) -> (usize, &'static [SkillType]) {
+        use SkillType::*;
+
+        match self {
+            Class::Barbarian => (
+                2,
+                &[
+                    AnimalHandling,
+                    Athletics,
+                    Intimidation,
+                    Nature,
+                    Perception,
+                    Survival,
+                ],
+            ),
+            Class::Bard => (3, SkillType::ALL),
+            Class::Cleric => (2, &[History, Insight, Medicine, Persuasion, Religion]),
+            Class::Druid => (
+                2,
+                &[
+                    Arcana,
+                    AnimalHandling,
+                    Insight,
+                    Medicine,
+                    Nature,
+                    Perception,
+                    Religion,
+                    Survival,
+                ],
+            ),
+            Class::Fighter => (
+                2,
+                &[
+                    Acrobatics,
+                    AnimalHandling,
+                    Athletics,
+                    History,
+                    Insight,
+                    Intimidation,
+                    Perception,
+                    Survival,
+                ],
+            ),
+            Class::Monk => (
+                2,
+                &[Acrobatics, Athletics, History, Insight, Religion, Stealth],
+            ),
+            Class::Paladin => (
+                2,
+                &[
+                    Athletics,
+                    Insight,
+                    Intimidation,
+                    Medicine,
+                    Persuasion,
+                    Religion,
+                ],
+            ),
+            Class::Ranger => (
+                3,
+                &[
+                    AnimalHandling,
+                    Athletics,
+                    Insight,
+                    Investigation,
+                    Nature,
+                    Perception,
+                    Stealth,
+                    Survival,
+                ],
+            ),
+            Class::Rogue => (
+                4,
+                &[
+                    Acrobatics,
+                    Athletics,
+                    Deception,
+                    Insight,
+                    Intimidation,
+                    Investigation,
+                    Perception,
+                    Performance,
+                    Persuasion,
+                    SleightOfHand,
+                    Stealth,
+                ],
+            ),
+            Class::Sorcerer => (
+                2,
+                &[
+                    Arcana,
+                    Deception,
+                    Insight,
+                    Intimidation,
+                    Persuasion,
+                    Religion,
+                ],
+            ),
+            Class::Warlock => (
+                2,
+                &[
+                    Arcana,
+                    Deception,
+                    History,
+                    Intimidation,
+                    Investigation,
+                    Nature,
+                    Religion,
+                ],
+            ),
+            Class::Wizard => (
+                2,
+                &[Arcana, History, Insight, Investigation, Medicine, Religion],
+            ),
+        }
+    }
+
+    pub fn armor_proficiencies(&self) -> &'static [ArmorType] {
+        use ArmorType::*;
+
+        match self {
+            Class::Fighter | Class::Paladin => &[Light, Medium, Heavy, Shield],
+            Class::Barbarian | Class::Cleric | Class::Druid | Class::Ranger => {
+                &[Light, Medium, Shield]
+            }
+            Class::Bard | Class::Rogue | Class::Warlock => &[Light],
+            Class::Monk | Class::Sorcerer | Class::Wizard => &[],
+        }
+    }
+
+    pub fn spellcasting_ability(&self, level: i32) -> Option<AbilityType> {
+        match self {
+            Class::Bard | Class::Sorcerer | Class::Warlock => Some(AbilityType::Charisma),
+            Class::Cleric | Class::Druid => Some(AbilityType::Wisdom),
+            Class::Wizard => Some(AbilityType::Intelligence),
+            Class::Paladin if level >= 2 => Some(AbilityType::Charisma),
+            Class::Ranger if level >= 2 => Some(AbilityType::Wisdom),
+            _ => None,
+        }
+    }
+
+    pub fn subclass_level(&self) -> i32 {
+        match self {
+            Class::Cleric | Class::Sorcerer | Class::Warlock => 1,
+            Class::Druid | Class::Wizard => 2,
+            _ => 3,
+        }
+    }
+
+    pub fn grants_ability_score_improvement(&self, level: i32) -> bool {
+        matches!(
+            (self, level),
+            (_, 4)
+                | (_, 8)
+                | (_, 12)
+                | (_, 16)
+                | (_, 19)
+                | (Class::Fighter, 6)
+                | (Class::Fighter, 14)
+                | (Class::Rogue, 10)
+        )
+    }
+
+    // warlocks use pact magic which does not combine with other spellcasting
+    pub fn caster_progression(&self) -> Option<CasterProgression> {
+        match self {
+            Class::Bard | Class::Cleric | Class::Druid | Class::Sorcerer | Class::Wizard => {
+                Some(CasterProgression::Full)
+            }
+            Class::Paladin | Class::Ranger => Some(CasterProgression::Half),
+            _ => None,
+        }
+    }
+
+    pub fn features(&self, level: i32) -> Vec<ClassFeature> {
+        let mut features = vec![];
+
+        match self {
+            Class::Barbarian => {
+                let uses = match level {
+                    1..=2 => 2,
+                    3..=5 => 3,
+                    6..=11 => 4,
+                    12..=16 => 5,
+                    _ => 6,
+                };
+                let damage = match level {
+                    1..=8 => 2,
+                    9..=15 => 3,
+                    _ => 4,
+                };
+                features.push(ClassFeature::Rage { uses, damage });
+
+                if level >= 5 {
+                    features.push(ClassFeature::ExtraAttack { attacks: 1 });
+                }
+            }
+            Class::Fighter => {
+                if level >= 2 {
+                    let uses = if level >= 17 { 2 } else { 1 };
+                    features.push(ClassFeature::ActionSurge { uses });
+                }
+
+                if level >= 20 {
+                    features.push(ClassFeature::ExtraAttack { attacks: 3 });
+                } else if level >= 11 {
+                    features.push(ClassFeature::ExtraAttack { attacks: 2 });
+                } else if level >= 5 {
+                    features.push(ClassFeature::ExtraAttack { attacks: 1 });
+                }
+            }
+            Class::Monk | Class::Paladin | Class::Ranger if level >= 5 => {
+                features.push(ClassFeature::ExtraAttack { attacks: 1 });
+            }
+            Class::Rogue => {
+                features.push(ClassFeature::SneakAttack {
+                    dice: (level + 1) / 2,
+                });
+            }
+            _ => {}
+        }
+
+        features
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Subclass {
+    PathOfTheBerserker,
+    CollegeOfLore,
+    LifeDomain,
+    CircleOfTheLand,
+    Champion,
+    WayOfTheOpenHand,
+    OathOfDevotion,
+    Hunter,
+    Thief,
+    DraconicBloodline,
+    TheFiend,
+    SchoolOfEvocation,
+}
+
+impl Subclass {
+    pub fn class(&self) -> Class {
+        match self {
+            Subclass::PathOfTheBerserker => Class::Barbarian,
+            Subclass::CollegeOfLore => Class::Bard,
+            Subclass::LifeDomain => Class::Cleric,
+            Subclass::CircleOfTheLand => Class::Druid,
+            Subclass::Champion => Class::Fighter,
+            Subclass::WayOfTheOpenHand => Class::Monk,
+            Subclass::OathOfDevotion => Class::Paladin,
+            Subclass::Hunter => Class::Ranger,
+            Subclass::Thief => Class::Rogue,
+            Subclass::DraconicBloodline => Class::Sorcerer,
+            Subclass::TheFiend => Class::Warlock,
+            Subclass::SchoolOfEvocation => Class::Wizard,
+        }
+    }
+
+    pub fn features(&self, _level: i32) -> Vec<ClassFeature> {
+        // subclass features are added here as they are given mechanics
+        vec![]
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ClassLevel {
+    pub class: Class,
+    pub level: i32,
+    pub subclass: Option<Subclass>,
+}
+
+impl ClassLevel {
+    pub fn features(&self) -> Vec<ClassFeature> {
+        let mut features = self.class.features(self.level);
+        if let Some(subclass) = &self.subclass {
+            features.extend(subclass.features(self.level));
+        }
+
+        features
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ClassFeature {
+    ExtraAttack { attacks: i32 },
+    SneakAttack { dice: i32 },
+    Rage { uses: i32, damage: i32 },
+    ActionSurge { uses: i32 },
+}
+
+impl ClassFeature {
+    pub fn limited_uses(&self) -> Option<(LimitedFeature, i32)> {
+        match self {
+            ClassFeature::Rage { uses, .. } => Some((LimitedFeature::Rage, *uses)),
+            ClassFeature::ActionSurge { uses } => Some((LimitedFeature::ActionSurge, *uses)),
+            _ => None,
+        }
+    }
+}
+
+// features that can only be used a number of times between rests
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum LimitedFeature {
+    Rage,
+    ActionSurge,
+    // racial traits are not used directly, but are tracked the same way
+    RelentlessEndurance,
+    BreathWeapon,
+}
+
+impl LimitedFeature {
+    pub fn recharges_on_short_rest(&self) -> bool {
+        match self {
+            LimitedFeature::Rage => false,
+            LimitedFeature::ActionSurge => true,
+            LimitedFeature::RelentlessEndurance => false,
+            LimitedFeature::BreathWeapon => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FeatureUse {
+    pub feature: LimitedFeature,
+    pub expended: i32,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CasterProgression {
+    Full,
+    Half,
+}
+
+const SPELL_SLOTS_BY_CASTER_LEVEL: [[i32; 9]; 20] = [
+    [2, 0, 0, 0, 0, 0, 0, 0, 0],
+    [3, 0, 0, 0, 0, 0, 0, 0, 0],
+    [4, 2, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 2, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 1, 0, 0, 0, 0, 0],
+    [4, 3, 3, 2, 0, 0, 0, 0, 0],
+    [4, 3, 3, 3, 1, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 2, 1, 1],
+];
+
+// a single class half caster rounds up, but multiclassed characters round
+// down when combining spellcasting levels
+pub fn caster_level(classes: &[ClassLevel]) -> i32 {
+    let casting_classes: Vec<_> = classes
+        .iter()
+        .filter_map(|class| Some((class.class.caster_progression()?, class.level)))
+        .collect();
+
+    match casting_classes.as_slice() {
+        [(CasterProgression::Half, level)] if *level >= 2 => (level + 1) / 2,
+        _ => casting_classes
+            .iter()
+            .map(|(progression, level)| match progression {
+                CasterProgression::Full => *level,
+                CasterProgression::Half => level / 2,
+            })
+            .sum(),
+    }
+}
+
+// the total number of spell slots for each spell level, starting at 1st level
+pub fn spell_slot_totals(classes: &[ClassLevel]) -> Vec<i32> {
+    let caster_level = i32::min(caster_level(classes), 20);
+    if caster_level < 1 {
+        return vec![];
+    }
+
+    SPELL_SLOTS_BY_CASTER_LEVEL[caster_level as usize - 1]
+        .iter()
+        .copied()
+        .take_while(|total| *total > 0)
+        .collect()
+}
+
+// warlocks have a few slots of a single level from pact magic, given as the
+// slot level and the number of slots
+pub fn pact_magic_slots(classes: &[ClassLevel]) -> Option<(i32, i32)> {
+    let level = classes
+        .iter()
+        .find(|class| class.class == Class::Warlock)?
+        .level;
+
+    let slot_level = i32::min((level + 1) / 2, 5);
+    let total = match level {
+        1 => 1,
+        2..=10 => 2,
+        11..=16 => 3,
+        _ => 4,
+    };
+
+    Some((slot_level, total))
+}
+
+pub fn proficiency_bonus(level: i32) -> i32 {
+    2 + (i32::max(level, 1) - 1) / 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proficiency_bonus_increases_every_four_levels() {
+        assert_eq!(proficiency_bonus(1), 2);
+        assert_eq!(proficiency_bonus(4), 2);
+        assert_eq!(proficiency_bonus(5), 3);
+        assert_eq!(proficiency_bonus(12), 4);
+        assert_eq!(proficiency_bonus(17), 6);
+        assert_eq!(proficiency_bonus(20), 6);
+    }
+
+    #[test]
+    fn fighter_gains_extra_attacks() {
+        let attacks = |level| {
+            Class::Fighter
+                .features(level)
+                .into_iter()
+                .find_map(|feature| match feature {
+                    ClassFeature::ExtraAttack { attacks } => Some(attacks),
+                    _ => None,
+                })
+        };
+
+        assert_eq!(attacks(4), None);
+        assert_eq!(attacks(5), Some(1));
+        assert_eq!(attacks(11), Some(2));
+        assert_eq!(attacks(20), Some(3));
+    }
+
+    #[test]
+    fn multiclass_spell_slots_combine_caster_levels() {
+        let paladin = ClassLevel {
+            class: Class::Paladin,
+            level: 5,
+            subclass: None,
+        };
+        let wizard = ClassLevel {
+            class: Class::Wizard,
+            level: 3,
+            subclass: None,
+        };
+
+        assert_eq!(
+            spell_slot_totals(std::slice::from_ref(&paladin)),
+            vec![4, 2]
+        );
+        assert_eq!(spell_slot_totals(&[paladin, wizard]), vec![4, 3, 2]);
+    }
+
+    #[test]
+    fn warlocks_get_pact_magic_slots_instead() {
+        let warlock = |level| ClassLevel {
+            class: Class::Warlock,
+            level,
+            subclass: None,
+        };
+
+        assert!(spell_slot_totals(&[warlock(5)]).is_empty());
+        assert_eq!(pact_magic_slots(&[warlock(1)]), Some((1, 1)));
+        assert_eq!(pact_magic_slots(&[warlock(5)]), Some((3, 2)));
+        assert_eq!(pact_magic_slots(&[warlock(11)]), Some((5, 3)));
+        assert_eq!(pact_magic_slots(&[warlock(20)]), Some((5, 4)));
+    }
+}
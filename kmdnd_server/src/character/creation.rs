@@ -0,0 +1,347 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::operation::{AbilityType, SkillType};
+
+use super::class::{Class, Subclass};
+use super::race::{DragonType, Race, RacialTrait, Subrace};
+use super::{CharacterAbilities, EquipmentEntry, ToolType};
+
+pub const STANDARD_ARRAY: [i32; 6] = [15, 14, 13, 12, 10, 8];
+pub const POINT_BUY_BUDGET: i32 = 27;
+
+// the choices made by the player when creating a character, anything left
+// unspecified falls back to a classless Human with all scores at 10
+#[derive(Clone, Debug)]
+pub struct CharacterCreation {
+    pub name: String,
+    pub race: Race,
+    pub subrace: Option<Subrace>,
+    pub draconic_ancestry: Option<DragonType>,
+    pub class: Option<Class>,
+    pub subclass: Option<Subclass>,
+    pub background: Option<Background>,
+    pub abilities: Option<AbilityScoreChoice>,
+    pub proficiencies: ProficiencyChoices,
+    pub equipment: Vec<EquipmentEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "method", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum AbilityScoreChoice {
+    StandardArray { scores: CharacterAbilities },
+    PointBuy { scores: CharacterAbilities },
+    // the rolled totals are assigned from highest to lowest in the order of
+    // the listed abilities
+    Rolled { priority: Vec<AbilityType> },
+}
+
+// kept on the character so the origin of their scores can be audited
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(tag = "method", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum AbilityScoreGeneration {
+    #[default]
+    Unspecified,
+    StandardArray,
+    PointBuy {
+        points_spent: i32,
+    },
+    Rolled {
+        rolls: Vec<AbilityScoreRoll>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AbilityScoreRoll {
+    pub ability: AbilityType,
+    pub dice: Vec<i32>,
+    pub total: i32,
+}
+
+impl AbilityScoreChoice {
+    pub fn generate<R: Rng>(
+        self,
+        rng: &mut R,
+    ) -> Result<(CharacterAbilities, AbilityScoreGeneration), Error> {
+        match self {
+            AbilityScoreChoice::StandardArray { scores } => {
+                let mut provided: Vec<i32> =
+                    AbilityType::ALL.iter().map(|a| scores.score(*a)).collect();
+                provided.sort_unstable_by(|a, b| b.cmp(a));
+                if provided != STANDARD_ARRAY {
+                    return Err(Error::AbilityScoresDoNotMatchStandardArray);
+                }
+
+                Ok((scores, AbilityScoreGeneration::StandardArray))
+            }
+            AbilityScoreChoice::PointBuy { scores } => {
+                let mut points_spent = 0;
+                for &ability in AbilityType::ALL {
+                    let score = scores.score(ability);
+                    points_spent += point_buy_cost(score)
+                        .ok_or(Error::AbilityScoreOutOfPointBuyRange { ability, score })?;
+                }
+
+                if points_spent > POINT_BUY_BUDGET {
+                    return Err(Error::AbilityScoresExceedPointBuy { points_spent });
+                }
+
+                Ok((scores, AbilityScoreGeneration::PointBuy { points_spent }))
+            }
+            AbilityScoreChoice::Rolled { priority } => {
+                let is_permutation = priority.len() == AbilityType::ALL.len()
+                    && AbilityType::ALL
+                        .iter()
+                        .all(|ability| priority.contains(ability));
+                if !is_permutation {
+                    return Err(Error::InvalidAbilityScorePriority { priority });
+                }
+
+                let mut rolls: Vec<(Vec<i32>, i32)> = (0..AbilityType::ALL.len())
+                    .map(|_| roll_ability_score(rng))
+                    .collect();
+                rolls.sort_by_key(|(_, total)| -total);
+
+                let mut scores = CharacterAbilities::default();
+                let rolls = priority
+                    .into_iter()
+                    .zip(rolls)
+                    .map(|(ability, (dice, total))| {
+                        *scores.score_mut(ability) = total;
+                        AbilityScoreRoll {
+                            ability,
+                            dice,
+                            total,
+                        }
+                    })
+                    .collect();
+
+                Ok((scores, AbilityScoreGeneration::Rolled { rolls }))
+            }
+        }
+    }
+}
+
+pub fn point_buy_cost(score: i32) -> Option<i32> {
+    match score {
+        8..=13 => Some(score - 8),
+        14 => Some(7),
+        15 => Some(9),
+        _ => None,
+    }
+}
+
+// rolls 4d6 and drops the lowest die
+fn roll_ability_score<R: Rng>(rng: &mut R) -> (Vec<i32>, i32) {
+    let dice: Vec<i32> = (0..4).map(|_| rng.gen_range(1..=6)).collect();
+    let total = dice.iter().sum::<i32>() - dice.iter().min().unwrap();
+
+    (dice, total)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Background {
+    Acolyte,
+    Charlatan,
+    Criminal,
+    Entertainer,
+    FolkHero,
+    GuildArtisan,
+    Hermit,
+    Noble,
+    Outlander,
+    Sage,
+    Sailor,
+    Soldier,
+    Urchin,
+}
+
+impl Background {
+    pub fn skill_proficiencies(&self) -> [SkillType; 2] {
+        use SkillType::*;
+
+        match self {
+            Background::Acolyte => [Insight, Religion],
+            Background::Charlatan => [Deception, SleightOfHand],
+            Background::Criminal => [Deception, Stealth],
+            Background::Entertainer => [Acrobatics, Performance],
+            Background::FolkHero => [AnimalHandling, Survival],
+            Background::GuildArtisan => [Insight, Persuasion],
+            Background::Hermit => [Medicine, Religion],
+            Background::Noble => [History, Persuasion],
+            Background::Outlander => [Athletics, Survival],
+            Background::Sage => [Arcana, History],
+            Background::Sailor => [Athletics, Perception],
+            Background::Soldier => [Athletics, Intimidation],
+            Background::Urchin => [SleightOfHand, Stealth],
+        }
+    }
+
+    // tool proficiencies that involve a choice (like a gaming set or musical
+    // instrument) are not included
+    pub fn tool_proficiencies(&self) -> &'static [ToolType] {
+        match self {
+            Background::Charlatan => &[ToolType::DisguiseKit, ToolType::ForgeryKit],
+            Background::Criminal => &[ToolType::TheivesTools],
+            Background::Entertainer => &[ToolType::DisguiseKit],
+            Background::Hermit => &[ToolType::HerbalismKit],
+            Background::Sailor => &[ToolType::NavigatorTools],
+            Background::Urchin => &[ToolType::DisguiseKit, ToolType::TheivesTools],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProficiencyChoices {
+    #[serde(default)]
+    pub class_skills: Vec<SkillType>,
+    #[serde(default)]
+    pub racial_skills: Vec<SkillType>,
+    #[serde(default)]
+    pub racial_tools: Vec<ToolType>,
+}
+
+impl ProficiencyChoices {
+    pub fn validate_class_skills(&self, class: Option<Class>) -> Result<(), Error> {
+        let (expected_count, allowed) = class.map(|c| c.skill_choices()).unwrap_or((0, &[]));
+        validate_choices(&self.class_skills, expected_count, allowed)
+    }
+
+    // fills in the racial traits that were left empty for the player to choose
+    pub fn apply_to_racial_traits(&self, racial_traits: &mut [RacialTrait]) -> Result<(), Error> {
+        let mut skills_chosen = false;
+        let mut tools_chosen = false;
+        for racial_trait in racial_traits.iter_mut() {
+            match racial_trait {
+                RacialTrait::SkillVersatility(skills) => {
+                    validate_choices(&self.racial_skills, 2, SkillType::ALL)?;
+                    *skills = self.racial_skills.clone();
+                    skills_chosen = true;
+                }
+                RacialTrait::ToolProficiency(tools) => {
+                    validate_choices(&self.racial_tools, 1, DWARVEN_TOOLS)?;
+                    *tools = self.racial_tools.clone();
+                    tools_chosen = true;
+                }
+                _ => {}
+            }
+        }
+
+        if !skills_chosen {
+            validate_choices(&self.racial_skills, 0, &[])?;
+        }
+        if !tools_chosen {
+            validate_choices(&self.racial_tools, 0, &[])?;
+        }
+
+        Ok(())
+    }
+}
+
+const DWARVEN_TOOLS: &[ToolType] = &[
+    ToolType::SmithsTools,
+    ToolType::BrewersSupplies,
+    ToolType::MasonsTools,
+];
+
+fn validate_choices<T: Copy + PartialEq + std::fmt::Debug>(
+    chosen: &[T],
+    expected_count: usize,
+    allowed: &[T],
+) -> Result<(), Error> {
+    let has_duplicates = chosen
+        .iter()
+        .enumerate()
+        .any(|(i, choice)| chosen[..i].contains(choice));
+    let all_allowed = chosen.iter().all(|choice| allowed.contains(choice));
+
+    if chosen.len() != expected_count || has_duplicates || !all_allowed {
+        return Err(Error::InvalidProficiencyChoice {
+            choices: chosen
+                .iter()
+                .map(|choice| format!("{:?}", choice))
+                .collect(),
+            expected_count,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+
+    use super::*;
+
+    fn scores(values: [i32; 6]) -> CharacterAbilities {
+        CharacterAbilities {
+            strength: values[0],
+            dexterity: values[1],
+            constitution: values[2],
+            intelligence: values[3],
+            wisdom: values[4],
+            charisma: values[5],
+        }
+    }
+
+    #[test]
+    fn standard_array_must_be_a_permutation() {
+        let mut rng = StepRng::new(0, 1);
+
+        let choice = AbilityScoreChoice::StandardArray {
+            scores: scores([8, 15, 14, 10, 13, 12]),
+        };
+        assert!(choice.generate(&mut rng).is_ok());
+
+        let choice = AbilityScoreChoice::StandardArray {
+            scores: scores([15, 15, 14, 10, 13, 12]),
+        };
+        assert!(choice.generate(&mut rng).is_err());
+    }
+
+    #[test]
+    fn point_buy_must_fit_the_budget() {
+        let mut rng = StepRng::new(0, 1);
+
+        let choice = AbilityScoreChoice::PointBuy {
+            scores: scores([15, 15, 15, 8, 8, 8]),
+        };
+        assert!(choice.generate(&mut rng).is_ok());
+
+        let choice = AbilityScoreChoice::PointBuy {
+            scores: scores([15, 15, 15, 9, 8, 8]),
+        };
+        assert!(choice.generate(&mut rng).is_err());
+
+        let choice = AbilityScoreChoice::PointBuy {
+            scores: scores([16, 8, 8, 8, 8, 8]),
+        };
+        assert!(choice.generate(&mut rng).is_err());
+    }
+
+    #[test]
+    fn rolled_scores_drop_the_lowest_die() {
+        let mut rng = rand::thread_rng();
+
+        let choice = AbilityScoreChoice::Rolled {
+            priority: AbilityType::ALL.to_vec(),
+        };
+        let (abilities, generation) = choice.generate(&mut rng).unwrap();
+        let rolls = match generation {
+            AbilityScoreGeneration::Rolled { rolls } => rolls,
+            _ => panic!("expected rolled generation"),
+        };
+
+        assert_eq!(rolls.len(), 6);
+        for roll in &rolls {
+            let lowest = roll.dice.iter().min().unwrap();
+            assert_eq!(roll.total, roll.dice.iter().sum::<i32>() - lowest);
+            assert_eq!(abilities.score(roll.ability), roll.total);
+        }
+        assert!(rolls.windows(2).all(|w| w[0].total >= w[1].total));
+    }
+}
@@ -4,10 +4,13 @@ use futures::TryStreamExt;
 use mongodb::bson;
 
 use crate::campaign::CampaignId;
+use crate::currency::Purse;
 use crate::database::MongoCharacterStore;
 use crate::error::Error;
+use crate::item::ItemId;
 
-use super::{Character, CharacterId, Position};
+use super::class::FeatureUse;
+use super::{Character, CharacterId, Condition, EquipmentEntry, Position, SpellSlot};
 
 #[async_trait]
 pub trait CharacterStore {
@@ -24,6 +27,12 @@ pub trait CharacterStore {
         character_id: CharacterId,
     ) -> Result<Option<Character>, Error>;
 
+    async fn fetch_characters_by_item(&self, item_id: ItemId) -> Result<Vec<Character>, Error>;
+
+    async fn update_character(&self, mut character: Character) -> Result<Character, Error>;
+
+    async fn update_character_details(&self, mut character: Character) -> Result<Character, Error>;
+
     async fn update_character_position(
         &self,
         mut character: Character,
@@ -35,6 +44,44 @@ pub trait CharacterStore {
         mut character: Character,
         hit_points: i32,
     ) -> Result<Character, Error>;
+
+    async fn update_character_equipment(
+        &self,
+        mut character: Character,
+        equipment: Vec<EquipmentEntry>,
+    ) -> Result<Character, Error>;
+
+    async fn update_character_spell_slots(
+        &self,
+        mut character: Character,
+        spell_slots: Vec<SpellSlot>,
+    ) -> Result<Character, Error>;
+
+    async fn update_character_purse(
+        &self,
+        mut character: Character,
+        purse: Purse,
+    ) -> Result<Character, Error>;
+
+    async fn update_character_experience(
+        &self,
+        mut character: Character,
+        experience: i32,
+    ) -> Result<Character, Error>;
+
+    async fn update_character_feature_uses(
+        &self,
+        mut character: Character,
+        feature_uses: Vec<FeatureUse>,
+    ) -> Result<Character, Error>;
+
+    async fn update_character_conditions(
+        &self,
+        mut character: Character,
+        conditions: Vec<Condition>,
+    ) -> Result<Character, Error>;
+
+    async fn delete_character(&self, character: Character) -> Result<(), Error>;
 }
 
 #[async_trait]
@@ -76,6 +123,74 @@ impl CharacterStore for MongoCharacterStore {
         Ok(character)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn fetch_characters_by_item(&self, item_id: ItemId) -> Result<Vec<Character>, Error> {
+        let characters: Vec<Character> = self
+            .find(bson::doc! { "equipment.item_id": item_id }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(characters)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character(&self, mut character: Character) -> Result<Character, Error> {
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        character.modified_at = Utc::now();
+
+        let result = self
+            .replace_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                &character,
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_details(&self, mut character: Character) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_ability_scores = bson::to_bson(&character.ability_scores)?;
+        let new_stats = bson::to_document(&character.stats)?;
+        let new_proficiencies = bson::to_document(&character.proficiencies)?;
+        let new_spell_slots = bson::to_bson(&character.spell_slots)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": {
+                    "name": &character.name,
+                    "ability_scores": new_ability_scores,
+                    "stats": new_stats,
+                    "proficiencies": new_proficiencies,
+                    "spell_slots": new_spell_slots,
+                    "current_hit_points": character.current_hit_points,
+                    "maximum_hit_points": character.maximum_hit_points,
+                    "ai_controlled": character.ai_controlled,
+                    "modified_at": new_modified_at,
+                } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+
+        Ok(character)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn update_character_position(
         &self,
@@ -133,4 +248,383 @@ impl CharacterStore for MongoCharacterStore {
 
         Ok(character)
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_equipment(
+        &self,
+        mut character: Character,
+        equipment: Vec<EquipmentEntry>,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_equipment = bson::to_bson(&equipment)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "equipment": new_equipment, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.equipment = equipment;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_purse(
+        &self,
+        mut character: Character,
+        purse: Purse,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_purse = bson::to_document(&purse)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "purse": new_purse, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.purse = purse;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_spell_slots(
+        &self,
+        mut character: Character,
+        spell_slots: Vec<SpellSlot>,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_spell_slots = bson::to_bson(&spell_slots)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "spell_slots": new_spell_slots, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.spell_slots = spell_slots;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_experience(
+        &self,
+        mut character: Character,
+        experience: i32,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "experience": experience, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.experience = experience;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_feature_uses(
+        &self,
+        mut character: Character,
+        feature_uses: Vec<FeatureUse>,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_feature_uses = bson::to_bson(&feature_uses)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "feature_uses": new_feature_uses, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.feature_uses = feature_uses;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_character_conditions(
+        &self,
+        mut character: Character,
+        conditions: Vec<Condition>,
+    ) -> Result<Character, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_conditions = bson::to_bson(&conditions)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": { "conditions": new_conditions, "modified_at": new_modified_at } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        character.modified_at = now;
+        character.conditions = conditions;
+
+        Ok(character)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_character(&self, character: Character) -> Result<(), Error> {
+        let old_modified_at = bson::DateTime::from_chrono(character.modified_at);
+
+        let result = self
+            .delete_one(
+                bson::doc! { "_id": character.id, "modified_at": old_modified_at },
+                None,
+            )
+            .await?;
+
+        if result.deleted_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+pub struct MockCharacterStore {
+    pub on_insert_character: Box<dyn Fn(&Character) -> Result<(), Error> + Send + Sync>,
+    pub on_fetch_characters_by_campaign:
+        Box<dyn Fn(CampaignId) -> Result<Vec<Character>, Error> + Send + Sync>,
+    pub on_fetch_character_by_campaign_and_id:
+        Box<dyn Fn(CampaignId, CharacterId) -> Result<Option<Character>, Error> + Send + Sync>,
+    pub on_fetch_characters_by_item:
+        Box<dyn Fn(ItemId) -> Result<Vec<Character>, Error> + Send + Sync>,
+    pub on_update_character: Box<dyn Fn(Character) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_details:
+        Box<dyn Fn(Character) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_position:
+        Box<dyn Fn(Character, Option<Position>) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_hit_points:
+        Box<dyn Fn(Character, i32) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_equipment:
+        Box<dyn Fn(Character, Vec<EquipmentEntry>) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_spell_slots:
+        Box<dyn Fn(Character, Vec<SpellSlot>) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_purse:
+        Box<dyn Fn(Character, Purse) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_experience:
+        Box<dyn Fn(Character, i32) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_feature_uses:
+        Box<dyn Fn(Character, Vec<FeatureUse>) -> Result<Character, Error> + Send + Sync>,
+    pub on_update_character_conditions:
+        Box<dyn Fn(Character, Vec<Condition>) -> Result<Character, Error> + Send + Sync>,
+    pub on_delete_character: Box<dyn Fn(Character) -> Result<(), Error> + Send + Sync>,
+}
+
+#[cfg(test)]
+impl MockCharacterStore {
+    pub fn new() -> MockCharacterStore {
+        MockCharacterStore {
+            on_insert_character: Box::new(|_| {
+                unimplemented!("MockCharacterStore::insert_character")
+            }),
+            on_fetch_characters_by_campaign: Box::new(|_| {
+                unimplemented!("MockCharacterStore::fetch_characters_by_campaign")
+            }),
+            on_fetch_character_by_campaign_and_id: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::fetch_character_by_campaign_and_id")
+            }),
+            on_fetch_characters_by_item: Box::new(|_| {
+                unimplemented!("MockCharacterStore::fetch_characters_by_item")
+            }),
+            on_update_character: Box::new(|_| {
+                unimplemented!("MockCharacterStore::update_character")
+            }),
+            on_update_character_details: Box::new(|_| {
+                unimplemented!("MockCharacterStore::update_character_details")
+            }),
+            on_update_character_position: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_position")
+            }),
+            on_update_character_hit_points: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_hit_points")
+            }),
+            on_update_character_equipment: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_equipment")
+            }),
+            on_update_character_spell_slots: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_spell_slots")
+            }),
+            on_update_character_purse: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_purse")
+            }),
+            on_update_character_experience: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_experience")
+            }),
+            on_update_character_feature_uses: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_feature_uses")
+            }),
+            on_update_character_conditions: Box::new(|_, _| {
+                unimplemented!("MockCharacterStore::update_character_conditions")
+            }),
+            on_delete_character: Box::new(|_| {
+                unimplemented!("MockCharacterStore::delete_character")
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl CharacterStore for MockCharacterStore {
+    async fn insert_character(&self, character: &Character) -> Result<(), Error> {
+        (self.on_insert_character)(character)
+    }
+
+    async fn fetch_characters_by_campaign(
+        &self,
+        campaign_id: CampaignId,
+    ) -> Result<Vec<Character>, Error> {
+        (self.on_fetch_characters_by_campaign)(campaign_id)
+    }
+
+    async fn fetch_character_by_campaign_and_id(
+        &self,
+        campaign_id: CampaignId,
+        character_id: CharacterId,
+    ) -> Result<Option<Character>, Error> {
+        (self.on_fetch_character_by_campaign_and_id)(campaign_id, character_id)
+    }
+
+    async fn fetch_characters_by_item(&self, item_id: ItemId) -> Result<Vec<Character>, Error> {
+        (self.on_fetch_characters_by_item)(item_id)
+    }
+
+    async fn update_character(&self, character: Character) -> Result<Character, Error> {
+        (self.on_update_character)(character)
+    }
+
+    async fn update_character_details(&self, character: Character) -> Result<Character, Error> {
+        (self.on_update_character_details)(character)
+    }
+
+    async fn update_character_position(
+        &self,
+        character: Character,
+        position: Option<Position>,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_position)(character, position)
+    }
+
+    async fn update_character_hit_points(
+        &self,
+        character: Character,
+        hit_points: i32,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_hit_points)(character, hit_points)
+    }
+
+    async fn update_character_equipment(
+        &self,
+        character: Character,
+        equipment: Vec<EquipmentEntry>,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_equipment)(character, equipment)
+    }
+
+    async fn update_character_spell_slots(
+        &self,
+        character: Character,
+        spell_slots: Vec<SpellSlot>,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_spell_slots)(character, spell_slots)
+    }
+
+    async fn update_character_purse(
+        &self,
+        character: Character,
+        purse: Purse,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_purse)(character, purse)
+    }
+
+    async fn update_character_experience(
+        &self,
+        character: Character,
+        experience: i32,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_experience)(character, experience)
+    }
+
+    async fn update_character_feature_uses(
+        &self,
+        character: Character,
+        feature_uses: Vec<FeatureUse>,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_feature_uses)(character, feature_uses)
+    }
+
+    async fn update_character_conditions(
+        &self,
+        character: Character,
+        conditions: Vec<Condition>,
+    ) -> Result<Character, Error> {
+        (self.on_update_character_conditions)(character, conditions)
+    }
+
+    async fn delete_character(&self, character: Character) -> Result<(), Error> {
+        (self.on_delete_character)(character)
+    }
 }
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use super::race::Size;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Encumbrance {
+    Unencumbered,
+    Encumbered,
+    HeavilyEncumbered,
+    OverCapacity,
+}
+
+impl Encumbrance {
+    // the encumbered thresholds are only used with the variant rule, otherwise
+    // only exceeding the carrying capacity has an effect
+    pub fn from_weight(weight: i32, strength: i32, size: Size, variant: bool) -> Encumbrance {
+        let multiplier = size_multiplier(size);
+        if weight > carrying_capacity(strength, size) {
+            Encumbrance::OverCapacity
+        } else if variant && weight * 2 > strength * 10 * multiplier {
+            Encumbrance::HeavilyEncumbered
+        } else if variant && weight * 2 > strength * 5 * multiplier {
+            Encumbrance::Encumbered
+        } else {
+            Encumbrance::Unencumbered
+        }
+    }
+
+    pub fn apply_to_speed(&self, speed: i32) -> i32 {
+        match self {
+            Encumbrance::Unencumbered => speed,
+            Encumbrance::Encumbered => i32::max(speed - 10, 0),
+            Encumbrance::HeavilyEncumbered => i32::max(speed - 20, 0),
+            Encumbrance::OverCapacity => i32::min(speed, 5),
+        }
+    }
+
+    // heavily encumbered creatures have disadvantage on strength, dexterity
+    // and constitution ability checks, saves and on attack rolls
+    pub fn imposes_disadvantage(&self) -> bool {
+        matches!(
+            self,
+            Encumbrance::HeavilyEncumbered | Encumbrance::OverCapacity
+        )
+    }
+}
+
+pub fn carrying_capacity(strength: i32, size: Size) -> i32 {
+    strength * 15 * size_multiplier(size) / 2
+}
+
+// expressed in halves so tiny creatures can carry half as much
+fn size_multiplier(size: Size) -> i32 {
+    match size {
+        Size::Tiny => 1,
+        Size::Small | Size::Medium => 2,
+        Size::Large => 4,
+        Size::Huge => 8,
+        Size::Gargantuan => 16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_encumbrance_uses_strength_thresholds() {
+        let encumbrance =
+            |weight, variant| Encumbrance::from_weight(weight, 10, Size::Medium, variant);
+
+        assert_eq!(encumbrance(50, true), Encumbrance::Unencumbered);
+        assert_eq!(encumbrance(51, true), Encumbrance::Encumbered);
+        assert_eq!(encumbrance(101, true), Encumbrance::HeavilyEncumbered);
+        assert_eq!(encumbrance(101, false), Encumbrance::Unencumbered);
+        assert_eq!(encumbrance(151, false), Encumbrance::OverCapacity);
+    }
+}
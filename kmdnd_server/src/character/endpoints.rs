@@ -1,22 +1,57 @@
-use actix_web::web::{Data, Json, Path};
-use actix_web::{get, post};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
 use chrono::{DateTime, Utc};
 use futures::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::{self, CampaignId};
+use crate::currency::Purse;
 use crate::database::Database;
+use crate::encounter::{self, EncounterId};
 use crate::error::Error;
-use crate::item::{self, ItemBody};
+use crate::item::{self, DiceRoll, Item, ItemBody, ItemId};
+use crate::monster::MonsterInstance;
 use crate::operation::RollType;
 
+use super::class::{Class, ClassLevel, FeatureUse, Subclass};
+use super::creation::{
+    AbilityScoreChoice, AbilityScoreGeneration, Background, CharacterCreation, ProficiencyChoices,
+};
+use super::level::{self, LevelUp};
+use super::race::{DragonType, Race, RacialTrait, Size, Subrace};
 use super::{
-    manager, Character, CharacterId, CharacterOwner, CharacterStats, Position, RollModifier,
+    manager, Character, CharacterAbilities, CharacterId, CharacterOwner, CharacterStats,
+    CharacterUpdate, Condition, EquipmentEntry, HitDiceUse, ItemChargeUse, Position, Proficiencies,
+    RollCircumstances, RollModifier, SpellSlot,
 };
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct CreateCharacterBody {
     pub name: String,
+    #[serde(default = "default_race")]
+    pub race: Race,
+    pub subrace: Option<Subrace>,
+    pub draconic_ancestry: Option<DragonType>,
+    pub class: Option<Class>,
+    pub subclass: Option<Subclass>,
+    pub background: Option<Background>,
+    pub abilities: Option<AbilityScoreChoice>,
+    #[serde(default)]
+    pub proficiencies: ProficiencyChoices,
+    #[serde(default)]
+    pub equipment: Vec<EquipmentEntry>,
+}
+
+fn default_race() -> Race {
+    Race::Human
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AwardExperienceBody {
+    pub experience: i32,
+    pub encounter_id: Option<EncounterId>,
+    #[serde(default)]
+    pub character_ids: Vec<CharacterId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -26,24 +61,51 @@ pub struct CharacterBody {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub modified_at: DateTime<Utc>,
+    pub ability_scores: CharacterAbilities,
+    pub ability_score_generation: AbilityScoreGeneration,
     pub stats: CharacterStats,
     pub equipment: Vec<ItemWithQuantityBody>,
+    pub purse: Purse,
     pub position: Option<Position>,
     pub current_hit_points: i32,
     pub maximum_hit_points: i32,
+    pub level: i32,
+    pub experience: i32,
+    pub can_level_up: bool,
+    pub classes: Vec<ClassLevel>,
+    pub hit_dice: Vec<DiceRoll>,
+    pub expended_hit_dice: Vec<HitDiceUse>,
+    pub feature_uses: Vec<FeatureUse>,
+    pub attuned_items: Vec<ItemId>,
+    pub item_charges: Vec<ItemChargeUse>,
+    pub spell_slots: Vec<SpellSlot>,
+    pub conditions: Vec<Condition>,
+    pub exhaustion: i32,
+    pub race: Option<Race>,
+    pub subrace: Option<Subrace>,
+    pub background: Option<Background>,
+    pub size: Size,
+    pub racial_traits: Vec<RacialTrait>,
+    pub proficiencies: Proficiencies,
+    pub monster: Option<MonsterInstance>,
+    pub ai_controlled: bool,
 }
 
 impl CharacterBody {
     pub async fn render(db: &dyn Database, character: Character) -> Result<CharacterBody, Error> {
-        let mut equipment = vec![];
-        for entry in character.equipment {
-            let item = item::manager::expect_item_by_id(db, entry.item_id).await?;
-            let body = ItemBody::render(item);
-            equipment.push(ItemWithQuantityBody {
-                quantity: entry.quantity,
-                item: body,
-            });
+        let level = character.level();
+        let can_level_up = level < level::level_for_experience(character.experience);
+        let hit_dice = character.hit_dice();
+        let size = character.size();
+        let campaign_id = character.owner.campaign_id();
+
+        let mut items = vec![];
+        for entry in &character.equipment {
+            let item = item::manager::expect_item_by_id(db, campaign_id, entry.item_id).await?;
+            items.push(item);
         }
+        let equipment = ItemWithQuantityBody::render_contents(&character.equipment, &items, None);
+        let ability_scores = character.base_abilities();
 
         Ok(CharacterBody {
             id: character.id,
@@ -51,11 +113,34 @@ impl CharacterBody {
             name: character.name,
             created_at: character.created_at,
             modified_at: character.modified_at,
+            ability_scores,
+            ability_score_generation: character.ability_score_generation,
             stats: character.stats,
             equipment,
+            purse: character.purse,
             position: character.position,
             current_hit_points: character.current_hit_points,
             maximum_hit_points: character.maximum_hit_points,
+            level,
+            experience: character.experience,
+            can_level_up,
+            classes: character.classes,
+            hit_dice,
+            expended_hit_dice: character.expended_hit_dice,
+            feature_uses: character.feature_uses,
+            attuned_items: character.attuned_items,
+            item_charges: character.item_charges,
+            spell_slots: character.spell_slots,
+            conditions: character.conditions,
+            exhaustion: character.exhaustion,
+            race: character.race,
+            subrace: character.subrace,
+            background: character.background,
+            size,
+            racial_traits: character.racial_traits,
+            proficiencies: character.proficiencies,
+            monster: character.monster,
+            ai_controlled: character.ai_controlled,
         })
     }
 }
@@ -63,12 +148,41 @@ impl CharacterBody {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemWithQuantityBody {
     pub quantity: i32,
+    pub equiped: bool,
     pub item: ItemBody,
+    pub contents: Vec<ItemWithQuantityBody>,
+}
+
+impl ItemWithQuantityBody {
+    // renders the entries stored in the container, or those not in any
+    // container, along with everything stored inside them
+    fn render_contents(
+        equipment: &[EquipmentEntry],
+        items: &[Item],
+        container_id: Option<ItemId>,
+    ) -> Vec<ItemWithQuantityBody> {
+        equipment
+            .iter()
+            .filter(|entry| entry.container_id == container_id)
+            .filter_map(|entry| {
+                let item = items.iter().find(|item| item.id == entry.item_id)?;
+
+                Some(ItemWithQuantityBody {
+                    quantity: entry.quantity,
+                    equiped: entry.equiped,
+                    item: ItemBody::render(item.clone()),
+                    contents: Self::render_contents(equipment, items, Some(item.id)),
+                })
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct RollStatsBody {
     modifier: RollModifier,
+    bonus: i32,
+    reroll_ones: bool,
 }
 
 #[post("/campaigns/{campaign_id}/characters")]
@@ -84,7 +198,20 @@ async fn create_character_in_campaign(
         .ok_or(Error::CampaignNotFound { campaign_id })?;
     let body = body.into_inner();
 
-    let character = manager::create_character(&***db, &campaign, body.name).await?;
+    let creation = CharacterCreation {
+        name: body.name,
+        race: body.race,
+        subrace: body.subrace,
+        draconic_ancestry: body.draconic_ancestry,
+        class: body.class,
+        subclass: body.subclass,
+        background: body.background,
+        abilities: body.abilities,
+        proficiencies: body.proficiencies,
+        equipment: body.equipment,
+    };
+
+    let character = manager::create_character(&***db, &campaign, creation).await?;
 
     Ok(Json(CharacterBody::render(&***db, character).await?))
 }
@@ -130,11 +257,122 @@ async fn get_character_in_campaign_by_id(
     Ok(Json(CharacterBody::render(&***db, character).await?))
 }
 
+#[patch("/campaigns/{campaign_id}/characters/{character_id}")]
+#[tracing::instrument(skip(db))]
+async fn update_character_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, CharacterId)>,
+    body: Json<CharacterUpdate>,
+) -> Result<Json<CharacterBody>, Error> {
+    let (campaign_id, character_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let character = manager::get_character_by_id(&***db, &campaign, character_id)
+        .await?
+        .ok_or(Error::CharacterNotFoundInCampaign {
+            campaign_id: campaign.id,
+            character_id,
+        })?;
+    let body = body.into_inner();
+
+    let character = manager::update_character_details(&***db, character, body).await?;
+
+    Ok(Json(CharacterBody::render(&***db, character).await?))
+}
+
+#[delete("/campaigns/{campaign_id}/characters/{character_id}")]
+#[tracing::instrument(skip(db))]
+async fn delete_character_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, CharacterId)>,
+) -> Result<HttpResponse, Error> {
+    let (campaign_id, character_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let character = manager::get_character_by_id(&***db, &campaign, character_id)
+        .await?
+        .ok_or(Error::CharacterNotFoundInCampaign {
+            campaign_id: campaign.id,
+            character_id,
+        })?;
+
+    manager::delete_character(&***db, &campaign, character).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/campaigns/{campaign_id}/characters/{character_id}/level-up")]
+#[tracing::instrument(skip(db))]
+async fn level_up_character_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, CharacterId)>,
+    body: Json<LevelUp>,
+) -> Result<Json<CharacterBody>, Error> {
+    let (campaign_id, character_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let character = manager::get_character_by_id(&***db, &campaign, character_id)
+        .await?
+        .ok_or(Error::CharacterNotFoundInCampaign {
+            campaign_id: campaign.id,
+            character_id,
+        })?;
+    let body = body.into_inner();
+
+    let character = manager::level_up_character(&***db, &campaign, character, body).await?;
+
+    Ok(Json(CharacterBody::render(&***db, character).await?))
+}
+
+#[post("/campaigns/{campaign_id}/experience")]
+#[tracing::instrument(skip(db))]
+async fn award_experience_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<AwardExperienceBody>,
+) -> Result<Json<Vec<CharacterBody>>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let characters = match body.encounter_id {
+        Some(encounter_id) => {
+            let encounter =
+                encounter::manager::get_encounter_by_id(&***db, &campaign, encounter_id)
+                    .await?
+                    .ok_or(Error::EncounterNotFoundInCampaign {
+                        campaign_id: campaign.id,
+                        encounter_id,
+                    })?;
+
+            manager::award_encounter_experience(&***db, &campaign, &encounter, body.experience)
+                .await?
+        }
+        None => {
+            manager::award_experience(&***db, &campaign, body.character_ids, body.experience)
+                .await?
+        }
+    };
+
+    let body = stream::iter(characters)
+        .then(|character| CharacterBody::render(&***db, character))
+        .try_collect()
+        .await?;
+
+    Ok(Json(body))
+}
+
 #[get("/campaigns/{campaign_id}/characters/{character_id}/roll/{roll_type}")]
 #[tracing::instrument(skip(db))]
 async fn get_character_roll_stats(
     db: Data<Box<dyn Database>>,
     params: Path<(CampaignId, CharacterId, RollType)>,
+    query: Query<RollCircumstances>,
 ) -> Result<Json<RollStatsBody>, Error> {
     let (campaign_id, character_id, roll_type) = params.into_inner();
     let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
@@ -147,8 +385,21 @@ async fn get_character_roll_stats(
             character_id,
         })?;
 
+    let circumstances = query.into_inner();
+
     let modifier =
-        manager::get_character_roll_stats(&***db, &campaign, &character, roll_type).await?;
+        manager::get_character_roll_stats(&***db, &campaign, &character, roll_type, &circumstances)
+            .await?;
+    let bonus = match roll_type {
+        RollType::Save(_) => character.stats.saving_throw_bonus,
+        _ => 0,
+    };
+    let reroll_ones = roll_type != RollType::Damage
+        && character.has_racial_trait(|t| matches!(t, RacialTrait::Lucky));
 
-    Ok(Json(RollStatsBody { modifier }))
+    Ok(Json(RollStatsBody {
+        modifier,
+        bonus,
+        reroll_ones,
+    }))
 }
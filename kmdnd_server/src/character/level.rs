@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::item::Dice;
+use crate::operation::AbilityType;
+
+use super::class::{Class, ClassLevel, Subclass};
+use super::race::RacialTrait;
+use super::Character;
+
+pub const MAXIMUM_LEVEL: i32 = 20;
+pub const MAXIMUM_ABILITY_SCORE: i32 = 20;
+
+const EXPERIENCE_BY_LEVEL: [i32; 20] = [
+    0, 300, 900, 2700, 6500, 14000, 23000, 34000, 48000, 64000, 85000, 100000, 120000, 140000,
+    165000, 195000, 225000, 265000, 305000, 355000,
+];
+
+pub fn experience_for_level(level: i32) -> i32 {
+    let index = i32::clamp(level, 1, MAXIMUM_LEVEL) - 1;
+    EXPERIENCE_BY_LEVEL[index as usize]
+}
+
+pub fn level_for_experience(experience: i32) -> i32 {
+    EXPERIENCE_BY_LEVEL
+        .iter()
+        .take_while(|required| **required <= experience)
+        .count() as i32
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum HitPointChoice {
+    Roll { result: i32 },
+    Average,
+}
+
+impl HitPointChoice {
+    pub fn hit_points(&self, hit_die: Dice) -> Result<i32, Error> {
+        match *self {
+            HitPointChoice::Roll { result } if result < 1 || result > hit_die.sides() => {
+                Err(Error::LevelUpHitPointRollOutOfRange {
+                    dice: hit_die,
+                    result,
+                })
+            }
+            HitPointChoice::Roll { result } => Ok(result),
+            HitPointChoice::Average => Ok(hit_die.sides() / 2 + 1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LevelUp {
+    pub class: Class,
+    pub subclass: Option<Subclass>,
+    pub hit_points: HitPointChoice,
+    #[serde(default)]
+    pub ability_score_improvements: Vec<AbilityType>,
+}
+
+impl LevelUp {
+    // validates the choices against the character and applies them, the
+    // derived stats should be recalculated afterwards
+    pub fn apply(self, character: &mut Character) -> Result<(), Error> {
+        let current_level = character.level();
+        if current_level >= MAXIMUM_LEVEL {
+            return Err(Error::CharacterAtMaximumLevel {
+                character_id: character.id,
+            });
+        }
+
+        let required_experience = experience_for_level(current_level + 1);
+        if character.experience < required_experience {
+            return Err(Error::CharacterLacksExperience {
+                character_id: character.id,
+                experience: character.experience,
+                required_experience,
+            });
+        }
+
+        let (class_level, current_subclass) = character
+            .classes
+            .iter()
+            .find(|class| class.class == self.class)
+            .map(|class| (class.level + 1, class.subclass))
+            .unwrap_or((1, None));
+
+        let subclass_is_valid = match (current_subclass, self.subclass) {
+            (Some(_), None) => true,
+            (Some(_), Some(_)) => false,
+            (None, None) => class_level < self.class.subclass_level(),
+            (None, Some(subclass)) => {
+                subclass.class() == self.class && class_level >= self.class.subclass_level()
+            }
+        };
+        if !subclass_is_valid {
+            return Err(Error::LevelUpInvalidSubclass {
+                class: self.class,
+                subclass: self.subclass,
+            });
+        }
+
+        let expected_improvements = if self.class.grants_ability_score_improvement(class_level) {
+            2
+        } else {
+            0
+        };
+        if self.ability_score_improvements.len() != expected_improvements {
+            return Err(Error::LevelUpInvalidAbilityScoreImprovement {
+                expected_count: expected_improvements,
+                provided_count: self.ability_score_improvements.len(),
+            });
+        }
+
+        let mut ability_scores = character.base_abilities();
+        let mut abilities = character.stats.abilities.clone();
+        // the cap applies to the character's own scores, not to what items or
+        // conditions make of them
+        for ability in &self.ability_score_improvements {
+            *abilities.score_mut(*ability) += 1;
+            let score = ability_scores.score_mut(*ability);
+            *score += 1;
+            if *score > MAXIMUM_ABILITY_SCORE {
+                return Err(Error::AbilityScoreExceedsMaximum {
+                    ability: *ability,
+                    score: *score,
+                });
+            }
+        }
+
+        // the first character level always grants the maximum hit points
+        let hit_die = self.class.hit_die();
+        let constitution_modifier = abilities.modifier(AbilityType::Constitution);
+        let hit_points = if current_level == 0 {
+            i32::max(hit_die.sides() + constitution_modifier, 1)
+        } else {
+            i32::max(
+                self.hit_points.hit_points(hit_die)? + constitution_modifier,
+                1,
+            )
+        };
+
+        let hit_points =
+            if character.has_racial_trait(|t| matches!(t, RacialTrait::DwarvenToughness)) {
+                hit_points + 1
+            } else {
+                hit_points
+            };
+
+        if current_level == 0 {
+            character.maximum_hit_points = hit_points;
+            character.current_hit_points = hit_points;
+        } else {
+            character.maximum_hit_points += hit_points;
+            character.current_hit_points += hit_points;
+        }
+
+        character.ability_scores = Some(ability_scores);
+        character.stats.abilities = abilities;
+        match character
+            .classes
+            .iter_mut()
+            .find(|class| class.class == self.class)
+        {
+            Some(class) => {
+                class.level = class_level;
+                class.subclass = class.subclass.or(self.subclass);
+            }
+            None => character.classes.push(ClassLevel {
+                class: self.class,
+                level: class_level,
+                subclass: self.subclass,
+            }),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::CampaignId;
+
+    #[test]
+    fn experience_thresholds_match_levels() {
+        assert_eq!(level_for_experience(0), 1);
+        assert_eq!(level_for_experience(299), 1);
+        assert_eq!(level_for_experience(300), 2);
+        assert_eq!(level_for_experience(6500), 5);
+        assert_eq!(level_for_experience(1_000_000), 20);
+        assert_eq!(experience_for_level(5), 6500);
+    }
+
+    #[test]
+    fn hit_point_rolls_must_fit_the_hit_die() {
+        assert_eq!(HitPointChoice::Average.hit_points(Dice::D10), Ok(6));
+        assert_eq!(
+            HitPointChoice::Roll { result: 8 }.hit_points(Dice::D8),
+            Ok(8)
+        );
+        assert!(HitPointChoice::Roll { result: 9 }
+            .hit_points(Dice::D8)
+            .is_err());
+    }
+
+    #[test]
+    fn ability_score_improvements_are_capped_on_the_base_scores() {
+        let level_up = || LevelUp {
+            class: Class::Fighter,
+            subclass: None,
+            hit_points: HitPointChoice::Average,
+            ability_score_improvements: vec![AbilityType::Strength, AbilityType::Strength],
+        };
+        let mut fighter = crate::character::test::character(CampaignId::new());
+        fighter.experience = experience_for_level(4);
+        fighter.classes = vec![ClassLevel {
+            class: Class::Fighter,
+            level: 3,
+            subclass: Some(Subclass::Champion),
+        }];
+        fighter.ability_scores.as_mut().unwrap().strength = 18;
+        // a belt of giant strength sets it far above what the character has
+        fighter.stats.abilities.strength = 21;
+
+        level_up().apply(&mut fighter).unwrap();
+        assert_eq!(fighter.ability_scores.as_ref().unwrap().strength, 20);
+
+        fighter.experience = experience_for_level(8);
+        fighter.classes[0].level = 7;
+        assert!(matches!(
+            level_up().apply(&mut fighter),
+            Err(Error::AbilityScoreExceedsMaximum {
+                ability: AbilityType::Strength,
+                score: 21,
+            })
+        ));
+    }
+}
@@ -3,20 +3,100 @@ use futures::{future, stream, StreamExt, TryStreamExt};
 
 use crate::campaign::Campaign;
 use crate::character::race::Race;
-use crate::character::{Proficiencies, RollModifier};
+use crate::character::{CharacterUpdate, Proficiencies, RollCircumstances, RollModifier};
+use crate::currency::Purse;
 use crate::database::Database;
+use crate::encounter::{self, Encounter, Faction};
 use crate::error::Error;
 use crate::item::{self};
 use crate::operation::{AbilityType, RollType};
 
-use super::{Character, CharacterId, CharacterOwner};
+use super::class::{FeatureUse, LimitedFeature};
+use super::creation::{AbilityScoreGeneration, CharacterCreation};
+use super::level::{HitPointChoice, LevelUp};
+use super::race::RacialTrait;
+use super::{Character, CharacterId, CharacterOwner, Condition, EquipmentEntry, SpellSlot};
 
 #[tracing::instrument(skip(db))]
 pub async fn create_character(
     db: &dyn Database,
     campaign: &Campaign,
-    name: String,
+    creation: CharacterCreation,
 ) -> Result<Character, Error> {
+    let CharacterCreation {
+        name,
+        race,
+        subrace,
+        draconic_ancestry,
+        class,
+        subclass,
+        background,
+        abilities,
+        proficiencies,
+        mut equipment,
+    } = creation;
+
+    let subrace_is_valid = match subrace {
+        Some(subrace) => subrace.race() == race,
+        None => race.subraces().is_empty(),
+    };
+    if !subrace_is_valid {
+        return Err(Error::InvalidSubrace { race, subrace });
+    }
+
+    if (race == Race::Dragonborn) != draconic_ancestry.is_some() {
+        return Err(Error::InvalidDraconicAncestry {
+            race,
+            draconic_ancestry,
+        });
+    }
+
+    if let (None, Some(subclass)) = (class, subclass) {
+        return Err(Error::LevelUpInvalidSubclass {
+            class: subclass.class(),
+            subclass: Some(subclass),
+        });
+    }
+
+    let (ability_scores, ability_score_generation) = match abilities {
+        Some(choice) => choice.generate(&mut rand::thread_rng())?,
+        None => (Default::default(), AbilityScoreGeneration::Unspecified),
+    };
+
+    let mut racial_traits = race.default_traits(subrace);
+    if let Some(dragon) = draconic_ancestry {
+        racial_traits.push(RacialTrait::DraconicAncestry(dragon));
+    }
+    proficiencies.apply_to_racial_traits(&mut racial_traits)?;
+    proficiencies.validate_class_skills(class)?;
+
+    // starting equipment is carried loose and can be packed away afterwards
+    for entry in &mut equipment {
+        if entry.quantity < 1 {
+            return Err(Error::InvalidStartingEquipmentQuantity {
+                item_id: entry.item_id,
+                quantity: entry.quantity,
+            });
+        }
+        entry.container_id = None;
+        item::manager::get_item_by_id(db, Some(campaign.id), entry.item_id)
+            .await?
+            .ok_or(Error::ItemDoesNotExist {
+                item_id: entry.item_id,
+            })?;
+    }
+
+    let mut skills = proficiencies.class_skills;
+    let mut tools = vec![];
+    if let Some(background) = background {
+        for skill in background.skill_proficiencies().iter() {
+            if !skills.contains(skill) {
+                skills.push(*skill);
+            }
+        }
+        tools.extend_from_slice(background.tool_proficiencies());
+    }
+
     let now = Utc::now();
     let mut character = Character {
         id: CharacterId::new(),
@@ -24,22 +104,53 @@ pub async fn create_character(
         name,
         created_at: now,
         modified_at: now,
+        ability_scores: Some(ability_scores),
+        ability_score_generation,
         stats: Default::default(),
-        equipment: vec![],
+        equipment,
+        purse: Purse::default(),
         position: None,
         current_hit_points: 10,
         maximum_hit_points: 10,
-        race: Race::Human,
+        experience: 0,
+        classes: vec![],
+        feature_uses: vec![],
+        attuned_items: vec![],
+        item_charges: vec![],
+        expended_hit_dice: vec![],
+        spell_slots: vec![],
+        race: Some(race),
+        subrace,
+        background,
         proficiencies: Proficiencies {
-            armor: vec![],
-            tool: vec![],
+            armor: class
+                .map(|class| class.armor_proficiencies().to_vec())
+                .unwrap_or_default(),
+            tool: tools,
             saving_throws: vec![],
-            skills: vec![],
+            skills,
         },
-        racial_traits: vec![],
+        racial_traits,
+        conditions: vec![],
+        exhaustion: 0,
+        monster: None,
+        ai_controlled: false,
     };
     character.recalculate_stats(db).await?;
 
+    // the first class level sets the starting hit points from the constitution
+    // modifier that includes racial increases
+    if let Some(class) = class {
+        let level_up = LevelUp {
+            class,
+            subclass,
+            hit_points: HitPointChoice::Average,
+            ability_score_improvements: vec![],
+        };
+        level_up.apply(&mut character)?;
+        character.recalculate_stats(db).await?;
+    }
+
     db.characters().insert_character(&character).await?;
 
     Ok(character)
@@ -90,24 +201,169 @@ pub async fn expect_character_by_id(
     Ok(character)
 }
 
+// the experience is split evenly among the characters, discarding any remainder
+#[tracing::instrument(skip(db))]
+pub async fn award_experience(
+    db: &dyn Database,
+    campaign: &Campaign,
+    character_ids: Vec<CharacterId>,
+    experience: i32,
+) -> Result<Vec<Character>, Error> {
+    if character_ids.is_empty() {
+        return Err(Error::ExperienceHasNoRecipients);
+    }
+
+    let mut characters = vec![];
+    for character_id in &character_ids {
+        let character = get_character_by_id(db, campaign, *character_id)
+            .await?
+            .ok_or(Error::CharacterNotInCampaign {
+                campaign_id: campaign.id,
+                character_id: *character_id,
+            })?;
+
+        characters.push(character);
+    }
+
+    let share = experience / character_ids.len() as i32;
+
+    let mut updated_characters = vec![];
+    for character in characters {
+        let experience = character.experience + share;
+        let character = db
+            .characters()
+            .update_character_experience(character, experience)
+            .await?;
+
+        updated_characters.push(character);
+    }
+
+    Ok(updated_characters)
+}
+
+// the monsters and anyone else fighting the party don't share in the
+// experience for an encounter
+#[tracing::instrument(skip(db))]
+pub async fn award_encounter_experience(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: &Encounter,
+    experience: i32,
+) -> Result<Vec<Character>, Error> {
+    let character_ids = encounter::manager::get_encounter_characters(db, encounter)
+        .await?
+        .into_iter()
+        .filter(|character| character.monster.is_none())
+        .filter(|character| encounter.faction(character) != Faction::Enemies)
+        .map(|character| character.id)
+        .collect();
+
+    award_experience(db, campaign, character_ids, experience).await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn level_up_character(
+    db: &dyn Database,
+    campaign: &Campaign,
+    mut character: Character,
+    level_up: LevelUp,
+) -> Result<Character, Error> {
+    level_up.apply(&mut character)?;
+    character.recalculate_stats(db).await?;
+
+    db.characters().update_character(character).await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn update_character_details(
+    db: &dyn Database,
+    mut character: Character,
+    update: CharacterUpdate,
+) -> Result<Character, Error> {
+    if let Some(name) = update.name {
+        character.name = name;
+    }
+
+    if let Some(ability_scores) = update.ability_scores {
+        for &ability in AbilityType::ALL {
+            let score = ability_scores.score(ability);
+            if !(1..=30).contains(&score) {
+                return Err(Error::AbilityScoreOutOfRange { ability, score });
+            }
+        }
+        character.ability_scores = Some(ability_scores);
+    }
+
+    if let Some(proficiencies) = update.proficiencies {
+        character.proficiencies = proficiencies;
+    }
+
+    if let Some(ai_controlled) = update.ai_controlled {
+        if !matches!(character.owner, CharacterOwner::Campaign(_)) {
+            return Err(Error::CharacterNotOwnedByCampaign {
+                character_id: character.id,
+            });
+        }
+        character.ai_controlled = ai_controlled;
+    }
+
+    let maximum_hit_points = update
+        .maximum_hit_points
+        .unwrap_or(character.maximum_hit_points);
+    let current_hit_points = update
+        .current_hit_points
+        .unwrap_or_else(|| i32::min(character.current_hit_points, maximum_hit_points));
+    if maximum_hit_points < 1 || !(0..=maximum_hit_points).contains(&current_hit_points) {
+        return Err(Error::InvalidHitPoints {
+            current_hit_points,
+            maximum_hit_points,
+        });
+    }
+    character.maximum_hit_points = maximum_hit_points;
+    character.current_hit_points = current_hit_points;
+
+    character.recalculate_stats(db).await?;
+
+    db.characters().update_character_details(character).await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn delete_character(
+    db: &dyn Database,
+    campaign: &Campaign,
+    character: Character,
+) -> Result<(), Error> {
+    let current_encounter = encounter::manager::get_current_encounter(db, campaign).await?;
+    if let Some(encounter) = current_encounter {
+        if encounter.character_ids.contains(&character.id) {
+            return Err(Error::CharacterInCurrentEncounter {
+                character_id: character.id,
+            });
+        }
+    }
+
+    db.characters().delete_character(character).await
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn get_character_roll_stats(
     db: &dyn Database,
     campaign: &Campaign,
     character: &Character,
     roll_type: RollType,
+    circumstances: &RollCircumstances,
 ) -> Result<RollModifier, Error> {
-    let mut modifier = RollModifier::Normal;
+    let mut has_advantage = false;
+    let mut has_disadvantage = false;
     match roll_type {
-        RollType::SkillCheck(skill) => {
-            if character.proficiencies.skills.contains(&skill) {
-                modifier = RollModifier::Advantage;
-            }
+        RollType::SkillCheck(skill) if character.proficiencies.skills.contains(&skill) => {
+            has_advantage = true;
         }
-        RollType::Save(ability) => {
-            if character.proficiencies.saving_throws.contains(&ability) {
-                modifier = RollModifier::Advantage;
-            }
+        RollType::Save(ability) if character.proficiencies.saving_throws.contains(&ability) => {
+            has_advantage = true;
+        }
+        RollType::Save(ability) if character.stats.saving_throw_advantages.contains(&ability) => {
+            has_advantage = true;
         }
         _ => {}
     }
@@ -119,6 +375,37 @@ pub async fn get_character_roll_stats(
         _ => None,
     };
 
+    if ability == Some(AbilityType::Strength) && character.conditions.contains(&Condition::Raging) {
+        has_advantage = true;
+    }
+
+    if let RollType::Save(ability) = roll_type {
+        has_advantage |= character.racial_traits.iter().any(|t| match t {
+            RacialTrait::Brave => circumstances.frightened,
+            RacialTrait::FeyAncestry => circumstances.charmed,
+            RacialTrait::DwarvenResiliance => circumstances.poisoned,
+            RacialTrait::GnomeCunning => {
+                circumstances.magic
+                    && matches!(
+                        ability,
+                        AbilityType::Intelligence | AbilityType::Wisdom | AbilityType::Charisma
+                    )
+            }
+            _ => false,
+        });
+    }
+
+    if character.stats.encumbrance.imposes_disadvantage()
+        && (matches!(
+            ability,
+            Some(AbilityType::Strength)
+                | Some(AbilityType::Dexterity)
+                | Some(AbilityType::Constitution)
+        ) || matches!(roll_type, RollType::Hit))
+    {
+        has_disadvantage = true;
+    }
+
     if matches!(
         ability,
         Some(AbilityType::Strength) | Some(AbilityType::Dexterity)
@@ -133,23 +420,365 @@ pub async fn get_character_roll_stats(
         for item in items {
             if let item::ItemType::Armor(armor) = item.item_type {
                 if !character.proficiencies.armor.contains(&armor.armor_type) {
-                    // TODO: cancel out advantage
-                    modifier = RollModifier::Disadvantage;
+                    has_disadvantage = true;
                 }
             }
         }
     }
 
+    // any advantage and any disadvantage cancel out, however many of each
+    let modifier = match (has_advantage, has_disadvantage) {
+        (true, false) => RollModifier::Advantage,
+        (false, true) => RollModifier::Disadvantage,
+        _ => RollModifier::Normal,
+    };
+
     Ok(modifier)
 }
 
 #[tracing::instrument(skip(db))]
-pub async fn update_character_hit_points(
+pub async fn update_character_equipment(
+    db: &dyn Database,
+    character: Character,
+    equipment: Vec<EquipmentEntry>,
+) -> Result<Character, Error> {
+    db.characters()
+        .update_character_equipment(character, equipment)
+        .await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn update_character_spell_slots(
     db: &dyn Database,
     character: Character,
+    spell_slots: Vec<SpellSlot>,
+) -> Result<Character, Error> {
+    db.characters()
+        .update_character_spell_slots(character, spell_slots)
+        .await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn update_character_hit_points(
+    db: &dyn Database,
+    mut character: Character,
     hit_points: i32,
 ) -> Result<Character, Error> {
+    // a half-orc can drop to 1 hit point instead once per long rest
+    if hit_points == 0
+        && character.current_hit_points > 0
+        && character.has_racial_trait(|t| matches!(t, RacialTrait::RelentlessEndurance))
+        && character.feature_uses_expended(LimitedFeature::RelentlessEndurance) == 0
+    {
+        character.current_hit_points = 1;
+        character
+            .feature_uses
+            .retain(|usage| usage.feature != LimitedFeature::RelentlessEndurance);
+        character.feature_uses.push(FeatureUse {
+            feature: LimitedFeature::RelentlessEndurance,
+            expended: 1,
+        });
+
+        return db.characters().update_character(character).await;
+    }
+
     db.characters()
         .update_character_hit_points(character, hit_points)
         .await
 }
+
+#[tracing::instrument(skip(db))]
+pub async fn update_character_feature_uses(
+    db: &dyn Database,
+    character: Character,
+    feature_uses: Vec<FeatureUse>,
+) -> Result<Character, Error> {
+    db.characters()
+        .update_character_feature_uses(character, feature_uses)
+        .await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn update_character_conditions(
+    db: &dyn Database,
+    character: Character,
+    mut conditions: Vec<Condition>,
+) -> Result<Character, Error> {
+    conditions.retain(|condition| !character.is_immune_to_condition(*condition));
+
+    db.characters()
+        .update_character_conditions(character, conditions)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::test::campaign;
+    use crate::character::test::character;
+    use crate::database::test::MockDatabase;
+    use crate::encounter::test::encounter;
+    use crate::monster::test::goblin_instance;
+    use crate::user::UserId;
+    use std::sync::{Arc, Mutex};
+
+    #[actix_rt::test]
+    async fn encounter_experience_is_shared_by_the_party_only() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let wizard = character(campaign.id);
+        let goblin = goblin_instance(campaign.id);
+        let mut encounter = encounter(campaign.id, vec![fighter.id, wizard.id, goblin.id]);
+        encounter.factions[2].faction = Faction::Enemies;
+
+        let characters = vec![fighter.clone(), wizard.clone(), goblin.clone()];
+        db.characters.on_fetch_characters_by_campaign = {
+            let characters = characters.clone();
+            Box::new(move |_| Ok(characters.clone()))
+        };
+        db.characters.on_fetch_character_by_campaign_and_id = Box::new(move |_, character_id| {
+            Ok(characters
+                .iter()
+                .find(|character| character.id == character_id)
+                .cloned())
+        });
+        let awarded = Arc::new(Mutex::new(vec![]));
+        let awarded_clone = Arc::clone(&awarded);
+        db.characters.on_update_character_experience =
+            Box::new(move |mut character, experience| {
+                awarded_clone
+                    .lock()
+                    .unwrap()
+                    .push((character.id, experience));
+                character.experience = experience;
+                Ok(character)
+            });
+
+        let characters = award_encounter_experience(&db, &campaign, &encounter, 300)
+            .await
+            .unwrap();
+
+        assert_eq!(characters.len(), 2);
+        assert_eq!(
+            *awarded.lock().unwrap(),
+            vec![(fighter.id, 150), (wizard.id, 150)]
+        );
+    }
+
+    fn chain_mail() -> item::Item {
+        item::Item {
+            id: item::ItemId::new(),
+            campaign_id: None,
+            name: "Chain Mail".to_string(),
+            weight: 55,
+            value: 7500,
+            item_type: item::ItemType::Armor(item::Armor {
+                base_armor_class: 16,
+                armor_type: item::ArmorType::Heavy,
+                strength_requirement: Some(13),
+                stealth_disadvantage: true,
+            }),
+            magic: None,
+        }
+    }
+
+    fn wearing(character: &mut Character, item: &item::Item) {
+        character.equipment = vec![EquipmentEntry {
+            equiped: true,
+            quantity: 1,
+            item_id: item.id,
+            container_id: None,
+        }];
+    }
+
+    #[actix_rt::test]
+    async fn advantage_and_disadvantage_cancel_out() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let armor = chain_mail();
+        let armor_clone = armor.clone();
+        db.items.on_fetch_item_by_id = Box::new(move |_| Ok(Some(armor_clone.clone())));
+        let roll_type = RollType::AbilityCheck(AbilityType::Strength);
+        let circumstances = RollCircumstances::default();
+
+        let mut raging = character(campaign.id);
+        raging.conditions = vec![Condition::Raging];
+        let modifier = get_character_roll_stats(&db, &campaign, &raging, roll_type, &circumstances)
+            .await
+            .unwrap();
+        assert!(matches!(modifier, RollModifier::Advantage));
+
+        let mut untrained = character(campaign.id);
+        wearing(&mut untrained, &armor);
+        let modifier =
+            get_character_roll_stats(&db, &campaign, &untrained, roll_type, &circumstances)
+                .await
+                .unwrap();
+        assert!(matches!(modifier, RollModifier::Disadvantage));
+
+        wearing(&mut raging, &armor);
+        let modifier = get_character_roll_stats(&db, &campaign, &raging, roll_type, &circumstances)
+            .await
+            .unwrap();
+        assert!(
+            matches!(modifier, RollModifier::Normal),
+            "rage and untrained armor should cancel out, got {:?}",
+            modifier
+        );
+    }
+
+    fn update() -> CharacterUpdate {
+        CharacterUpdate {
+            name: None,
+            ability_scores: None,
+            proficiencies: None,
+            current_hit_points: None,
+            maximum_hit_points: None,
+            ai_controlled: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn updates_are_validated_before_being_saved() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        db.campaigns.on_fetch_campaign_by_id = Box::new(|_| Ok(None));
+        let updated = Arc::new(Mutex::new(None));
+        let updated_clone = Arc::clone(&updated);
+        db.characters.on_update_character_details = Box::new(move |character| {
+            *updated_clone.lock().unwrap() = Some(character.clone());
+            Ok(character)
+        });
+
+        let mut ability_scores = character(campaign.id).base_abilities();
+        ability_scores.strength = 31;
+        let result = update_character_details(
+            &db,
+            character(campaign.id),
+            CharacterUpdate {
+                ability_scores: Some(ability_scores),
+                ..update()
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::AbilityScoreOutOfRange { score: 31, .. })
+        ));
+
+        let result = update_character_details(
+            &db,
+            character(campaign.id),
+            CharacterUpdate {
+                current_hit_points: Some(11),
+                ..update()
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(Error::InvalidHitPoints { .. })));
+
+        let mut player = character(campaign.id);
+        player.owner = CharacterOwner::User(UserId::new());
+        let result = update_character_details(
+            &db,
+            player,
+            CharacterUpdate {
+                ai_controlled: Some(true),
+                ..update()
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::CharacterNotOwnedByCampaign { .. })
+        ));
+        assert!(
+            updated.lock().unwrap().is_none(),
+            "db.update_character_details was called"
+        );
+
+        let character = update_character_details(
+            &db,
+            character(campaign.id),
+            CharacterUpdate {
+                name: Some("Renamed".to_string()),
+                maximum_hit_points: Some(8),
+                ..update()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(character.name, "Renamed");
+        assert_eq!(character.maximum_hit_points, 8);
+        assert_eq!(character.current_hit_points, 8);
+        assert!(
+            updated.lock().unwrap().is_some(),
+            "db.update_character_details was not called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn characters_in_the_current_encounter_are_not_deleted() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let current = encounter(campaign.id, vec![fighter.id]);
+        db.encounters.on_fetch_current_encounter_by_campaign =
+            Box::new(move |_| Ok(Some(current.clone())));
+        let called_delete = Arc::new(Mutex::new(false));
+        let called_delete_clone = Arc::clone(&called_delete);
+        db.characters.on_delete_character = Box::new(move |_| {
+            *called_delete_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let result = delete_character(&db, &campaign, fighter).await;
+        assert!(matches!(
+            result,
+            Err(Error::CharacterInCurrentEncounter { .. })
+        ));
+        assert!(
+            !*called_delete.lock().unwrap(),
+            "db.delete_character was called"
+        );
+
+        let bystander = character(campaign.id);
+        delete_character(&db, &campaign, bystander).await.unwrap();
+        assert!(
+            *called_delete.lock().unwrap(),
+            "db.delete_character was not called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn starting_equipment_needs_a_quantity() {
+        let db = MockDatabase::new();
+        let campaign = campaign();
+
+        let item_id = item::ItemId::new();
+        let creation = CharacterCreation {
+            name: "Empty Handed".to_string(),
+            race: Race::Human,
+            subrace: None,
+            draconic_ancestry: None,
+            class: None,
+            subclass: None,
+            background: None,
+            abilities: None,
+            proficiencies: Default::default(),
+            equipment: vec![EquipmentEntry {
+                equiped: false,
+                quantity: 0,
+                item_id,
+                container_id: None,
+            }],
+        };
+        let result = create_character(&db, &campaign, creation).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidStartingEquipmentQuantity { quantity: 0, .. })
+        ));
+    }
+}
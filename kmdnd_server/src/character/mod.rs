@@ -2,23 +2,35 @@ use std::future;
 
 use chrono::{DateTime, Utc};
 use futures::{stream, StreamExt, TryStreamExt};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::CampaignId;
+use crate::currency::Purse;
 use crate::database::Database;
 use crate::error::Error;
-use crate::item::{self, ArmorType, ItemId};
+use crate::item::magic::{MagicEffect, MagicProperties};
+use crate::item::{self, ArmorType, DamageType, Dice, DiceRoll, Item, ItemId};
+use crate::monster::{MonsterInstance, StatBlock};
+use crate::operation::inventory;
 use crate::operation::{AbilityType, SkillType};
 use crate::typedid::{TypedId, TypedIdMarker};
 use crate::user::UserId;
 
+pub mod class;
+pub mod creation;
 pub mod db;
+pub mod encumbrance;
 pub mod endpoints;
+pub mod level;
 pub mod manager;
 pub mod race;
 pub use endpoints::*;
 
-use self::race::{Race, RacialTrait};
+use self::class::{ClassFeature, ClassLevel, FeatureUse, LimitedFeature};
+use self::creation::{AbilityScoreGeneration, Background};
+use self::encumbrance::Encumbrance;
+use self::race::{DragonType, Race, RacialTrait, Size, Subrace};
 
 pub type CharacterId = TypedId<Character>;
 
@@ -32,37 +44,510 @@ pub struct Character {
     pub created_at: DateTime<Utc>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub modified_at: DateTime<Utc>,
+    // characters stored before this was tracked only have their stats, which
+    // were their base scores at the time
+    #[serde(default)]
+    pub ability_scores: Option<CharacterAbilities>,
+    #[serde(default)]
+    pub ability_score_generation: AbilityScoreGeneration,
     pub stats: CharacterStats,
     pub equipment: Vec<EquipmentEntry>,
+    #[serde(default)]
+    pub purse: Purse,
     pub position: Option<Position>,
     pub current_hit_points: i32,
     pub maximum_hit_points: i32,
-    pub race: Race,
+    #[serde(default)]
+    pub experience: i32,
+    #[serde(default)]
+    pub classes: Vec<ClassLevel>,
+    #[serde(default)]
+    pub feature_uses: Vec<FeatureUse>,
+    #[serde(default)]
+    pub attuned_items: Vec<ItemId>,
+    #[serde(default)]
+    pub item_charges: Vec<ItemChargeUse>,
+    #[serde(default)]
+    pub expended_hit_dice: Vec<HitDiceUse>,
+    #[serde(default)]
+    pub spell_slots: Vec<SpellSlot>,
+    // monsters have a creature type from their stat block instead
+    #[serde(default)]
+    pub race: Option<Race>,
+    #[serde(default)]
+    pub subrace: Option<Subrace>,
+    #[serde(default)]
+    pub background: Option<Background>,
     pub racial_traits: Vec<RacialTrait>,
     pub proficiencies: Proficiencies,
-    // conditions: Vec<Condition>,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub exhaustion: i32,
+    #[serde(default)]
+    pub monster: Option<MonsterInstance>,
+    // the server takes this character's turns in encounters
+    #[serde(default)]
+    pub ai_controlled: bool,
 }
 
 impl Character {
-    pub async fn recalculate_stats(&mut self, db: &dyn Database) -> Result<(), Error> {
+    pub fn stat_block(&self) -> Option<&StatBlock> {
+        self.monster.as_ref().map(|monster| &monster.stat_block)
+    }
+
+    // characters stored before base scores were kept only have their stats,
+    // which already include the racial increases
+    pub fn base_abilities(&self) -> CharacterAbilities {
+        match &self.ability_scores {
+            Some(ability_scores) => ability_scores.clone(),
+            None => {
+                let mut abilities = self.stats.abilities.clone();
+                for ability in self
+                    .racial_traits
+                    .iter()
+                    .flat_map(|t| t.ability_score_increases())
+                {
+                    *abilities.score_mut(*ability) -= 1;
+                }
+                abilities
+            }
+        }
+    }
+
+    pub fn level(&self) -> i32 {
+        self.classes.iter().map(|class| class.level).sum()
+    }
+
+    pub fn features(&self) -> Vec<ClassFeature> {
+        self.classes
+            .iter()
+            .flat_map(|class| class.features())
+            .collect()
+    }
+
+    // extra attacks from multiple classes do not stack
+    pub fn attacks_per_action(&self) -> i32 {
+        if let Some(multiattack) = self.stat_block().and_then(|s| s.multiattack.as_ref()) {
+            return multiattack.attacks;
+        }
+
+        let extra_attacks = self
+            .features()
+            .into_iter()
+            .filter_map(|feature| match feature {
+                ClassFeature::ExtraAttack { attacks } => Some(attacks),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        1 + extra_attacks
+    }
+
+    pub fn sneak_attack_dice(&self) -> Option<i32> {
+        self.features()
+            .into_iter()
+            .find_map(|feature| match feature {
+                ClassFeature::SneakAttack { dice } => Some(dice),
+                _ => None,
+            })
+    }
+
+    pub fn rage_damage(&self) -> Option<i32> {
+        self.features()
+            .into_iter()
+            .find_map(|feature| match feature {
+                ClassFeature::Rage { damage, .. } => Some(damage),
+                _ => None,
+            })
+    }
+
+    pub fn feature_uses_total(&self, feature: LimitedFeature) -> Option<i32> {
+        self.features()
+            .into_iter()
+            .filter_map(|f| f.limited_uses())
+            .find(|(f, _)| *f == feature)
+            .map(|(_, uses)| uses)
+    }
+
+    pub fn feature_uses_expended(&self, feature: LimitedFeature) -> i32 {
+        self.feature_uses
+            .iter()
+            .find(|usage| usage.feature == feature)
+            .map(|usage| usage.expended)
+            .unwrap_or(0)
+    }
+
+    pub fn hit_dice(&self) -> Vec<DiceRoll> {
+        let mut hit_dice: Vec<DiceRoll> = vec![];
+        for class in &self.classes {
+            let dice = class.class.hit_die();
+            match hit_dice.iter_mut().find(|roll| roll.dice == dice) {
+                Some(roll) => roll.amount += class.level,
+                None => hit_dice.push(DiceRoll::new(class.level, dice)),
+            }
+        }
+
+        hit_dice
+    }
+
+    pub fn hit_dice_remaining(&self, dice: Dice) -> i32 {
+        let total = self
+            .hit_dice()
+            .into_iter()
+            .find(|roll| roll.dice == dice)
+            .map(|roll| roll.amount)
+            .unwrap_or(0);
+        let expended = self
+            .expended_hit_dice
+            .iter()
+            .find(|usage| usage.dice == dice)
+            .map(|usage| usage.expended)
+            .unwrap_or(0);
+
+        total - expended
+    }
+
+    pub fn size(&self) -> Size {
+        match (self.stat_block(), self.race) {
+            (Some(stat_block), _) => stat_block.size,
+            (None, Some(race)) => race.size(),
+            (None, None) => Size::Medium,
+        }
+    }
+
+    pub fn draconic_ancestry(&self) -> Option<DragonType> {
+        self.racial_traits.iter().find_map(|t| match t {
+            RacialTrait::DraconicAncestry(dragon) => Some(*dragon),
+            _ => None,
+        })
+    }
+
+    pub fn damage_resistances(&self) -> Vec<DamageType> {
+        let mut resistances: Vec<DamageType> = self
+            .racial_traits
+            .iter()
+            .flat_map(|t| t.damage_resistances())
+            .collect();
+
+        if self.has_racial_trait(|t| matches!(t, RacialTrait::DamageResistance)) {
+            resistances.extend(self.draconic_ancestry().map(|dragon| dragon.damage_type()));
+        }
+
+        resistances.extend(self.stats.damage_resistances.iter().copied());
+        if let Some(stat_block) = self.stat_block() {
+            resistances.extend(stat_block.damage_resistances.iter().copied());
+        }
+
+        if self.conditions.contains(&Condition::Raging) {
+            resistances.extend([
+                DamageType::Bludgeoning,
+                DamageType::Piercing,
+                DamageType::Slashing,
+            ]);
+        }
+
+        resistances
+    }
+
+    // the damage actually taken after immunities, resistances and
+    // vulnerabilities are considered
+    pub fn resisted_damage(&self, damage: i32, damage_type: DamageType) -> i32 {
+        let (immune, vulnerable) = match self.stat_block() {
+            Some(stat_block) => (
+                stat_block.damage_immunities.contains(&damage_type),
+                stat_block.damage_vulnerabilities.contains(&damage_type),
+            ),
+            None => (false, false),
+        };
+
+        if immune {
+            0
+        } else if self.damage_resistances().contains(&damage_type) {
+            damage / 2
+        } else if vulnerable {
+            damage * 2
+        } else {
+            damage
+        }
+    }
+
+    pub fn is_immune_to_condition(&self, condition: Condition) -> bool {
+        self.stat_block()
+            .is_some_and(|stat_block| stat_block.condition_immunities.contains(&condition))
+    }
+
+    // a character at 0 hit points is unconscious even without the condition
+    pub fn is_incapacitated(&self) -> bool {
+        const INCAPACITATING: [Condition; 5] = [
+            Condition::Incapacitated,
+            Condition::Paralyzed,
+            Condition::Petrified,
+            Condition::Stunned,
+            Condition::Unconscious,
+        ];
+
+        self.current_hit_points <= 0
+            || self
+                .conditions
+                .iter()
+                .any(|condition| INCAPACITATING.contains(condition))
+    }
+
+    // weapons with the reach property aren't taken into account
+    pub fn melee_reach(&self) -> f32 {
+        self.stat_block()
+            .and_then(|stat_block| {
+                stat_block
+                    .attacks
+                    .iter()
+                    .filter(|attack| attack.range.is_none())
+                    .map(|attack| attack.reach)
+                    .max_by(f32::total_cmp)
+            })
+            .unwrap_or(5.0)
+    }
+
+    // the magic properties of an item that currently apply to the character,
+    // which needs attunement for some items
+    pub fn active_magic<'a>(&self, item: &'a Item) -> Option<&'a MagicProperties> {
+        item.magic
+            .as_ref()
+            .filter(|magic| !magic.requires_attunement || self.attuned_items.contains(&item.id))
+    }
+
+    pub fn item_charges_remaining(&self, item: &Item) -> i32 {
+        let maximum = item
+            .magic
+            .as_ref()
+            .and_then(|magic| magic.charges.as_ref())
+            .map(|charges| charges.maximum)
+            .unwrap_or(0);
+        let expended = self
+            .item_charges
+            .iter()
+            .find(|usage| usage.item_id == item.id)
+            .map(|usage| usage.expended)
+            .unwrap_or(0);
+
+        maximum - expended
+    }
+
+    pub fn has_racial_trait(&self, predicate: impl Fn(&RacialTrait) -> bool) -> bool {
+        self.racial_traits.iter().any(predicate)
+    }
+
+    // rolls a d20 on behalf of the character, a halfling's luck lets them
+    // reroll a natural 1
+    pub fn roll_d20<R: Rng>(&self, rng: &mut R) -> i32 {
+        let roll = rng.gen_range(1..=20);
+        if roll == 1 && self.has_racial_trait(|t| matches!(t, RacialTrait::Lucky)) {
+            rng.gen_range(1..=20)
+        } else {
+            roll
+        }
+    }
+
+    pub fn spell_save_difficulty_class(&self) -> i32 {
+        let spellcasting_modifier = self
+            .classes
+            .iter()
+            .filter_map(|class| class.class.spellcasting_ability(class.level))
+            .map(|ability| self.stats.abilities.modifier(ability))
+            .max()
+            .unwrap_or(0);
+
+        8 + self.stats.proficiency_bonus + spellcasting_modifier
+    }
+
+    pub fn has_spell_slot(&self, level: i32) -> bool {
+        self.spell_slots
+            .iter()
+            .any(|slot| slot.level == level && slot.expended < slot.total)
+    }
+
+    pub async fn equiped_items(&self, db: &dyn Database) -> Result<Vec<Item>, Error> {
         let items: Vec<_> = stream::iter(&self.equipment)
             .filter(|entry| future::ready(entry.equiped))
-            .then(|entry| item::manager::get_item_by_id(db, entry.item_id))
+            .then(|entry| {
+                item::manager::get_item_by_id(db, self.owner.campaign_id(), entry.item_id)
+            })
+            .try_filter_map(|item| future::ready(Ok(item)))
+            .try_collect()
+            .await?;
+
+        Ok(items)
+    }
+
+    pub async fn carried_items(&self, db: &dyn Database) -> Result<Vec<Item>, Error> {
+        let items: Vec<_> = stream::iter(&self.equipment)
+            .then(|entry| {
+                item::manager::get_item_by_id(db, self.owner.campaign_id(), entry.item_id)
+            })
+            .try_filter_map(|item| future::ready(Ok(item)))
             .try_collect()
             .await?;
 
-        let armor: Vec<_> = items
+        Ok(items)
+    }
+
+    pub async fn recalculate_stats(&mut self, db: &dyn Database) -> Result<(), Error> {
+        // a monster's stats come straight from its stat block
+        if let Some(stat_block) = self.stat_block() {
+            self.stats = CharacterStats {
+                abilities: stat_block.ability_scores.clone(),
+                initiative: stat_block.ability_scores.modifier(AbilityType::Dexterity),
+                armor_class: stat_block.armor_class,
+                speed: stat_block.speed,
+                proficiency_bonus: stat_block.challenge_rating.proficiency_bonus(),
+                ..CharacterStats::default()
+            };
+
+            return Ok(());
+        }
+
+        // attunement ends once the item is no longer carried
+        let equipment = &self.equipment;
+        self.attuned_items
+            .retain(|item_id| equipment.iter().any(|entry| entry.item_id == *item_id));
+
+        let items = self.equiped_items(db).await?;
+        let effects: Vec<MagicEffect> = items
             .iter()
-            .filter_map(|item| item.as_ref())
-            .filter_map(|item| item.item_type.as_armor())
+            .filter_map(|item| self.active_magic(item))
+            .flat_map(|magic| magic.effects.iter().cloned())
             .collect();
 
+        // the base scores are kept once derived so the bonuses below are never
+        // applied on top of themselves
+        let mut abilities = self.base_abilities();
+        self.ability_scores = Some(abilities.clone());
+        for ability in self
+            .racial_traits
+            .iter()
+            .flat_map(|t| t.ability_score_increases())
+        {
+            *abilities.score_mut(*ability) += 1;
+        }
+        for effect in &effects {
+            if let MagicEffect::AbilityScore { ability, score } = *effect {
+                let current = abilities.score_mut(ability);
+                *current = i32::max(*current, score);
+            }
+        }
+        self.stats.abilities = abilities;
+
+        for racial_trait in &self.racial_traits {
+            for skill in racial_trait.skill_proficiencies() {
+                if !self.proficiencies.skills.contains(&skill) {
+                    self.proficiencies.skills.push(skill);
+                }
+            }
+            for armor in racial_trait.armor_proficiencies() {
+                if !self.proficiencies.armor.contains(&armor) {
+                    self.proficiencies.armor.push(armor);
+                }
+            }
+            for tool in racial_trait.tool_proficiencies() {
+                if !self.proficiencies.tool.contains(&tool) {
+                    self.proficiencies.tool.push(tool);
+                }
+            }
+        }
+
         let mut armor_class = 10;
-        for piece in armor {
-            armor_class += piece.effective_armor_class(self);
+        for item in &items {
+            if let Some(armor) = item.item_type.as_armor() {
+                armor_class += armor.effective_armor_class(self);
+                armor_class += self
+                    .active_magic(item)
+                    .map(|magic| magic.bonus)
+                    .unwrap_or(0);
+            }
+        }
+
+        let mut damage_resistances = vec![];
+        let mut saving_throw_advantages = vec![];
+        let mut saving_throw_bonus = 0;
+        for effect in effects {
+            match effect {
+                MagicEffect::DamageResistance { damage_type } => {
+                    damage_resistances.push(damage_type)
+                }
+                MagicEffect::SaveAdvantage { ability } => saving_throw_advantages.push(ability),
+                MagicEffect::SaveBonus { bonus } => saving_throw_bonus += bonus,
+                MagicEffect::ArmorClassBonus { bonus } => armor_class += bonus,
+                MagicEffect::AbilityScore { .. } => {}
+            }
         }
 
         self.stats.armor_class = armor_class;
+        self.stats.damage_resistances = damage_resistances;
+        self.stats.saving_throw_advantages = saving_throw_advantages;
+        self.stats.saving_throw_bonus = saving_throw_bonus;
+
+        let carried_items = self.carried_items(db).await?;
+        let carried_weight = inventory::stored_weight(&self.equipment, &carried_items, None);
+
+        let variant_encumbrance = match self.owner.campaign_id() {
+            Some(campaign_id) => db
+                .campaigns()
+                .fetch_campaign_by_id(campaign_id)
+                .await?
+                .map(|campaign| campaign.variant_encumbrance)
+                .unwrap_or(false),
+            None => false,
+        };
+
+        let strength = self.stats.abilities.strength;
+        let encumbrance =
+            Encumbrance::from_weight(carried_weight, strength, self.size(), variant_encumbrance);
+
+        self.stats.carried_weight = carried_weight;
+        self.stats.carrying_capacity = encumbrance::carrying_capacity(strength, self.size());
+        self.stats.encumbrance = encumbrance;
+        let speed = self
+            .race
+            .map(|race| race.speed(self.subrace))
+            .unwrap_or(CharacterStats::default().speed);
+        self.stats.speed = encumbrance.apply_to_speed(speed);
+        self.stats.initiative = self.stats.abilities.modifier(AbilityType::Dexterity);
+        self.stats.proficiency_bonus = class::proficiency_bonus(self.level());
+
+        // only the first class grants saving throw proficiencies when
+        // multiclassing
+        if let Some(class) = self.classes.first() {
+            for ability in class.class.saving_throws().iter() {
+                if !self.proficiencies.saving_throws.contains(ability) {
+                    self.proficiencies.saving_throws.push(*ability);
+                }
+            }
+
+            let pact_magic_slots =
+                class::pact_magic_slots(&self.classes).map(|(level, total)| (level, total, true));
+            self.spell_slots = class::spell_slot_totals(&self.classes)
+                .into_iter()
+                .zip(1..)
+                .map(|(total, level)| (level, total, false))
+                .chain(pact_magic_slots)
+                .map(|(level, total, pact_magic)| {
+                    let expended = self
+                        .spell_slots
+                        .iter()
+                        .find(|slot| slot.level == level && slot.pact_magic == pact_magic)
+                        .map(|slot| i32::min(slot.expended, total))
+                        .unwrap_or(0);
+
+                    SpellSlot {
+                        level,
+                        total,
+                        expended,
+                        pact_magic,
+                    }
+                })
+                .collect();
+        }
 
         Ok(())
     }
@@ -82,6 +567,16 @@ pub enum CharacterOwner {
     UserInCampaign(UserId, CampaignId),
 }
 
+impl CharacterOwner {
+    pub fn campaign_id(&self) -> Option<CampaignId> {
+        match *self {
+            CharacterOwner::Campaign(campaign_id) => Some(campaign_id),
+            CharacterOwner::User(_) => None,
+            CharacterOwner::UserInCampaign(_, campaign_id) => Some(campaign_id),
+        }
+    }
+}
+
 impl Serialize for CharacterOwner {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -138,13 +633,23 @@ impl<'de> Deserialize<'de> for CharacterOwner {
     }
 }
 
+// stats are recalculated, so anything missing from a stored character takes
+// its default until then
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct CharacterStats {
     pub abilities: CharacterAbilities,
     pub initiative: i32,
     pub speed: i32,
     pub armor_class: i32,
     pub proficiency_bonus: i32,
+    pub carried_weight: i32,
+    pub carrying_capacity: i32,
+    pub encumbrance: Encumbrance,
+    // granted by magic items
+    pub damage_resistances: Vec<DamageType>,
+    pub saving_throw_advantages: Vec<AbilityType>,
+    pub saving_throw_bonus: i32,
 }
 
 impl Default for CharacterStats {
@@ -155,6 +660,12 @@ impl Default for CharacterStats {
             speed: 30,
             armor_class: 10,
             proficiency_bonus: 1,
+            carried_weight: 0,
+            carrying_capacity: 150,
+            encumbrance: Encumbrance::Unencumbered,
+            damage_resistances: vec![],
+            saving_throw_advantages: vec![],
+            saving_throw_bonus: 0,
         }
     }
 }
@@ -170,8 +681,34 @@ pub struct CharacterAbilities {
 }
 
 impl CharacterAbilities {
+    pub fn score(&self, ability: AbilityType) -> i32 {
+        match ability {
+            AbilityType::Strength => self.strength,
+            AbilityType::Dexterity => self.dexterity,
+            AbilityType::Constitution => self.constitution,
+            AbilityType::Intelligence => self.intelligence,
+            AbilityType::Wisdom => self.wisdom,
+            AbilityType::Charisma => self.charisma,
+        }
+    }
+
+    pub fn score_mut(&mut self, ability: AbilityType) -> &mut i32 {
+        match ability {
+            AbilityType::Strength => &mut self.strength,
+            AbilityType::Dexterity => &mut self.dexterity,
+            AbilityType::Constitution => &mut self.constitution,
+            AbilityType::Intelligence => &mut self.intelligence,
+            AbilityType::Wisdom => &mut self.wisdom,
+            AbilityType::Charisma => &mut self.charisma,
+        }
+    }
+
+    pub fn modifier(&self, ability: AbilityType) -> i32 {
+        (self.score(ability) - 10).div_euclid(2)
+    }
+
     pub fn dexterity_modifier(&self) -> i32 {
-        (self.dexterity - 10) / 2
+        self.modifier(AbilityType::Dexterity)
     }
 }
 
@@ -193,6 +730,31 @@ pub struct EquipmentEntry {
     pub equiped: bool,
     pub quantity: i32,
     pub item_id: ItemId,
+    // the container item the stack is stored in, if any
+    pub container_id: Option<ItemId>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HitDiceUse {
+    pub dice: Dice,
+    pub expended: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ItemChargeUse {
+    pub item_id: ItemId,
+    pub expended: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SpellSlot {
+    pub level: i32,
+    pub total: i32,
+    pub expended: i32,
+    // pact magic slots are kept apart from other slots of the same level as
+    // they come back on a short rest
+    #[serde(default)]
+    pub pact_magic: bool,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
@@ -220,6 +782,53 @@ pub struct Proficiencies {
     pub skills: Vec<SkillType>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct CharacterUpdate {
+    pub name: Option<String>,
+    pub ability_scores: Option<CharacterAbilities>,
+    pub proficiencies: Option<Proficiencies>,
+    pub current_hit_points: Option<i32>,
+    pub maximum_hit_points: Option<i32>,
+    pub ai_controlled: Option<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Condition {
+    Blinded,
+    Charmed,
+    Deafened,
+    Frightened,
+    Grappled,
+    Incapacitated,
+    Invisible,
+    Paralyzed,
+    Petrified,
+    Poisoned,
+    Prone,
+    Restrained,
+    Stunned,
+    Unconscious,
+
+    // not a condition in the rules, but the result of being in the area of
+    // Silence or otherwise gagged
+    Silenced,
+    // not a condition in the rules, but tracked the same way
+    Raging,
+}
+
+impl Condition {
+    pub fn prevents_speech(&self) -> bool {
+        matches!(
+            self,
+            Condition::Paralyzed
+                | Condition::Petrified
+                | Condition::Unconscious
+                | Condition::Silenced
+        )
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum RollModifier {
@@ -228,7 +837,21 @@ pub enum RollModifier {
     Disadvantage,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+// what the roll is made against, since some traits only apply in certain
+// situations
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RollCircumstances {
+    #[serde(default)]
+    pub magic: bool,
+    #[serde(default)]
+    pub frightened: bool,
+    #[serde(default)]
+    pub charmed: bool,
+    #[serde(default)]
+    pub poisoned: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum ToolType {
     // Artisan Tools
@@ -278,7 +901,7 @@ pub enum ToolType {
     TheivesTools,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum Language {
     Common,
     Dwarvish,
@@ -298,3 +921,123 @@ pub enum Language {
     Sylvan,
     Undercommon,
 }
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    // a bare character owned by the campaign for manager tests to build on
+    pub fn character(campaign_id: CampaignId) -> Character {
+        let now = Utc::now();
+        Character {
+            id: CharacterId::new(),
+            owner: CharacterOwner::Campaign(campaign_id),
+            name: "Mr. Understanding".to_string(),
+            created_at: now,
+            modified_at: now,
+            ability_scores: Some(CharacterAbilities::default()),
+            ability_score_generation: AbilityScoreGeneration::Unspecified,
+            stats: CharacterStats::default(),
+            equipment: vec![],
+            purse: Purse::default(),
+            position: None,
+            current_hit_points: 10,
+            maximum_hit_points: 10,
+            experience: 0,
+            classes: vec![],
+            feature_uses: vec![],
+            attuned_items: vec![],
+            item_charges: vec![],
+            expended_hit_dice: vec![],
+            spell_slots: vec![],
+            race: Some(Race::Human),
+            subrace: None,
+            background: None,
+            racial_traits: vec![],
+            proficiencies: Proficiencies {
+                armor: vec![],
+                tool: vec![],
+                saving_throws: vec![],
+                skills: vec![],
+            },
+            conditions: vec![],
+            exhaustion: 0,
+            monster: None,
+            ai_controlled: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson;
+
+    use super::*;
+    use crate::database::test::MockDatabase;
+
+    #[test]
+    fn characters_stored_before_later_fields_still_load() {
+        let now = bson::DateTime::from_chrono(Utc::now());
+        let document = bson::doc! {
+            "_id": CharacterId::new().to_string(),
+            "owner": { "campaign_id": CampaignId::new().to_string(), "user_id": null },
+            "name": "Mr. Understanding",
+            "created_at": now,
+            "modified_at": now,
+            "stats": {
+                "abilities": {
+                    "strength": 14,
+                    "dexterity": 12,
+                    "constitution": 10,
+                    "intelligence": 10,
+                    "wisdom": 10,
+                    "charisma": 8,
+                },
+                "initiative": 1,
+                "speed": 30,
+                "armor_class": 11,
+                "proficiency_bonus": 2,
+            },
+            "equipment": [],
+            "position": null,
+            "current_hit_points": 8,
+            "maximum_hit_points": 8,
+            "race": "HUMAN",
+            "racial_traits": [],
+            "proficiencies": {
+                "armor": [],
+                "tool": [],
+                "saving_throws": [],
+                "skills": [],
+            },
+        };
+
+        let character: Character = bson::from_document(document).unwrap();
+
+        assert_eq!(character.base_abilities().strength, 14);
+        assert_eq!(character.stats.carrying_capacity, 150);
+        assert_eq!(character.level(), 0);
+        assert!(character.race.is_some());
+        assert!(character.monster.is_none());
+        assert!(!character.ai_controlled);
+    }
+
+    #[actix_rt::test]
+    async fn racial_increases_are_not_applied_twice() {
+        let mut db = MockDatabase::new();
+        db.campaigns.on_fetch_campaign_by_id = Box::new(|_| Ok(None));
+        let mut character = test::character(CampaignId::new());
+        character.racial_traits = vec![RacialTrait::AbilityScoreIncrease(vec![
+            AbilityType::Strength,
+            AbilityType::Strength,
+        ])];
+        character.ability_scores = None;
+        character.stats.abilities.strength = 18;
+
+        character.recalculate_stats(&db).await.unwrap();
+        character.recalculate_stats(&db).await.unwrap();
+
+        assert_eq!(character.ability_scores.unwrap().strength, 16);
+        assert_eq!(character.stats.abilities.strength, 18);
+    }
+}
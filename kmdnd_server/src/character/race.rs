@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use crate::item::{ArmorType, DamageType};
 use crate::operation::{AbilityType, SkillType};
 
-use super::{Language, ToolType};
+use super::{Language, Position, ToolType};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum Race {
     Dwarf,
@@ -18,6 +19,294 @@ pub enum Race {
     Tiefling,
 }
 
+impl Race {
+    pub fn size(&self) -> Size {
+        match self {
+            Race::Halfling | Race::Gnome => Size::Small,
+            _ => Size::Medium,
+        }
+    }
+
+    pub fn speed(&self, subrace: Option<Subrace>) -> i32 {
+        match (self, subrace) {
+            (Race::Dwarf, _) | (Race::Halfling, _) | (Race::Gnome, _) => 25,
+            (Race::Elf, Some(Subrace::WoodElf)) => 35,
+            _ => 30,
+        }
+    }
+
+    pub fn subraces(&self) -> &'static [Subrace] {
+        match self {
+            Race::Dwarf => &[Subrace::HillDwarf, Subrace::MountainDwarf],
+            Race::Elf => &[Subrace::HighElf, Subrace::WoodElf],
+            Race::Halfling => &[Subrace::Lightfoot, Subrace::Stout],
+            Race::Gnome => &[Subrace::ForestGnome, Subrace::RockGnome],
+            _ => &[],
+        }
+    }
+
+    pub fn languages(&self) -> Vec<Language> {
+        match self {
+            Race::Dwarf => vec![Language::Common, Language::Dwarvish],
+            Race::Elf => vec![Language::Common, Language::Elvish],
+            Race::Halfling => vec![Language::Common, Language::Halfling],
+            Race::Human => vec![Language::Common],
+            Race::Dragonborn => vec![Language::Common, Language::Draconic],
+            Race::Gnome => vec![Language::Common, Language::Gnomish],
+            Race::HalfElf => vec![Language::Common, Language::Elvish],
+            Race::HalfOrc => vec![Language::Common, Language::Orc],
+            Race::Tiefling => vec![Language::Common, Language::Infernal],
+        }
+    }
+
+    // traits that involve a choice (like a Dwarf's tool proficiency or a
+    // Half-Elf's skills) are left empty to be filled in by the player
+    pub fn default_traits(&self, subrace: Option<Subrace>) -> Vec<RacialTrait> {
+        use AbilityType::*;
+
+        let mut traits = match self {
+            Race::Dwarf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Constitution, Constitution]),
+                RacialTrait::Darkvision,
+                RacialTrait::DwarvenResiliance,
+                RacialTrait::DwarvenCombatTraining,
+                RacialTrait::ToolProficiency(vec![]),
+                RacialTrait::Stonecunning,
+            ],
+            Race::Elf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Dexterity, Dexterity]),
+                RacialTrait::Darkvision,
+                RacialTrait::KeenSenses,
+                RacialTrait::FeyAncestry,
+                RacialTrait::Trance,
+            ],
+            Race::Halfling => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Dexterity, Dexterity]),
+                RacialTrait::Lucky,
+                RacialTrait::Brave,
+                RacialTrait::HalflingNimbleness,
+            ],
+            Race::Human => vec![
+                RacialTrait::AbilityScoreIncrease(vec![
+                    Strength,
+                    Dexterity,
+                    Constitution,
+                    Intelligence,
+                    Wisdom,
+                    Charisma,
+                ]),
+                RacialTrait::ExtraLanguage,
+            ],
+            Race::Dragonborn => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Strength, Strength, Charisma]),
+                RacialTrait::BreathWeapon,
+                RacialTrait::DamageResistance,
+            ],
+            Race::Gnome => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Intelligence, Intelligence]),
+                RacialTrait::Darkvision,
+                RacialTrait::GnomeCunning,
+            ],
+            Race::HalfElf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Charisma, Charisma]),
+                RacialTrait::Darkvision,
+                RacialTrait::FeyAncestry,
+                RacialTrait::SkillVersatility(vec![]),
+                RacialTrait::ExtraLanguage,
+            ],
+            Race::HalfOrc => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Strength, Strength, Constitution]),
+                RacialTrait::Darkvision,
+                RacialTrait::Menacing,
+                RacialTrait::RelentlessEndurance,
+                RacialTrait::SavageAttacks,
+            ],
+            Race::Tiefling => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Intelligence, Charisma, Charisma]),
+                RacialTrait::Darkvision,
+                RacialTrait::HellishResistance,
+                RacialTrait::InfernalLegacy,
+            ],
+        };
+
+        traits.push(RacialTrait::Languages(self.languages()));
+
+        if let Some(subrace) = subrace {
+            traits.extend(subrace.traits());
+        }
+
+        traits
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Subrace {
+    HillDwarf,
+    MountainDwarf,
+    HighElf,
+    WoodElf,
+    Lightfoot,
+    Stout,
+    ForestGnome,
+    RockGnome,
+}
+
+impl Subrace {
+    pub fn race(&self) -> Race {
+        match self {
+            Subrace::HillDwarf | Subrace::MountainDwarf => Race::Dwarf,
+            Subrace::HighElf | Subrace::WoodElf => Race::Elf,
+            Subrace::Lightfoot | Subrace::Stout => Race::Halfling,
+            Subrace::ForestGnome | Subrace::RockGnome => Race::Gnome,
+        }
+    }
+
+    pub fn traits(&self) -> Vec<RacialTrait> {
+        use AbilityType::*;
+
+        match self {
+            Subrace::HillDwarf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Wisdom]),
+                RacialTrait::DwarvenToughness,
+            ],
+            Subrace::MountainDwarf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Strength, Strength]),
+                RacialTrait::DwarvenArmorTraining,
+            ],
+            Subrace::HighElf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Intelligence]),
+                RacialTrait::ElfWeaponTraining,
+                RacialTrait::ExtraLanguage,
+            ],
+            Subrace::WoodElf => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Wisdom]),
+                RacialTrait::ElfWeaponTraining,
+                RacialTrait::FleetOfFoot,
+                RacialTrait::MaskOfTheWild,
+            ],
+            Subrace::Lightfoot => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Charisma]),
+                RacialTrait::NaturallyStealthy,
+            ],
+            Subrace::Stout => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Constitution]),
+                RacialTrait::StoutResilience,
+            ],
+            Subrace::ForestGnome => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Dexterity]),
+                RacialTrait::NaturalIllusionist,
+                RacialTrait::SpeakWithSmallBeasts,
+            ],
+            Subrace::RockGnome => vec![
+                RacialTrait::AbilityScoreIncrease(vec![Constitution]),
+                RacialTrait::ArtificersLore,
+                RacialTrait::Tinker,
+            ],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum DragonType {
+    Black,
+    Blue,
+    Brass,
+    Bronze,
+    Copper,
+    Gold,
+    Green,
+    Red,
+    Silver,
+    White,
+}
+
+impl DragonType {
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            DragonType::Black | DragonType::Copper => DamageType::Acid,
+            DragonType::Blue | DragonType::Bronze => DamageType::Lightning,
+            DragonType::Brass | DragonType::Gold | DragonType::Red => DamageType::Fire,
+            DragonType::Green => DamageType::Poison,
+            DragonType::Silver | DragonType::White => DamageType::Cold,
+        }
+    }
+
+    pub fn breath_shape(&self) -> BreathShape {
+        match self {
+            DragonType::Black
+            | DragonType::Blue
+            | DragonType::Brass
+            | DragonType::Bronze
+            | DragonType::Copper => BreathShape::Line {
+                length: 30.0,
+                width: 5.0,
+            },
+            DragonType::Gold
+            | DragonType::Green
+            | DragonType::Red
+            | DragonType::Silver
+            | DragonType::White => BreathShape::Cone { length: 15.0 },
+        }
+    }
+
+    pub fn breath_save(&self) -> AbilityType {
+        match self {
+            DragonType::Green | DragonType::Silver | DragonType::White => AbilityType::Constitution,
+            _ => AbilityType::Dexterity,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum BreathShape {
+    Cone { length: f32 },
+    Line { length: f32, width: f32 },
+}
+
+impl BreathShape {
+    // the breath is aimed from the origin toward the target position
+    pub fn contains(&self, origin: &Position, toward: &Position, point: &Position) -> bool {
+        let direction = [
+            toward.x - origin.x,
+            toward.y - origin.y,
+            toward.z - origin.z,
+        ];
+        let direction_length = origin.distance(toward);
+        if direction_length == 0.0 {
+            return false;
+        }
+
+        let offset = [point.x - origin.x, point.y - origin.y, point.z - origin.z];
+        let along = (0..3).map(|i| offset[i] * direction[i]).sum::<f32>() / direction_length;
+        let across = f32::sqrt(f32::max(
+            origin.distance(point).powi(2) - along.powi(2),
+            0.0,
+        ));
+
+        match *self {
+            // the width of a cone is equal to the distance from its origin
+            BreathShape::Cone { length } => along > 0.0 && along <= length && across <= along / 2.0,
+            BreathShape::Line { length, width } => {
+                along > 0.0 && along <= length && across <= width / 2.0
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Size {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum RacialTrait {
     AbilityScoreIncrease(Vec<AbilityType>),
@@ -28,20 +317,26 @@ pub enum RacialTrait {
     Stonecunning,
     Languages(Vec<Language>),
     DwarvenToughness,
+    DwarvenArmorTraining,
     KeenSenses,
     FeyAncestry,
     Trance,
     ElfWeaponTraining,
+    FleetOfFoot,
+    MaskOfTheWild,
     // Cantrip(),
     ExtraLanguage,
     Lucky,
     Brave,
     HalflingNimbleness,
     NaturallyStealthy,
-    // DraconicAncestry(DragonType),
+    StoutResilience,
+    DraconicAncestry(DragonType),
     BreathWeapon,
     DamageResistance,
     GnomeCunning,
+    NaturalIllusionist,
+    SpeakWithSmallBeasts,
     ArtificersLore,
     Tinker,
     SkillVersatility(Vec<SkillType>),
@@ -51,3 +346,89 @@ pub enum RacialTrait {
     HellishResistance,
     InfernalLegacy,
 }
+
+impl RacialTrait {
+    pub fn ability_score_increases(&self) -> &[AbilityType] {
+        match self {
+            RacialTrait::AbilityScoreIncrease(abilities) => abilities,
+            _ => &[],
+        }
+    }
+
+    pub fn skill_proficiencies(&self) -> Vec<SkillType> {
+        match self {
+            RacialTrait::KeenSenses => vec![SkillType::Perception],
+            RacialTrait::Menacing => vec![SkillType::Intimidation],
+            RacialTrait::SkillVersatility(skills) => skills.clone(),
+            _ => vec![],
+        }
+    }
+
+    pub fn damage_resistances(&self) -> Vec<DamageType> {
+        match self {
+            RacialTrait::DwarvenResiliance | RacialTrait::StoutResilience => {
+                vec![DamageType::Poison]
+            }
+            RacialTrait::HellishResistance => vec![DamageType::Fire],
+            _ => vec![],
+        }
+    }
+
+    pub fn armor_proficiencies(&self) -> Vec<ArmorType> {
+        match self {
+            RacialTrait::DwarvenArmorTraining => vec![ArmorType::Light, ArmorType::Medium],
+            _ => vec![],
+        }
+    }
+
+    pub fn tool_proficiencies(&self) -> Vec<ToolType> {
+        match self {
+            RacialTrait::ToolProficiency(tools) => tools.clone(),
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subraces_belong_to_their_race() {
+        for race in [Race::Dwarf, Race::Elf, Race::Halfling, Race::Gnome] {
+            assert!(race.subraces().iter().all(|subrace| subrace.race() == race));
+        }
+    }
+
+    #[test]
+    fn breath_weapon_shapes_cover_their_area() {
+        let origin = Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let toward = Position {
+            x: 10.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let at = |x, y| Position { x, y, z: 0.0 };
+
+        let cone = DragonType::Red.breath_shape();
+        assert!(cone.contains(&origin, &toward, &at(10.0, 4.0)));
+        assert!(!cone.contains(&origin, &toward, &at(10.0, 6.0)));
+        assert!(!cone.contains(&origin, &toward, &at(20.0, 0.0)));
+        assert!(!cone.contains(&origin, &toward, &at(-5.0, 0.0)));
+
+        let line = DragonType::Blue.breath_shape();
+        assert!(line.contains(&origin, &toward, &at(25.0, 2.0)));
+        assert!(!line.contains(&origin, &toward, &at(25.0, 3.0)));
+    }
+
+    #[test]
+    fn small_races_are_slower() {
+        assert_eq!(Race::Halfling.speed(Some(Subrace::Lightfoot)), 25);
+        assert_eq!(Race::Elf.speed(Some(Subrace::WoodElf)), 35);
+        assert_eq!(Race::Human.speed(None), 30);
+    }
+}
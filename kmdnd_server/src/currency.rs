@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+// item values and material costs are tracked in copper pieces, the smallest
+// denomination
+pub type CopperPieces = i32;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Coin {
+    Copper,
+    Silver,
+    Electrum,
+    Gold,
+    Platinum,
+}
+
+impl Coin {
+    const ASCENDING: [Coin; 5] = [
+        Coin::Copper,
+        Coin::Silver,
+        Coin::Electrum,
+        Coin::Gold,
+        Coin::Platinum,
+    ];
+
+    pub fn value(&self) -> CopperPieces {
+        match self {
+            Coin::Copper => 1,
+            Coin::Silver => 10,
+            Coin::Electrum => 50,
+            Coin::Gold => 100,
+            Coin::Platinum => 1000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Purse {
+    #[serde(default)]
+    pub copper: i32,
+    #[serde(default)]
+    pub silver: i32,
+    #[serde(default)]
+    pub electrum: i32,
+    #[serde(default)]
+    pub gold: i32,
+    #[serde(default)]
+    pub platinum: i32,
+}
+
+impl Purse {
+    // makes change with the common denominations, electrum and platinum are
+    // never handed out as change
+    pub fn from_copper(amount: CopperPieces) -> Purse {
+        Purse {
+            gold: amount / 100,
+            silver: amount % 100 / 10,
+            copper: amount % 10,
+            ..Purse::default()
+        }
+    }
+
+    pub fn coins(&self, coin: Coin) -> i32 {
+        match coin {
+            Coin::Copper => self.copper,
+            Coin::Silver => self.silver,
+            Coin::Electrum => self.electrum,
+            Coin::Gold => self.gold,
+            Coin::Platinum => self.platinum,
+        }
+    }
+
+    pub fn coins_mut(&mut self, coin: Coin) -> &mut i32 {
+        match coin {
+            Coin::Copper => &mut self.copper,
+            Coin::Silver => &mut self.silver,
+            Coin::Electrum => &mut self.electrum,
+            Coin::Gold => &mut self.gold,
+            Coin::Platinum => &mut self.platinum,
+        }
+    }
+
+    pub fn total(&self) -> CopperPieces {
+        Coin::ASCENDING
+            .iter()
+            .map(|coin| self.coins(*coin) * coin.value())
+            .sum()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        Coin::ASCENDING.iter().all(|coin| self.coins(*coin) >= 0)
+    }
+
+    pub fn add(&mut self, other: &Purse) {
+        for coin in Coin::ASCENDING.iter() {
+            *self.coins_mut(*coin) += other.coins(*coin);
+        }
+    }
+
+    // removes the exact coins given, returns false if there aren't enough of
+    // any denomination
+    pub fn remove(&mut self, other: &Purse) -> bool {
+        let mut remaining = self.clone();
+        for coin in Coin::ASCENDING.iter() {
+            *remaining.coins_mut(*coin) -= other.coins(*coin);
+        }
+
+        if !remaining.is_valid() {
+            return false;
+        }
+
+        *self = remaining;
+        true
+    }
+
+    // pays the amount starting with the smallest coins, breaking a larger coin
+    // and receiving change if needed, returns false if the purse can't afford it
+    pub fn pay(&mut self, amount: CopperPieces) -> bool {
+        if self.total() < amount {
+            return false;
+        }
+
+        let mut remaining = amount;
+        for coin in Coin::ASCENDING.iter() {
+            let used = i32::min(self.coins(*coin), remaining / coin.value());
+            *self.coins_mut(*coin) -= used;
+            remaining -= used * coin.value();
+        }
+
+        if remaining > 0 {
+            let coin = Coin::ASCENDING
+                .iter()
+                .find(|coin| self.coins(**coin) > 0)
+                .copied()
+                .expect("a purse that can afford the amount must have a coin to break");
+
+            *self.coins_mut(coin) -= 1;
+            self.add(&Purse::from_copper(coin.value() - remaining));
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paying_breaks_larger_coins_for_change() {
+        let mut purse = Purse {
+            copper: 5,
+            gold: 1,
+            ..Purse::default()
+        };
+
+        assert!(purse.pay(12));
+        assert_eq!(purse.total(), 93);
+        assert_eq!(purse.gold, 0);
+
+        assert!(!purse.pay(94));
+        assert_eq!(purse.total(), 93);
+    }
+
+    #[test]
+    fn removing_requires_exact_coins() {
+        let mut purse = Purse {
+            gold: 2,
+            ..Purse::default()
+        };
+
+        assert!(!purse.remove(&Purse {
+            silver: 1,
+            ..Purse::default()
+        }));
+        assert!(purse.remove(&Purse {
+            gold: 1,
+            ..Purse::default()
+        }));
+        assert_eq!(purse.gold, 1);
+    }
+}
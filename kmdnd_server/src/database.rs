@@ -10,14 +10,20 @@ use crate::encounter::Encounter;
 use crate::error::Error;
 use crate::item::db::ItemStore;
 use crate::item::Item;
+use crate::monster::db::MonsterStore;
+use crate::monster::Monster;
 use crate::operation::db::OperationStore;
+use crate::operation::spell::db::SpellStore;
+use crate::operation::spell::Spell;
 use crate::operation::Operation;
 
 pub type MongoCampaignStore = Collection<Campaign>;
 pub type MongoCharacterStore = Collection<Character>;
 pub type MongoEncounterStore = Collection<Encounter>;
 pub type MongoItemStore = Collection<Item>;
+pub type MongoMonsterStore = Collection<Monster>;
 pub type MongoOperationStore = Collection<Operation>;
+pub type MongoSpellStore = Collection<Spell>;
 
 #[async_trait]
 pub trait Database {
@@ -25,7 +31,9 @@ pub trait Database {
     fn characters(&self) -> &dyn CharacterStore;
     fn encounters(&self) -> &dyn EncounterStore;
     fn items(&self) -> &dyn ItemStore;
+    fn monsters(&self) -> &dyn MonsterStore;
     fn operations(&self) -> &dyn OperationStore;
+    fn spells(&self) -> &dyn SpellStore;
 
     async fn drop(&self) -> Result<(), Error>;
 }
@@ -37,7 +45,9 @@ pub struct MongoDatabase {
     characters: Collection<Character>,
     encounters: Collection<Encounter>,
     items: Collection<Item>,
+    monsters: Collection<Monster>,
     operations: Collection<Operation>,
+    spells: Collection<Spell>,
 }
 
 impl MongoDatabase {
@@ -46,7 +56,9 @@ impl MongoDatabase {
         let characters = initialize_characters(&db).await?;
         let encounters = initialize_encounters(&db).await?;
         let items = initialize_items(&db).await?;
+        let monsters = initialize_monsters(&db).await?;
         let operations = initialize_operations(&db).await?;
+        let spells = initialize_spells(&db).await?;
 
         Ok(MongoDatabase {
             db,
@@ -54,7 +66,9 @@ impl MongoDatabase {
             characters,
             encounters,
             items,
+            monsters,
             operations,
+            spells,
         })
     }
 }
@@ -77,10 +91,18 @@ impl Database for MongoDatabase {
         &self.items
     }
 
+    fn monsters(&self) -> &dyn MonsterStore {
+        &self.monsters
+    }
+
     fn operations(&self) -> &dyn OperationStore {
         &self.operations
     }
 
+    fn spells(&self) -> &dyn SpellStore {
+        &self.spells
+    }
+
     async fn drop(&self) -> Result<(), Error> {
         self.db.drop(None).await?;
         Ok(())
@@ -91,7 +113,9 @@ const CAMPAIGNS: &str = "campaigns";
 const CHARACTERS: &str = "characters";
 const ENCOUNTERS: &str = "encounters";
 const ITEMS: &str = "items";
+const MONSTERS: &str = "monsters";
 const OPERATIONS: &str = "operations";
+const SPELLS: &str = "spells";
 
 pub async fn initialize_campaigns(db: &mongodb::Database) -> Result<MongoCampaignStore, Error> {
     Ok(db.collection(CAMPAIGNS))
@@ -132,6 +156,10 @@ pub async fn initialize_items(db: &mongodb::Database) -> Result<MongoItemStore,
     Ok(db.collection(ITEMS))
 }
 
+pub async fn initialize_monsters(db: &mongodb::Database) -> Result<MongoMonsterStore, Error> {
+    Ok(db.collection(MONSTERS))
+}
+
 pub async fn initialize_operations(db: &mongodb::Database) -> Result<MongoOperationStore, Error> {
     db.run_command(
         bson::doc! {
@@ -148,19 +176,49 @@ pub async fn initialize_operations(db: &mongodb::Database) -> Result<MongoOperat
     Ok(db.collection(OPERATIONS))
 }
 
+pub async fn initialize_spells(db: &mongodb::Database) -> Result<MongoSpellStore, Error> {
+    db.run_command(
+        bson::doc! {
+            "createIndexes": SPELLS,
+            "indexes": [
+                { "key": { "name": 1 }, "name": "by_name", "unique": true },
+            ]
+        },
+        None,
+    )
+    .await?;
+
+    Ok(db.collection(SPELLS))
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
     use crate::campaign::db::MockCampaignStore;
+    use crate::character::db::MockCharacterStore;
+    use crate::encounter::db::MockEncounterStore;
+    use crate::item::db::MockItemStore;
+    use crate::operation::db::MockOperationStore;
+    use crate::operation::spell::db::MockSpellStore;
 
     pub struct MockDatabase {
         pub campaigns: MockCampaignStore,
+        pub characters: MockCharacterStore,
+        pub encounters: MockEncounterStore,
+        pub items: MockItemStore,
+        pub operations: MockOperationStore,
+        pub spells: MockSpellStore,
     }
 
     impl MockDatabase {
         pub fn new() -> MockDatabase {
             MockDatabase {
                 campaigns: MockCampaignStore::new(),
+                characters: MockCharacterStore::new(),
+                encounters: MockEncounterStore::new(),
+                items: MockItemStore::new(),
+                operations: MockOperationStore::new(),
+                spells: MockSpellStore::new(),
             }
         }
     }
@@ -172,21 +230,30 @@ pub mod test {
         }
 
         fn characters(&self) -> &dyn CharacterStore {
-            unimplemented!("MockDatabase::characters")
+            &self.characters
         }
 
         fn encounters(&self) -> &dyn EncounterStore {
-            unimplemented!("MockDatabase::encounters")
+            &self.encounters
         }
 
         fn items(&self) -> &dyn ItemStore {
-            unimplemented!("MockDatabase::items")
+            &self.items
+        }
+
+        fn monsters(&self) -> &dyn MonsterStore {
+            unimplemented!("MockDatabase::monsters")
         }
 
         fn operations(&self) -> &dyn OperationStore {
-            unimplemented!("MockDatabase::operations")
+            &self.operations
+        }
+
+        fn spells(&self) -> &dyn SpellStore {
+            &self.spells
         }
 
+        #[allow(clippy::diverging_sub_expression)]
         async fn drop(&self) -> Result<(), Error> {
             unimplemented!("MockDatabase::drop")
         }
@@ -9,7 +9,7 @@ use crate::character::CharacterId;
 use crate::database::MongoEncounterStore;
 use crate::error::Error;
 
-use super::{Encounter, EncounterState};
+use super::{Encounter, EncounterId, EncounterState, Participant};
 
 #[async_trait]
 pub trait EncounterStore {
@@ -20,6 +20,12 @@ pub trait EncounterStore {
         campaign_id: CampaignId,
     ) -> Result<Vec<Encounter>, Error>;
 
+    async fn fetch_encounter_by_campaign_and_id(
+        &self,
+        campaign_id: CampaignId,
+        encounter_id: EncounterId,
+    ) -> Result<Option<Encounter>, Error>;
+
     async fn fetch_current_encounter_by_campaign(
         &self,
         campaign_id: CampaignId,
@@ -37,6 +43,12 @@ pub trait EncounterStore {
         state: EncounterState,
         character_ids: Vec<CharacterId>,
     ) -> Result<Encounter, Error>;
+
+    async fn update_encounter_factions(
+        &self,
+        mut encounter: Encounter,
+        factions: Vec<Participant>,
+    ) -> Result<Encounter, Error>;
 }
 
 #[async_trait]
@@ -66,6 +78,22 @@ impl EncounterStore for MongoEncounterStore {
         Ok(encounters)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn fetch_encounter_by_campaign_and_id(
+        &self,
+        campaign_id: CampaignId,
+        encounter_id: EncounterId,
+    ) -> Result<Option<Encounter>, Error> {
+        let encounter: Option<Encounter> = self
+            .find_one(
+                bson::doc! { "_id": encounter_id, "campaign_id": campaign_id },
+                None,
+            )
+            .await?;
+
+        Ok(encounter)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn fetch_current_encounter_by_campaign(
         &self,
@@ -147,4 +175,141 @@ impl EncounterStore for MongoEncounterStore {
 
         Ok(encounter)
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_encounter_factions(
+        &self,
+        mut encounter: Encounter,
+        factions: Vec<Participant>,
+    ) -> Result<Encounter, Error> {
+        let now = Utc::now();
+        let old_modified_at = bson::DateTime::from_chrono(encounter.modified_at);
+        let new_modified_at = bson::DateTime::from_chrono(now);
+        let new_factions = bson::to_bson(&factions)?;
+
+        let result = self
+            .update_one(
+                bson::doc! { "_id": encounter.id, "modified_at": old_modified_at },
+                bson::doc! { "$set": {
+                    "factions": new_factions,
+                    "modified_at": new_modified_at
+                } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ConcurrentModificationDetected);
+        }
+
+        encounter.modified_at = now;
+        encounter.factions = factions;
+
+        Ok(encounter)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+pub struct MockEncounterStore {
+    pub on_insert_encounter: Box<dyn Fn(&Encounter) -> Result<(), Error> + Send + Sync>,
+    pub on_fetch_encounters_by_campaign:
+        Box<dyn Fn(CampaignId) -> Result<Vec<Encounter>, Error> + Send + Sync>,
+    pub on_fetch_encounter_by_campaign_and_id:
+        Box<dyn Fn(CampaignId, EncounterId) -> Result<Option<Encounter>, Error> + Send + Sync>,
+    pub on_fetch_current_encounter_by_campaign:
+        Box<dyn Fn(CampaignId) -> Result<Option<Encounter>, Error> + Send + Sync>,
+    pub on_update_encounter_state:
+        Box<dyn Fn(Encounter, EncounterState) -> Result<Encounter, Error> + Send + Sync>,
+    pub on_update_encounter_state_and_characters: Box<
+        dyn Fn(Encounter, EncounterState, Vec<CharacterId>) -> Result<Encounter, Error>
+            + Send
+            + Sync,
+    >,
+    pub on_update_encounter_factions:
+        Box<dyn Fn(Encounter, Vec<Participant>) -> Result<Encounter, Error> + Send + Sync>,
+}
+
+#[cfg(test)]
+impl MockEncounterStore {
+    pub fn new() -> MockEncounterStore {
+        MockEncounterStore {
+            on_insert_encounter: Box::new(|_| {
+                unimplemented!("MockEncounterStore::insert_encounter")
+            }),
+            on_fetch_encounters_by_campaign: Box::new(|_| {
+                unimplemented!("MockEncounterStore::fetch_encounters_by_campaign")
+            }),
+            on_fetch_encounter_by_campaign_and_id: Box::new(|_, _| {
+                unimplemented!("MockEncounterStore::fetch_encounter_by_campaign_and_id")
+            }),
+            on_fetch_current_encounter_by_campaign: Box::new(|_| {
+                unimplemented!("MockEncounterStore::fetch_current_encounter_by_campaign")
+            }),
+            on_update_encounter_state: Box::new(|_, _| {
+                unimplemented!("MockEncounterStore::update_encounter_state")
+            }),
+            on_update_encounter_state_and_characters: Box::new(|_, _, _| {
+                unimplemented!("MockEncounterStore::update_encounter_state_and_characters")
+            }),
+            on_update_encounter_factions: Box::new(|_, _| {
+                unimplemented!("MockEncounterStore::update_encounter_factions")
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl EncounterStore for MockEncounterStore {
+    async fn insert_encounter(&self, encounter: &Encounter) -> Result<(), Error> {
+        (self.on_insert_encounter)(encounter)
+    }
+
+    async fn fetch_encounters_by_campaign(
+        &self,
+        campaign_id: CampaignId,
+    ) -> Result<Vec<Encounter>, Error> {
+        (self.on_fetch_encounters_by_campaign)(campaign_id)
+    }
+
+    async fn fetch_encounter_by_campaign_and_id(
+        &self,
+        campaign_id: CampaignId,
+        encounter_id: EncounterId,
+    ) -> Result<Option<Encounter>, Error> {
+        (self.on_fetch_encounter_by_campaign_and_id)(campaign_id, encounter_id)
+    }
+
+    async fn fetch_current_encounter_by_campaign(
+        &self,
+        campaign_id: CampaignId,
+    ) -> Result<Option<Encounter>, Error> {
+        (self.on_fetch_current_encounter_by_campaign)(campaign_id)
+    }
+
+    async fn update_encounter_state(
+        &self,
+        encounter: Encounter,
+        state: EncounterState,
+    ) -> Result<Encounter, Error> {
+        (self.on_update_encounter_state)(encounter, state)
+    }
+
+    async fn update_encounter_state_and_characters(
+        &self,
+        encounter: Encounter,
+        state: EncounterState,
+        character_ids: Vec<CharacterId>,
+    ) -> Result<Encounter, Error> {
+        (self.on_update_encounter_state_and_characters)(encounter, state, character_ids)
+    }
+
+    async fn update_encounter_factions(
+        &self,
+        encounter: Encounter,
+        factions: Vec<Participant>,
+    ) -> Result<Encounter, Error> {
+        (self.on_update_encounter_factions)(encounter, factions)
+    }
 }
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character::Character;
+
+// the experience thresholds of a single character by level, from the Dungeon
+// Master's Guide
+const EXPERIENCE_THRESHOLDS: [ExperienceThresholds; 20] = [
+    ExperienceThresholds::new(25, 50, 75, 100),
+    ExperienceThresholds::new(50, 100, 150, 200),
+    ExperienceThresholds::new(75, 150, 225, 400),
+    ExperienceThresholds::new(125, 250, 375, 500),
+    ExperienceThresholds::new(250, 500, 750, 1100),
+    ExperienceThresholds::new(300, 600, 900, 1400),
+    ExperienceThresholds::new(350, 750, 1100, 1700),
+    ExperienceThresholds::new(450, 900, 1400, 2100),
+    ExperienceThresholds::new(550, 1100, 1600, 2400),
+    ExperienceThresholds::new(600, 1200, 1900, 2800),
+    ExperienceThresholds::new(800, 1600, 2400, 3600),
+    ExperienceThresholds::new(1000, 2000, 3000, 4500),
+    ExperienceThresholds::new(1100, 2200, 3400, 5100),
+    ExperienceThresholds::new(1250, 2500, 3800, 5700),
+    ExperienceThresholds::new(1400, 2800, 4300, 6400),
+    ExperienceThresholds::new(1600, 3200, 4800, 7200),
+    ExperienceThresholds::new(2000, 3900, 5900, 8800),
+    ExperienceThresholds::new(2100, 4200, 6300, 9500),
+    ExperienceThresholds::new(2400, 4900, 7300, 10900),
+    ExperienceThresholds::new(2800, 5700, 8500, 12700),
+];
+
+// the multipliers in order, the party size can shift which one applies
+const MULTIPLIERS: [f32; 8] = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Difficulty {
+    // below the easy threshold
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Deadly,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ExperienceThresholds {
+    pub easy: i32,
+    pub medium: i32,
+    pub hard: i32,
+    pub deadly: i32,
+}
+
+impl ExperienceThresholds {
+    const fn new(easy: i32, medium: i32, hard: i32, deadly: i32) -> ExperienceThresholds {
+        ExperienceThresholds {
+            easy,
+            medium,
+            hard,
+            deadly,
+        }
+    }
+
+    fn for_level(level: i32) -> ExperienceThresholds {
+        EXPERIENCE_THRESHOLDS[level.clamp(1, 20) as usize - 1]
+    }
+
+    fn difficulty(&self, experience: i32) -> Difficulty {
+        if experience >= self.deadly {
+            Difficulty::Deadly
+        } else if experience >= self.hard {
+            Difficulty::Hard
+        } else if experience >= self.medium {
+            Difficulty::Medium
+        } else if experience >= self.easy {
+            Difficulty::Easy
+        } else {
+            Difficulty::Trivial
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EncounterDifficulty {
+    pub party_thresholds: ExperienceThresholds,
+    // the experience awarded for defeating every monster
+    pub total_experience: i32,
+    pub multiplier: f32,
+    // the total scaled by the multiplier, which is compared to the thresholds
+    pub adjusted_experience: i32,
+    pub difficulty: Difficulty,
+}
+
+impl EncounterDifficulty {
+    // enemies without a stat block have no challenge rating, so they count
+    // toward the multiplier without adding experience
+    pub fn evaluate<'a>(
+        party: impl IntoIterator<Item = &'a Character>,
+        enemies: impl IntoIterator<Item = &'a Character>,
+    ) -> EncounterDifficulty {
+        let mut party_thresholds = ExperienceThresholds::default();
+        let mut party_size = 0;
+        for character in party {
+            let thresholds = ExperienceThresholds::for_level(character.level());
+            party_thresholds.easy += thresholds.easy;
+            party_thresholds.medium += thresholds.medium;
+            party_thresholds.hard += thresholds.hard;
+            party_thresholds.deadly += thresholds.deadly;
+            party_size += 1;
+        }
+
+        let mut total_experience = 0;
+        let mut monster_count = 0;
+        for character in enemies {
+            if let Some(stat_block) = character.stat_block() {
+                total_experience += stat_block.challenge_rating.experience().unwrap_or(0);
+            }
+            monster_count += 1;
+        }
+
+        let multiplier = multiplier(monster_count, party_size);
+        let adjusted_experience = (total_experience as f32 * multiplier) as i32;
+
+        EncounterDifficulty {
+            party_thresholds,
+            total_experience,
+            multiplier,
+            adjusted_experience,
+            difficulty: party_thresholds.difficulty(adjusted_experience),
+        }
+    }
+}
+
+// small parties use the next higher multiplier and large parties the next
+// lower one
+fn multiplier(monster_count: usize, party_size: usize) -> f32 {
+    let index = match monster_count {
+        0 | 1 => 1,
+        2 => 2,
+        3..=6 => 3,
+        7..=10 => 4,
+        11..=14 => 5,
+        _ => 6,
+    };
+
+    let index = match party_size {
+        0..=2 => index + 1,
+        3..=5 => index,
+        _ => index - 1,
+    };
+
+    MULTIPLIERS[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipliers_depend_on_monster_count_and_party_size() {
+        assert_eq!(multiplier(1, 4), 1.0);
+        assert_eq!(multiplier(2, 4), 1.5);
+        assert_eq!(multiplier(4, 4), 2.0);
+        assert_eq!(multiplier(15, 4), 4.0);
+
+        assert_eq!(multiplier(1, 2), 1.5);
+        assert_eq!(multiplier(15, 1), 5.0);
+        assert_eq!(multiplier(1, 6), 0.5);
+    }
+
+    #[test]
+    fn adjusted_experience_is_compared_to_party_thresholds() {
+        // four 3rd level characters
+        let thresholds = ExperienceThresholds {
+            easy: 300,
+            medium: 600,
+            hard: 900,
+            deadly: 1600,
+        };
+
+        // four goblins at 50 experience each with a multiplier of 2
+        assert_eq!(thresholds.difficulty(400), Difficulty::Easy);
+        assert_eq!(thresholds.difficulty(200), Difficulty::Trivial);
+        assert_eq!(thresholds.difficulty(900), Difficulty::Hard);
+        assert_eq!(thresholds.difficulty(1600), Difficulty::Deadly);
+        assert_eq!(ExperienceThresholds::for_level(3).easy * 4, 300);
+    }
+}
@@ -4,16 +4,20 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::{self, CampaignId};
-use crate::character::CharacterId;
+use crate::character::{self, Character, CharacterId};
 use crate::database::Database;
 use crate::error::Error;
 use crate::utils::SuccessBody;
 
-use super::{manager, Encounter, EncounterId, EncounterState};
+use super::difficulty::EncounterDifficulty;
+use super::{manager, Encounter, EncounterId, EncounterState, Participant};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct CreateEncounterBody {
     pub character_ids: Vec<CharacterId>,
+    // characters left out join the side their kind defaults to
+    #[serde(default)]
+    pub factions: Vec<Participant>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -23,18 +27,34 @@ pub struct EncounterBody {
     pub created_at: DateTime<Utc>,
     pub modified_at: DateTime<Utc>,
     pub character_ids: Vec<CharacterId>,
+    pub factions: Vec<Participant>,
     pub state: EncounterState,
+    pub difficulty: EncounterDifficulty,
 }
 
 impl EncounterBody {
-    pub fn render(encounter: Encounter) -> EncounterBody {
+    pub async fn render(db: &dyn Database, encounter: Encounter) -> Result<EncounterBody, Error> {
+        let characters = manager::get_encounter_characters(db, &encounter).await?;
+
+        Ok(EncounterBody::render_with_characters(
+            encounter,
+            &characters,
+        ))
+    }
+
+    // lets a list of encounters share the campaign's characters
+    pub fn render_with_characters(encounter: Encounter, characters: &[Character]) -> EncounterBody {
+        let difficulty = manager::get_existing_encounter_difficulty(&encounter, characters);
+
         EncounterBody {
             id: encounter.id,
             campaign_id: encounter.campaign_id,
             created_at: encounter.created_at,
             modified_at: encounter.modified_at,
             character_ids: encounter.character_ids,
+            factions: encounter.factions,
             state: encounter.state,
+            difficulty,
         }
     }
 }
@@ -44,6 +64,11 @@ pub struct BeginEncounterResultBody {
     turn_order: Vec<CharacterId>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct EndTurnBody {
+    pub character_id: CharacterId,
+}
+
 #[post("/campaigns/{campaign_id}/encounters")]
 #[tracing::instrument(skip(db))]
 async fn create_encounter_in_campaign(
@@ -57,9 +82,31 @@ async fn create_encounter_in_campaign(
         .ok_or(Error::CampaignNotFound { campaign_id })?;
     let body = body.into_inner();
 
-    let encounter = manager::create_encounter(&***db, &campaign, body.character_ids).await?;
+    let encounter =
+        manager::create_encounter(&***db, &campaign, body.character_ids, body.factions).await?;
+
+    Ok(Json(EncounterBody::render(&***db, encounter).await?))
+}
+
+// evaluates a proposed roster without creating the encounter
+#[post("/campaigns/{campaign_id}/encounters/preview")]
+#[tracing::instrument(skip(db))]
+async fn preview_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<CreateEncounterBody>,
+) -> Result<Json<EncounterDifficulty>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let difficulty =
+        manager::get_encounter_difficulty(&***db, campaign.id, &body.character_ids, &body.factions)
+            .await?;
 
-    Ok(Json(EncounterBody::render(encounter)))
+    Ok(Json(difficulty))
 }
 
 #[get("/campaigns/{campaign_id}/encounters")]
@@ -74,8 +121,12 @@ async fn get_encounters_in_campaign(
         .ok_or(Error::CampaignNotFound { campaign_id })?;
 
     let encounters = manager::get_encounters(&***db, &campaign).await?;
+    let characters = character::manager::get_characters(&***db, &campaign).await?;
 
-    let body = encounters.into_iter().map(EncounterBody::render).collect();
+    let body = encounters
+        .into_iter()
+        .map(|encounter| EncounterBody::render_with_characters(encounter, &characters))
+        .collect();
 
     Ok(Json(body))
 }
@@ -96,7 +147,7 @@ async fn get_current_encounter_in_campaign(
             campaign_id: campaign.id,
         })?;
 
-    Ok(Json(EncounterBody::render(encounter)))
+    Ok(Json(EncounterBody::render(&***db, encounter).await?))
 }
 
 #[post("/campaigns/{campaign_id}/encounters/CURRENT/begin")]
@@ -122,6 +173,52 @@ async fn begin_current_encounter_in_campaign(
     Ok(Json(body))
 }
 
+#[post("/campaigns/{campaign_id}/encounters/CURRENT/end-turn")]
+#[tracing::instrument(skip(db))]
+async fn end_turn_in_current_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<EndTurnBody>,
+) -> Result<Json<EncounterBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let encounter = manager::get_current_encounter(&***db, &campaign)
+        .await?
+        .ok_or(Error::CurrentEncounterNotFound {
+            campaign_id: campaign.id,
+        })?;
+    let body = body.into_inner();
+
+    let encounter = manager::end_turn(&***db, &campaign, encounter, body.character_id).await?;
+
+    Ok(Json(EncounterBody::render(&***db, encounter).await?))
+}
+
+#[post("/campaigns/{campaign_id}/encounters/CURRENT/factions")]
+#[tracing::instrument(skip(db))]
+async fn update_faction_in_current_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<Participant>,
+) -> Result<Json<EncounterBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let encounter = manager::get_current_encounter(&***db, &campaign)
+        .await?
+        .ok_or(Error::CurrentEncounterNotFound {
+            campaign_id: campaign.id,
+        })?;
+    let body = body.into_inner();
+
+    let encounter = manager::update_encounter_faction(&***db, &campaign, encounter, body).await?;
+
+    Ok(Json(EncounterBody::render(&***db, encounter).await?))
+}
+
 #[post("/campaigns/{campaign_id}/encounters/CURRENT/finish")]
 #[tracing::instrument(skip(db))]
 async fn finish_current_encounter_in_campaign(
@@ -1,18 +1,20 @@
 use chrono::Utc;
 
-use crate::campaign::Campaign;
-use crate::character::CharacterId;
+use crate::campaign::{Campaign, CampaignId};
+use crate::character::{self, Character, CharacterId, Condition};
 use crate::database::Database;
 use crate::error::Error;
-use crate::operation::RollType;
+use crate::operation::{self, RollType};
 
-use super::{Encounter, EncounterId, EncounterState};
+use super::difficulty::EncounterDifficulty;
+use super::{Encounter, EncounterId, EncounterState, Faction, Participant};
 
 #[tracing::instrument(skip(db))]
 pub async fn create_encounter(
     db: &dyn Database,
     campaign: &Campaign,
     character_ids: Vec<CharacterId>,
+    factions: Vec<Participant>,
 ) -> Result<Encounter, Error> {
     let current_encounter = db
         .encounters()
@@ -29,11 +31,35 @@ pub async fn create_encounter(
         .characters()
         .fetch_characters_by_campaign(campaign.id)
         .await?;
+    let mut participants = vec![];
     for character_id in &character_ids {
-        if !characters.iter().any(|c| c.id == *character_id) {
-            return Err(Error::CharacterNotInCampaign {
+        let character = characters.iter().find(|c| c.id == *character_id).ok_or(
+            Error::CharacterNotInCampaign {
                 campaign_id: campaign.id,
                 character_id: *character_id,
+            },
+        )?;
+
+        participants.push(Participant {
+            character_id: character.id,
+            faction: Faction::of(character, &factions),
+        });
+    }
+
+    for (index, participant) in factions.iter().enumerate() {
+        if !character_ids.contains(&participant.character_id) {
+            return Err(Error::FactionForCharacterNotInEncounter {
+                campaign_id: campaign.id,
+                character_id: participant.character_id,
+            });
+        }
+        if factions[..index]
+            .iter()
+            .any(|other| other.character_id == participant.character_id)
+        {
+            return Err(Error::DuplicateFactionForCharacter {
+                campaign_id: campaign.id,
+                character_id: participant.character_id,
             });
         }
     }
@@ -45,6 +71,7 @@ pub async fn create_encounter(
         created_at: now,
         modified_at: now,
         character_ids,
+        factions: participants,
         state: EncounterState::Initiative,
     };
 
@@ -53,6 +80,80 @@ pub async fn create_encounter(
     Ok(encounter)
 }
 
+// a proposed roster has to be made of characters in the campaign
+#[tracing::instrument(skip(db))]
+pub async fn get_encounter_difficulty(
+    db: &dyn Database,
+    campaign_id: CampaignId,
+    character_ids: &[CharacterId],
+    factions: &[Participant],
+) -> Result<EncounterDifficulty, Error> {
+    let characters = db
+        .characters()
+        .fetch_characters_by_campaign(campaign_id)
+        .await?;
+
+    let mut participants = vec![];
+    for character_id in character_ids {
+        let character = characters.iter().find(|c| c.id == *character_id).ok_or(
+            Error::CharacterNotInCampaign {
+                campaign_id,
+                character_id: *character_id,
+            },
+        )?;
+
+        participants.push(character);
+    }
+
+    Ok(evaluate_difficulty(participants, factions))
+}
+
+// characters deleted since the encounter was created are left out instead of
+// failing to render it
+pub fn get_existing_encounter_difficulty(
+    encounter: &Encounter,
+    characters: &[Character],
+) -> EncounterDifficulty {
+    let participants = characters
+        .iter()
+        .filter(|character| encounter.character_ids.contains(&character.id));
+
+    evaluate_difficulty(participants, &encounter.factions)
+}
+
+fn evaluate_difficulty<'a>(
+    participants: impl IntoIterator<Item = &'a Character>,
+    factions: &[Participant],
+) -> EncounterDifficulty {
+    let mut party = vec![];
+    let mut enemies = vec![];
+    for character in participants {
+        match Faction::of(character, factions) {
+            Faction::Party => party.push(character),
+            Faction::Enemies => enemies.push(character),
+            Faction::Neutral => {}
+        }
+    }
+
+    EncounterDifficulty::evaluate(party, enemies)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn get_encounter_characters(
+    db: &dyn Database,
+    encounter: &Encounter,
+) -> Result<Vec<Character>, Error> {
+    let characters = db
+        .characters()
+        .fetch_characters_by_campaign(encounter.campaign_id)
+        .await?
+        .into_iter()
+        .filter(|character| encounter.character_ids.contains(&character.id))
+        .collect();
+
+    Ok(characters)
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn get_encounters(
     db: &dyn Database,
@@ -66,6 +167,20 @@ pub async fn get_encounters(
     Ok(encounters)
 }
 
+#[tracing::instrument(skip(db))]
+pub async fn get_encounter_by_id(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter_id: EncounterId,
+) -> Result<Option<Encounter>, Error> {
+    let encounter = db
+        .encounters()
+        .fetch_encounter_by_campaign_and_id(campaign.id, encounter_id)
+        .await?;
+
+    Ok(encounter)
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn get_current_encounter(
     db: &dyn Database,
@@ -79,12 +194,50 @@ pub async fn get_current_encounter(
     Ok(encounter)
 }
 
+#[tracing::instrument(skip(db))]
+pub async fn update_encounter_faction(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: Encounter,
+    participant: Participant,
+) -> Result<Encounter, Error> {
+    if !encounter.character_ids.contains(&participant.character_id) {
+        return Err(Error::CharacterNotInEncounter {
+            campaign_id: campaign.id,
+            encounter_id: encounter.id,
+            character_id: participant.character_id,
+        });
+    }
+
+    let mut factions = encounter.factions.clone();
+    factions.retain(|other| other.character_id != participant.character_id);
+    factions.push(participant);
+
+    db.encounters()
+        .update_encounter_factions(encounter, factions)
+        .await
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn finish_encounter(
     db: &dyn Database,
     campaign: &Campaign,
     encounter: Encounter,
 ) -> Result<(), Error> {
+    // rage lasts a minute, which no encounter outlasts
+    let characters = get_encounter_characters(db, &encounter).await?;
+    for character in characters {
+        if character.conditions.contains(&Condition::Raging) {
+            let conditions = character
+                .conditions
+                .iter()
+                .copied()
+                .filter(|&condition| condition != Condition::Raging)
+                .collect();
+            character::manager::update_character_conditions(db, character, conditions).await?;
+        }
+    }
+
     db.encounters()
         .update_encounter_state(encounter, EncounterState::Finished)
         .await?;
@@ -157,5 +310,348 @@ pub async fn begin_encounter(
         )
         .await?;
 
+    let encounter = take_ai_turns(db, campaign, encounter).await?;
+
     Ok(encounter.character_ids)
 }
+
+#[tracing::instrument(skip(db))]
+pub async fn end_turn(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: Encounter,
+    character_id: CharacterId,
+) -> Result<Encounter, Error> {
+    let current_character_id = match encounter.state {
+        EncounterState::Turn { character_id, .. } => character_id,
+        _ => {
+            return Err(Error::EncounterHasNotBegun {
+                campaign_id: campaign.id,
+                encounter_id: encounter.id,
+            })
+        }
+    };
+
+    if current_character_id != character_id {
+        return Err(Error::NotThisPlayersTurn {
+            campaign_id: campaign.id,
+            encounter_id: encounter.id,
+            request_character_id: character_id,
+            current_character_id,
+        });
+    }
+
+    let encounter = advance_turn(db, campaign, encounter).await?;
+
+    take_ai_turns(db, campaign, encounter).await
+}
+
+// the characters the server controls play their turns as they come up, for a
+// round at most so an encounter of only those doesn't run on forever
+async fn take_ai_turns(
+    db: &dyn Database,
+    campaign: &Campaign,
+    mut encounter: Encounter,
+) -> Result<Encounter, Error> {
+    for _ in 0..encounter.character_ids.len() {
+        let character_id = match encounter.state {
+            EncounterState::Turn { character_id, .. } => character_id,
+            _ => break,
+        };
+
+        match character::manager::get_character_by_id(db, campaign, character_id).await? {
+            Some(character) if character.ai_controlled => {}
+            _ => break,
+        }
+
+        // a turn the server can't play is passed on, it isn't the fault of the
+        // player whose request got here
+        if let Err(error) = operation::manager::take_ai_turn(db, campaign, &encounter).await {
+            tracing::warn!(%character_id, ?error, "could not take an AI controlled turn");
+        }
+        encounter = advance_turn(db, campaign, encounter).await?;
+    }
+
+    Ok(encounter)
+}
+
+// the turn passes down the initiative order, starting a new round at the top
+async fn advance_turn(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: Encounter,
+) -> Result<Encounter, Error> {
+    let (round, character_id) = match encounter.state {
+        EncounterState::Turn {
+            round,
+            character_id,
+        } => (round, character_id),
+        _ => {
+            return Err(Error::EncounterHasNotBegun {
+                campaign_id: campaign.id,
+                encounter_id: encounter.id,
+            })
+        }
+    };
+
+    let next = encounter
+        .character_ids
+        .iter()
+        .position(|&id| id == character_id)
+        .and_then(|index| encounter.character_ids.get(index + 1));
+    let state = match next {
+        Some(&next_character_id) => EncounterState::Turn {
+            round,
+            character_id: next_character_id,
+        },
+        None => EncounterState::Turn {
+            round: round + 1,
+            character_id: *encounter.character_ids.first().ok_or(
+                Error::NoCharactersInEncounter {
+                    campaign_id: campaign.id,
+                    encounter_id: encounter.id,
+                },
+            )?,
+        },
+    };
+
+    db.encounters()
+        .update_encounter_state(encounter, state)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::test::campaign;
+    use crate::character::test::character;
+    use crate::character::Position;
+    use crate::database::test::MockDatabase;
+    use crate::encounter::test::encounter;
+    use crate::monster::test::goblin_instance;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn deleted_characters_are_left_out_of_difficulty() {
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let deleted = character(campaign.id);
+        let goblin = goblin_instance(campaign.id);
+        let mut encounter = encounter(campaign.id, vec![fighter.id, deleted.id, goblin.id]);
+        encounter.factions[2].faction = Faction::Enemies;
+
+        let difficulty = get_existing_encounter_difficulty(&encounter, &[fighter, goblin]);
+
+        assert_eq!(difficulty.party_thresholds.easy, 25);
+        assert_eq!(difficulty.total_experience, 50);
+    }
+
+    #[actix_rt::test]
+    async fn ending_a_turn_plays_the_ai_controlled_characters_next() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let mut goblin = goblin_instance(campaign.id);
+        goblin.ai_controlled = true;
+        goblin.position = Some(Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        let mut encounter = encounter(campaign.id, vec![fighter.id, goblin.id]);
+        encounter.state = EncounterState::Turn {
+            round: 0,
+            character_id: fighter.id,
+        };
+
+        let characters = vec![fighter.clone(), goblin.clone()];
+        let characters_clone = characters.clone();
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(characters_clone.clone()));
+        db.characters.on_fetch_character_by_campaign_and_id = Box::new(move |_, id| {
+            Ok(characters
+                .iter()
+                .find(|character| character.id == id)
+                .cloned())
+        });
+
+        let called_fetch_operations = Arc::new(Mutex::new(false));
+        let called_fetch_operations_clone = Arc::clone(&called_fetch_operations);
+        let goblin_id = goblin.id;
+        db.operations.on_fetch_operations_by_turn = Box::new(move |_, _, character_id| {
+            assert_eq!(character_id, goblin_id);
+            *called_fetch_operations_clone.lock().unwrap() = true;
+            Ok(vec![])
+        });
+        db.encounters.on_update_encounter_state = Box::new(|mut encounter, state| {
+            encounter.state = state;
+            Ok(encounter)
+        });
+
+        let encounter = end_turn(&db, &campaign, encounter, fighter.id)
+            .await
+            .unwrap();
+
+        assert!(
+            *called_fetch_operations.lock().unwrap(),
+            "db.fetch_operations_by_turn was not called"
+        );
+        assert_eq!(
+            encounter.state,
+            EncounterState::Turn {
+                round: 1,
+                character_id: fighter.id,
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    async fn finishing_an_encounter_ends_rage() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let mut barbarian = character(campaign.id);
+        barbarian.conditions = vec![Condition::Raging, Condition::Poisoned];
+        let encounter = encounter(campaign.id, vec![barbarian.id]);
+
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(vec![barbarian.clone()]));
+        let updated_conditions = Arc::new(Mutex::new(None));
+        let updated_conditions_clone = Arc::clone(&updated_conditions);
+        db.characters.on_update_character_conditions = Box::new(move |character, conditions| {
+            *updated_conditions_clone.lock().unwrap() = Some(conditions);
+            Ok(character)
+        });
+        db.encounters.on_update_encounter_state = Box::new(|mut encounter, state| {
+            encounter.state = state;
+            Ok(encounter)
+        });
+
+        finish_encounter(&db, &campaign, encounter).await.unwrap();
+
+        let conditions = updated_conditions.lock().unwrap().clone();
+        assert_eq!(
+            conditions.expect("db.update_character_conditions was not called"),
+            vec![Condition::Poisoned]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn factions_are_only_given_to_the_roster() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let bystander = character(campaign.id);
+        let fighter_id = fighter.id;
+        let bystander_id = bystander.id;
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(vec![fighter.clone(), bystander.clone()]));
+        let called_insert = Arc::new(Mutex::new(false));
+        let called_insert_clone = Arc::clone(&called_insert);
+        db.encounters.on_insert_encounter = Box::new(move |_| {
+            *called_insert_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let factions = vec![Participant {
+            character_id: bystander_id,
+            faction: Faction::Enemies,
+        }];
+        let result = create_encounter(&db, &campaign, vec![fighter_id], factions).await;
+
+        match result {
+            Err(Error::FactionForCharacterNotInEncounter {
+                campaign_id,
+                character_id,
+            }) => {
+                assert_eq!(campaign_id, campaign.id);
+                assert_eq!(character_id, bystander_id);
+            }
+            _ => panic!("expected the faction to be rejected"),
+        }
+        assert!(
+            !*called_insert.lock().unwrap(),
+            "db.insert_encounter was called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn characters_can_only_be_on_one_side() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let fighter_id = fighter.id;
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(vec![fighter.clone()]));
+        let called_insert = Arc::new(Mutex::new(false));
+        let called_insert_clone = Arc::clone(&called_insert);
+        db.encounters.on_insert_encounter = Box::new(move |_| {
+            *called_insert_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let factions = vec![
+            Participant {
+                character_id: fighter_id,
+                faction: Faction::Party,
+            },
+            Participant {
+                character_id: fighter_id,
+                faction: Faction::Enemies,
+            },
+        ];
+        let result = create_encounter(&db, &campaign, vec![fighter_id], factions).await;
+
+        assert!(
+            matches!(result, Err(Error::DuplicateFactionForCharacter { character_id, .. }) if character_id == fighter_id),
+            "expected the second faction to be rejected"
+        );
+        assert!(
+            !*called_insert.lock().unwrap(),
+            "db.insert_encounter was called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn a_failed_ai_turn_is_passed_on() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let fighter = character(campaign.id);
+        let mut goblin = goblin_instance(campaign.id);
+        goblin.ai_controlled = true;
+        let mut encounter = encounter(campaign.id, vec![fighter.id, goblin.id]);
+        encounter.state = EncounterState::Turn {
+            round: 0,
+            character_id: fighter.id,
+        };
+
+        // the goblin was never placed, so it can't take its turn
+        let characters = vec![fighter.clone(), goblin];
+        let characters_clone = characters.clone();
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(characters_clone.clone()));
+        db.characters.on_fetch_character_by_campaign_and_id = Box::new(move |_, id| {
+            Ok(characters
+                .iter()
+                .find(|character| character.id == id)
+                .cloned())
+        });
+        db.encounters.on_update_encounter_state = Box::new(|mut encounter, state| {
+            encounter.state = state;
+            Ok(encounter)
+        });
+
+        let encounter = end_turn(&db, &campaign, encounter, fighter.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            encounter.state,
+            EncounterState::Turn {
+                round: 1,
+                character_id: fighter.id,
+            }
+        );
+    }
+}
@@ -2,10 +2,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::CampaignId;
-use crate::character::CharacterId;
+use crate::character::{Character, CharacterId};
 use crate::typedid::{TypedId, TypedIdMarker};
 
 pub mod db;
+pub mod difficulty;
 pub mod endpoints;
 pub mod manager;
 pub use endpoints::*;
@@ -23,9 +24,27 @@ pub struct Encounter {
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub modified_at: DateTime<Utc>,
     pub character_ids: Vec<CharacterId>,
+    #[serde(default)]
+    pub factions: Vec<Participant>,
     pub state: EncounterState,
 }
 
+impl Encounter {
+    pub fn faction(&self, character: &Character) -> Faction {
+        Faction::of(character, &self.factions)
+    }
+
+    pub fn are_hostile(&self, character: &Character, other: &Character) -> bool {
+        self.faction(character).is_hostile_to(self.faction(other))
+    }
+
+    pub fn are_allies(&self, character: &Character, other: &Character) -> bool {
+        let faction = self.faction(character);
+
+        character.id != other.id && faction != Faction::Neutral && faction == self.faction(other)
+    }
+}
+
 impl TypedIdMarker for Encounter {
     fn tag() -> &'static str {
         "ENC"
@@ -42,3 +61,94 @@ pub enum EncounterState {
     },
     Finished,
 }
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Faction {
+    Party,
+    Enemies,
+    Neutral,
+}
+
+impl Faction {
+    // creatures instantiated from a stat block oppose the party unless told
+    // otherwise, which includes participants of encounters from before
+    // factions were tracked
+    pub fn of(character: &Character, participants: &[Participant]) -> Faction {
+        participants
+            .iter()
+            .find(|participant| participant.character_id == character.id)
+            .map(|participant| participant.faction)
+            .unwrap_or(if character.monster.is_some() {
+                Faction::Enemies
+            } else {
+                Faction::Party
+            })
+    }
+
+    // neutral creatures aren't hostile to anyone until they pick a side
+    pub fn is_hostile_to(self, other: Faction) -> bool {
+        matches!(
+            (self, other),
+            (Faction::Party, Faction::Enemies) | (Faction::Enemies, Faction::Party)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Participant {
+    pub character_id: CharacterId,
+    pub faction: Faction,
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    // an encounter still rolling initiative, everyone on the party's side
+    pub fn encounter(campaign_id: CampaignId, character_ids: Vec<CharacterId>) -> Encounter {
+        let now = Utc::now();
+        Encounter {
+            id: EncounterId::new(),
+            campaign_id,
+            created_at: now,
+            modified_at: now,
+            factions: character_ids
+                .iter()
+                .map(|&character_id| Participant {
+                    character_id,
+                    faction: Faction::Party,
+                })
+                .collect(),
+            character_ids,
+            state: EncounterState::Initiative,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_opposing_sides_are_hostile() {
+        assert!(Faction::Party.is_hostile_to(Faction::Enemies));
+        assert!(Faction::Enemies.is_hostile_to(Faction::Party));
+        assert!(!Faction::Party.is_hostile_to(Faction::Party));
+        assert!(!Faction::Neutral.is_hostile_to(Faction::Enemies));
+        assert!(!Faction::Party.is_hostile_to(Faction::Neutral));
+    }
+
+    #[test]
+    fn participants_without_a_faction_take_their_default_side() {
+        let campaign_id = CampaignId::new();
+        let fighter = crate::character::test::character(campaign_id);
+        let goblin = crate::monster::test::goblin_instance(campaign_id);
+        let mut encounter = test::encounter(campaign_id, vec![fighter.id, goblin.id]);
+        encounter.factions.clear();
+
+        assert_eq!(encounter.faction(&fighter), Faction::Party);
+        assert_eq!(encounter.faction(&goblin), Faction::Enemies);
+        assert!(encounter.are_hostile(&fighter, &goblin));
+    }
+}
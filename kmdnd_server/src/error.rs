@@ -10,11 +10,15 @@ use mongodb::error::Error as DatabaseError;
 use serde::{Serialize, Serializer};
 
 use crate::campaign::CampaignId;
+use crate::character::class::{Class, LimitedFeature, Subclass};
+use crate::character::race::{DragonType, Race, Subrace};
 use crate::character::CharacterId;
+use crate::currency::{CopperPieces, Purse};
 use crate::encounter::EncounterId;
-use crate::item::ItemId;
+use crate::item::{Dice, ItemId};
+use crate::monster::MonsterId;
 use crate::operation::spell::SpellTargetType;
-use crate::operation::{InteractionId, Legality, OperationId, SpellTarget};
+use crate::operation::{AbilityType, InteractionId, Legality, OperationId, SpellTarget};
 use crate::violations::Violation;
 
 #[derive(Debug, Serialize, Derivative)]
@@ -54,6 +58,17 @@ pub enum Error {
         operation_id: OperationId,
         interaction_id: InteractionId,
     },
+    OperationNotFoundInCampaign {
+        campaign_id: CampaignId,
+        operation_id: OperationId,
+    },
+    EncounterNotFoundInCampaign {
+        campaign_id: CampaignId,
+        encounter_id: EncounterId,
+    },
+    MonsterDoesNotExist {
+        monster_id: MonsterId,
+    },
 
     // 409
     ConcurrentModificationDetected,
@@ -93,6 +108,178 @@ pub enum Error {
     ItemIsNotAWeapon {
         item_id: ItemId,
     },
+    AttackHasNoTargets,
+    CharacterDoesNotHaveFeature {
+        character_id: CharacterId,
+        feature: LimitedFeature,
+    },
+    CharacterLacksExperience {
+        character_id: CharacterId,
+        experience: i32,
+        required_experience: i32,
+    },
+    CharacterAtMaximumLevel {
+        character_id: CharacterId,
+    },
+    LevelUpHitPointRollOutOfRange {
+        dice: Dice,
+        result: i32,
+    },
+    LevelUpInvalidSubclass {
+        class: Class,
+        subclass: Option<Subclass>,
+    },
+    LevelUpInvalidAbilityScoreImprovement {
+        expected_count: usize,
+        provided_count: usize,
+    },
+    AbilityScoreExceedsMaximum {
+        ability: AbilityType,
+        score: i32,
+    },
+    ExperienceHasNoRecipients,
+    CharacterLacksHitDice {
+        character_id: CharacterId,
+        dice: Dice,
+    },
+    InvalidSubrace {
+        race: Race,
+        subrace: Option<Subrace>,
+    },
+    InvalidDraconicAncestry {
+        race: Race,
+        draconic_ancestry: Option<DragonType>,
+    },
+    AbilityScoresDoNotMatchStandardArray,
+    AbilityScoreOutOfPointBuyRange {
+        ability: AbilityType,
+        score: i32,
+    },
+    AbilityScoresExceedPointBuy {
+        points_spent: i32,
+    },
+    InvalidAbilityScorePriority {
+        priority: Vec<AbilityType>,
+    },
+    InvalidProficiencyChoice {
+        choices: Vec<String>,
+        expected_count: usize,
+    },
+    AbilityScoreOutOfRange {
+        ability: AbilityType,
+        score: i32,
+    },
+    InvalidHitPoints {
+        current_hit_points: i32,
+        maximum_hit_points: i32,
+    },
+    CharacterInCurrentEncounter {
+        character_id: CharacterId,
+    },
+    ItemNotInInventory {
+        character_id: CharacterId,
+        item_id: ItemId,
+    },
+    InsufficientItemQuantity {
+        character_id: CharacterId,
+        item_id: ItemId,
+        quantity: i32,
+    },
+    InvalidItemQuantity {
+        quantity: i32,
+    },
+    InventoryTransferToSelf {
+        character_id: CharacterId,
+    },
+    InsufficientFunds {
+        character_id: CharacterId,
+        price: CopperPieces,
+    },
+    TreasuryLacksCoins {
+        campaign_id: CampaignId,
+        coins: Purse,
+    },
+    PurseLacksCoins {
+        character_id: CharacterId,
+        coins: Purse,
+    },
+    InvalidCoins {
+        coins: Purse,
+    },
+    InvalidItem {
+        reason: String,
+    },
+    ItemInUse {
+        item_id: ItemId,
+        character_ids: Vec<CharacterId>,
+    },
+    AttunementLimitExceeded {
+        character_id: CharacterId,
+        maximum: usize,
+    },
+    ItemDoesNotRequireAttunement {
+        item_id: ItemId,
+    },
+    ItemNotAttuned {
+        character_id: CharacterId,
+        item_id: ItemId,
+    },
+    ItemHasNoCharges {
+        item_id: ItemId,
+    },
+    InsufficientItemCharges {
+        item_id: ItemId,
+        remaining: i32,
+    },
+    ItemIsNotConsumable {
+        item_id: ItemId,
+    },
+    ItemIsNotAContainer {
+        item_id: ItemId,
+    },
+    ContainerCapacityExceeded {
+        container_id: ItemId,
+        capacity: i32,
+    },
+    InvalidContainer {
+        item_id: ItemId,
+        container_id: ItemId,
+    },
+    MonsterActionNotFound {
+        character_id: CharacterId,
+        name: String,
+    },
+    InvalidMonster {
+        reason: String,
+    },
+    InvalidMonsterCount {
+        count: i32,
+    },
+    CharacterNotOwnedByCampaign {
+        character_id: CharacterId,
+    },
+    CharacterNotAiControlled {
+        character_id: CharacterId,
+    },
+    EncounterHasNotBegun {
+        campaign_id: CampaignId,
+        encounter_id: EncounterId,
+    },
+    InvalidStartingEquipmentQuantity {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    FactionForCharacterNotInEncounter {
+        campaign_id: CampaignId,
+        character_id: CharacterId,
+    },
+    StackedContainerHasContents {
+        container_id: ItemId,
+    },
+    DuplicateFactionForCharacter {
+        campaign_id: CampaignId,
+        character_id: CharacterId,
+    },
     CharacterDoesNotHavePosition {
         character_id: CharacterId,
     },
@@ -111,6 +298,21 @@ pub enum Error {
         #[derivative(PartialEq = "ignore")]
         provided_type: SpellTarget,
     },
+    CastUsesWrongTargetCount {
+        expected_count: usize,
+        provided_count: usize,
+    },
+    CastUsesInvalidSlotLevel {
+        spell_level: i32,
+        slot_level: i32,
+    },
+    SpellIsNotRitual {
+        name: String,
+    },
+    OperationIsInEncounter {
+        operation_id: OperationId,
+        encounter_id: EncounterId,
+    },
     OperationViolatesRules {
         #[derivative(PartialEq = "ignore")]
         violations: Vec<Violation>,
@@ -139,6 +341,10 @@ pub enum Error {
     FailedToSerializeToBson(#[derivative(PartialEq = "ignore")] BsonError),
     #[serde(serialize_with = "display")]
     IoError(#[derivative(PartialEq = "ignore")] IoError),
+    ImportFileInvalid {
+        file: String,
+        reason: String,
+    },
 }
 
 impl Error {
@@ -155,6 +361,9 @@ impl Error {
             Error::ItemDoesNotExist { .. } => "E4041004",
             Error::OperationNotFound { .. } => "E4041005",
             Error::InteractionNotFound { .. } => "E4041006",
+            Error::OperationNotFoundInCampaign { .. } => "E4041007",
+            Error::EncounterNotFoundInCampaign { .. } => "E4041008",
+            Error::MonsterDoesNotExist { .. } => "E4041009",
             Error::ConcurrentModificationDetected => "E4091000",
             Error::CurrentEncounterAlreadyExists { .. } => "E4091001",
             Error::CharacterNotInCampaign { .. } => "E4091002",
@@ -170,6 +379,59 @@ impl Error {
             Error::CastUsesWrongTargetType { .. } => "E4091014",
             Error::OperationViolatesRules { .. } => "E4091015",
             Error::OperationIsNotPending { .. } => "E4091016",
+            Error::CastUsesWrongTargetCount { .. } => "E4091017",
+            Error::CastUsesInvalidSlotLevel { .. } => "E4091018",
+            Error::SpellIsNotRitual { .. } => "E4091019",
+            Error::OperationIsInEncounter { .. } => "E4091020",
+            Error::AttackHasNoTargets => "E4091021",
+            Error::CharacterDoesNotHaveFeature { .. } => "E4091022",
+            Error::CharacterLacksExperience { .. } => "E4091023",
+            Error::CharacterAtMaximumLevel { .. } => "E4091024",
+            Error::LevelUpHitPointRollOutOfRange { .. } => "E4091025",
+            Error::LevelUpInvalidSubclass { .. } => "E4091026",
+            Error::LevelUpInvalidAbilityScoreImprovement { .. } => "E4091027",
+            Error::AbilityScoreExceedsMaximum { .. } => "E4091028",
+            Error::ExperienceHasNoRecipients => "E4091029",
+            Error::CharacterLacksHitDice { .. } => "E4091030",
+            Error::InvalidSubrace { .. } => "E4091031",
+            Error::InvalidDraconicAncestry { .. } => "E4091032",
+            Error::AbilityScoresDoNotMatchStandardArray => "E4091033",
+            Error::AbilityScoreOutOfPointBuyRange { .. } => "E4091034",
+            Error::AbilityScoresExceedPointBuy { .. } => "E4091035",
+            Error::InvalidAbilityScorePriority { .. } => "E4091036",
+            Error::InvalidProficiencyChoice { .. } => "E4091037",
+            Error::AbilityScoreOutOfRange { .. } => "E4091038",
+            Error::InvalidHitPoints { .. } => "E4091039",
+            Error::CharacterInCurrentEncounter { .. } => "E4091040",
+            Error::ItemNotInInventory { .. } => "E4091041",
+            Error::InsufficientItemQuantity { .. } => "E4091042",
+            Error::InvalidItemQuantity { .. } => "E4091043",
+            Error::InventoryTransferToSelf { .. } => "E4091044",
+            Error::InsufficientFunds { .. } => "E4091045",
+            Error::TreasuryLacksCoins { .. } => "E4091046",
+            Error::PurseLacksCoins { .. } => "E4091047",
+            Error::InvalidCoins { .. } => "E4091048",
+            Error::InvalidItem { .. } => "E4091049",
+            Error::ItemInUse { .. } => "E4091050",
+            Error::AttunementLimitExceeded { .. } => "E4091051",
+            Error::ItemDoesNotRequireAttunement { .. } => "E4091052",
+            Error::ItemNotAttuned { .. } => "E4091053",
+            Error::ItemHasNoCharges { .. } => "E4091054",
+            Error::InsufficientItemCharges { .. } => "E4091055",
+            Error::ItemIsNotConsumable { .. } => "E4091056",
+            Error::ItemIsNotAContainer { .. } => "E4091057",
+            Error::ContainerCapacityExceeded { .. } => "E4091058",
+            Error::InvalidContainer { .. } => "E4091059",
+            Error::MonsterActionNotFound { .. } => "E4091060",
+            Error::InvalidMonster { .. } => "E4091061",
+            Error::InvalidMonsterCount { .. } => "E4091062",
+            Error::CharacterNotOwnedByCampaign { .. } => "E4091063",
+            Error::CharacterNotAiControlled { .. } => "E4091064",
+            Error::EncounterHasNotBegun { .. } => "E4091065",
+            Error::InvalidStartingEquipmentQuantity { .. } => "E4091066",
+            Error::FactionForCharacterNotInEncounter { .. } => "E4091067",
+            Error::StackedContainerHasContents { .. } => "E4091068",
+            Error::DuplicateFactionForCharacter { .. } => "E4091069",
             Error::ExistentialState(_) => "E5002000",
             Error::CampaignExpected { .. } => "E5001001",
             Error::CharacterExpectedInCampaign { .. } => "E5001002",
@@ -178,6 +440,7 @@ impl Error {
             Error::FailedDatabaseCall(_) => "E5002001",
             Error::FailedToSerializeToBson(_) => "E5002002",
             Error::IoError(_) => "E5002003",
+            Error::ImportFileInvalid { .. } => "E5002004",
         }
     }
 
@@ -198,6 +461,13 @@ impl Error {
             Error::ItemDoesNotExist { .. } => "The requested item was not found",
             Error::OperationNotFound { .. } => "The requested operation was not found",
             Error::InteractionNotFound { .. } => "The requested interaction was not found",
+            Error::OperationNotFoundInCampaign { .. } => {
+                "The requested operation was not found in the campaign"
+            }
+            Error::EncounterNotFoundInCampaign { .. } => {
+                "The requested encounter was not found in the campaign"
+            }
+            Error::MonsterDoesNotExist { .. } => "The requested monster was not found",
             Error::ConcurrentModificationDetected => {
                 "The server detected a concurrent modification"
             }
@@ -223,6 +493,107 @@ impl Error {
                 "The requested player does not have permission for this turn"
             }
             Error::ItemIsNotAWeapon { .. } => "The provided item was expected to be a weapon",
+            Error::AttackHasNoTargets => "The requested attack does not have any targets",
+            Error::CharacterDoesNotHaveFeature { .. } => {
+                "The requested character does not have the requested feature"
+            }
+            Error::CharacterLacksExperience { .. } => {
+                "The requested character does not have enough experience to level up"
+            }
+            Error::CharacterAtMaximumLevel { .. } => {
+                "The requested character is already at the maximum level"
+            }
+            Error::LevelUpHitPointRollOutOfRange { .. } => {
+                "The provided hit point roll is not possible with the hit die of the class"
+            }
+            Error::LevelUpInvalidSubclass { .. } => {
+                "The provided subclass cannot be chosen for the class at this level"
+            }
+            Error::LevelUpInvalidAbilityScoreImprovement { .. } => {
+                "The provided ability score improvements are not allowed at this level"
+            }
+            Error::AbilityScoreExceedsMaximum { .. } => {
+                "The resulting ability score would exceed the maximum"
+            }
+            Error::ExperienceHasNoRecipients => {
+                "The experience award does not have any characters to receive it"
+            }
+            Error::CharacterLacksHitDice { .. } => {
+                "The requested character does not have the hit dice remaining"
+            }
+            Error::InvalidSubrace { .. } => "The provided subrace is not valid for the race",
+            Error::InvalidDraconicAncestry { .. } => {
+                "The provided draconic ancestry is not valid for the race"
+            }
+            Error::AbilityScoresDoNotMatchStandardArray => {
+                "The ability scores do not match the standard array"
+            }
+            Error::AbilityScoreOutOfPointBuyRange { .. } => {
+                "The ability score cannot be purchased with point buy"
+            }
+            Error::AbilityScoresExceedPointBuy { .. } => {
+                "The ability scores cost more than the point buy budget"
+            }
+            Error::InvalidAbilityScorePriority { .. } => {
+                "The ability score priority must list each ability exactly once"
+            }
+            Error::InvalidProficiencyChoice { .. } => "The chosen proficiencies are not valid",
+            Error::AbilityScoreOutOfRange { .. } => "The ability score is out of range",
+            Error::InvalidHitPoints { .. } => "The hit points are not valid",
+            Error::CharacterInCurrentEncounter { .. } => {
+                "The character is in the current encounter"
+            }
+            Error::ItemNotInInventory { .. } => "The character does not have the item",
+            Error::InsufficientItemQuantity { .. } => {
+                "The character does not have enough of the item"
+            }
+            Error::InvalidItemQuantity { .. } => "The item quantity is not valid",
+            Error::InventoryTransferToSelf { .. } => "Cannot transfer to the same character",
+            Error::InsufficientFunds { .. } => "The character cannot afford the price",
+            Error::TreasuryLacksCoins { .. } => "The party treasury does not have the coins",
+            Error::PurseLacksCoins { .. } => "The character does not have the coins",
+            Error::InvalidCoins { .. } => "The coins are not valid",
+            Error::InvalidItem { .. } => "The item is not valid",
+            Error::ItemInUse { .. } => "The item is still in use by characters",
+            Error::AttunementLimitExceeded { .. } => {
+                "A character cannot be attuned to that many items"
+            }
+            Error::ItemDoesNotRequireAttunement { .. } => "The item does not require attunement",
+            Error::ItemNotAttuned { .. } => "The character is not attuned to the item",
+            Error::ItemHasNoCharges { .. } => "The item does not have charges",
+            Error::InsufficientItemCharges { .. } => {
+                "The item does not have enough charges remaining"
+            }
+            Error::ItemIsNotConsumable { .. } => "The provided item was expected to be consumable",
+            Error::ItemIsNotAContainer { .. } => "The provided item was expected to be a container",
+            Error::ContainerCapacityExceeded { .. } => "The container cannot hold that much weight",
+            Error::InvalidContainer { .. } => "An item cannot be stored inside itself",
+            Error::MonsterActionNotFound { .. } => {
+                "The creature does not have the requested action"
+            }
+            Error::InvalidMonster { .. } => "The monster is not valid",
+            Error::InvalidMonsterCount { .. } => {
+                "The number of monsters to instantiate is not valid"
+            }
+            Error::CharacterNotOwnedByCampaign { .. } => {
+                "Only characters owned by the campaign can be controlled by the server"
+            }
+            Error::CharacterNotAiControlled { .. } => {
+                "The character whose turn it is is not controlled by the server"
+            }
+            Error::EncounterHasNotBegun { .. } => "The encounter is not taking turns",
+            Error::InvalidStartingEquipmentQuantity { .. } => {
+                "Starting equipment must have a quantity of at least one"
+            }
+            Error::FactionForCharacterNotInEncounter { .. } => {
+                "A faction was given for a character who is not in the encounter"
+            }
+            Error::StackedContainerHasContents { .. } => {
+                "A stack of containers cannot hold items, only a single container can"
+            }
+            Error::DuplicateFactionForCharacter { .. } => {
+                "A character can only be on one side of an encounter"
+            }
             Error::CharacterDoesNotHavePosition { .. } => {
                 "The requested character does not have a position"
             }
@@ -237,6 +608,16 @@ impl Error {
             Error::OperationIsNotPending { .. } => {
                 "The requested operation's legality is not pending"
             }
+            Error::CastUsesWrongTargetCount { .. } => {
+                "The provided targets do not match the number the spell expects"
+            }
+            Error::CastUsesInvalidSlotLevel { .. } => {
+                "The provided spell slot is lower than the spell's level"
+            }
+            Error::SpellIsNotRitual { .. } => "The requested spell cannot be cast as a ritual",
+            Error::OperationIsInEncounter { .. } => {
+                "The requested operation belongs to an encounter"
+            }
             Error::ExistentialState(_) => "The server detected an invalid state",
             Error::CampaignExpected { .. } => "The server expected a campaign to exist",
             Error::CharacterExpectedInCampaign { .. } => {
@@ -251,6 +632,7 @@ impl Error {
                 "An error occurred when serializing an object to bson"
             }
             Error::IoError(_) => "An error occurred during an I/O operation",
+            Error::ImportFileInvalid { .. } => "An import file could not be parsed",
         }
     }
 }
@@ -269,6 +651,9 @@ impl ResponseError for Error {
             Error::ItemDoesNotExist { .. } => StatusCode::NOT_FOUND,
             Error::OperationNotFound { .. } => StatusCode::NOT_FOUND,
             Error::InteractionNotFound { .. } => StatusCode::NOT_FOUND,
+            Error::OperationNotFoundInCampaign { .. } => StatusCode::NOT_FOUND,
+            Error::EncounterNotFoundInCampaign { .. } => StatusCode::NOT_FOUND,
+            Error::MonsterDoesNotExist { .. } => StatusCode::NOT_FOUND,
             Error::ConcurrentModificationDetected => StatusCode::CONFLICT,
             Error::CurrentEncounterAlreadyExists { .. } => StatusCode::CONFLICT,
             Error::CharacterNotInCampaign { .. } => StatusCode::CONFLICT,
@@ -278,12 +663,65 @@ impl ResponseError for Error {
             Error::NoCharactersInEncounter { .. } => StatusCode::CONFLICT,
             Error::NotThisPlayersTurn { .. } => StatusCode::CONFLICT,
             Error::ItemIsNotAWeapon { .. } => StatusCode::CONFLICT,
+            Error::AttackHasNoTargets => StatusCode::CONFLICT,
+            Error::CharacterDoesNotHaveFeature { .. } => StatusCode::CONFLICT,
+            Error::CharacterLacksExperience { .. } => StatusCode::CONFLICT,
+            Error::CharacterAtMaximumLevel { .. } => StatusCode::CONFLICT,
+            Error::LevelUpHitPointRollOutOfRange { .. } => StatusCode::CONFLICT,
+            Error::LevelUpInvalidSubclass { .. } => StatusCode::CONFLICT,
+            Error::LevelUpInvalidAbilityScoreImprovement { .. } => StatusCode::CONFLICT,
+            Error::AbilityScoreExceedsMaximum { .. } => StatusCode::CONFLICT,
+            Error::ExperienceHasNoRecipients => StatusCode::CONFLICT,
+            Error::CharacterLacksHitDice { .. } => StatusCode::CONFLICT,
+            Error::InvalidSubrace { .. } => StatusCode::CONFLICT,
+            Error::InvalidDraconicAncestry { .. } => StatusCode::CONFLICT,
+            Error::AbilityScoresDoNotMatchStandardArray => StatusCode::CONFLICT,
+            Error::AbilityScoreOutOfPointBuyRange { .. } => StatusCode::CONFLICT,
+            Error::AbilityScoresExceedPointBuy { .. } => StatusCode::CONFLICT,
+            Error::InvalidAbilityScorePriority { .. } => StatusCode::CONFLICT,
+            Error::InvalidProficiencyChoice { .. } => StatusCode::CONFLICT,
+            Error::AbilityScoreOutOfRange { .. } => StatusCode::CONFLICT,
+            Error::InvalidHitPoints { .. } => StatusCode::CONFLICT,
+            Error::CharacterInCurrentEncounter { .. } => StatusCode::CONFLICT,
+            Error::ItemNotInInventory { .. } => StatusCode::CONFLICT,
+            Error::InsufficientItemQuantity { .. } => StatusCode::CONFLICT,
+            Error::InvalidItemQuantity { .. } => StatusCode::CONFLICT,
+            Error::InventoryTransferToSelf { .. } => StatusCode::CONFLICT,
+            Error::InsufficientFunds { .. } => StatusCode::CONFLICT,
+            Error::TreasuryLacksCoins { .. } => StatusCode::CONFLICT,
+            Error::PurseLacksCoins { .. } => StatusCode::CONFLICT,
+            Error::InvalidCoins { .. } => StatusCode::CONFLICT,
+            Error::InvalidItem { .. } => StatusCode::CONFLICT,
+            Error::ItemInUse { .. } => StatusCode::CONFLICT,
+            Error::AttunementLimitExceeded { .. } => StatusCode::CONFLICT,
+            Error::ItemDoesNotRequireAttunement { .. } => StatusCode::CONFLICT,
+            Error::ItemNotAttuned { .. } => StatusCode::CONFLICT,
+            Error::ItemHasNoCharges { .. } => StatusCode::CONFLICT,
+            Error::InsufficientItemCharges { .. } => StatusCode::CONFLICT,
+            Error::ItemIsNotConsumable { .. } => StatusCode::CONFLICT,
+            Error::ItemIsNotAContainer { .. } => StatusCode::CONFLICT,
+            Error::ContainerCapacityExceeded { .. } => StatusCode::CONFLICT,
+            Error::InvalidContainer { .. } => StatusCode::CONFLICT,
+            Error::MonsterActionNotFound { .. } => StatusCode::CONFLICT,
+            Error::InvalidMonster { .. } => StatusCode::CONFLICT,
+            Error::InvalidMonsterCount { .. } => StatusCode::CONFLICT,
+            Error::CharacterNotOwnedByCampaign { .. } => StatusCode::CONFLICT,
+            Error::CharacterNotAiControlled { .. } => StatusCode::CONFLICT,
+            Error::EncounterHasNotBegun { .. } => StatusCode::CONFLICT,
+            Error::InvalidStartingEquipmentQuantity { .. } => StatusCode::CONFLICT,
+            Error::FactionForCharacterNotInEncounter { .. } => StatusCode::CONFLICT,
+            Error::StackedContainerHasContents { .. } => StatusCode::CONFLICT,
+            Error::DuplicateFactionForCharacter { .. } => StatusCode::CONFLICT,
             Error::CharacterDoesNotHavePosition { .. } => StatusCode::CONFLICT,
             Error::WrongCharacterForInteraction { .. } => StatusCode::CONFLICT,
             Error::SpellDoesNotExist { .. } => StatusCode::CONFLICT,
             Error::CastUsesWrongTargetType { .. } => StatusCode::CONFLICT,
             Error::OperationViolatesRules { .. } => StatusCode::CONFLICT,
             Error::OperationIsNotPending { .. } => StatusCode::CONFLICT,
+            Error::CastUsesWrongTargetCount { .. } => StatusCode::CONFLICT,
+            Error::CastUsesInvalidSlotLevel { .. } => StatusCode::CONFLICT,
+            Error::SpellIsNotRitual { .. } => StatusCode::CONFLICT,
+            Error::OperationIsInEncounter { .. } => StatusCode::CONFLICT,
             Error::ExistentialState(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::CampaignExpected { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Error::CharacterExpectedInCampaign { .. } => StatusCode::INTERNAL_SERVER_ERROR,
@@ -292,6 +730,7 @@ impl ResponseError for Error {
             Error::FailedDatabaseCall(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::FailedToSerializeToBson(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::ImportFileInvalid { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
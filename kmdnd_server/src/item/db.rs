@@ -2,10 +2,11 @@ use async_trait::async_trait;
 use futures::TryStreamExt;
 use mongodb::bson;
 
+use crate::campaign::CampaignId;
 use crate::database::MongoItemStore;
 use crate::error::Error;
 
-use super::{Item, ItemId};
+use super::{Item, ItemFilter, ItemId};
 
 #[async_trait]
 pub trait ItemStore {
@@ -14,6 +15,16 @@ pub trait ItemStore {
     async fn fetch_items(&self) -> Result<Vec<Item>, Error>;
 
     async fn fetch_item_by_id(&self, item_id: ItemId) -> Result<Option<Item>, Error>;
+
+    async fn fetch_items_by_filter(
+        &self,
+        campaign_id: Option<CampaignId>,
+        filter: ItemFilter,
+    ) -> Result<Vec<Item>, Error>;
+
+    async fn update_item(&self, item: Item) -> Result<Item, Error>;
+
+    async fn delete_item(&self, item_id: ItemId) -> Result<(), Error>;
 }
 
 #[async_trait]
@@ -38,4 +49,178 @@ impl ItemStore for MongoItemStore {
 
         Ok(item)
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_items_by_filter(
+        &self,
+        campaign_id: Option<CampaignId>,
+        filter: ItemFilter,
+    ) -> Result<Vec<Item>, Error> {
+        let query = filter_query(campaign_id, filter)?;
+        let items = self.find(query, None).await?.try_collect().await?;
+
+        Ok(items)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_item(&self, item: Item) -> Result<Item, Error> {
+        let result = self
+            .replace_one(bson::doc! { "_id": item.id }, &item, None)
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::ItemDoesNotExist { item_id: item.id });
+        }
+
+        Ok(item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_item(&self, item_id: ItemId) -> Result<(), Error> {
+        self.delete_one(bson::doc! { "_id": item_id }, None).await?;
+
+        Ok(())
+    }
+}
+
+// global items are always included, homebrew only for its own campaign
+fn filter_query(
+    campaign_id: Option<CampaignId>,
+    filter: ItemFilter,
+) -> Result<bson::Document, Error> {
+    let mut query = match campaign_id {
+        Some(campaign_id) => bson::doc! { "campaign_id": { "$in": [null, campaign_id] } },
+        None => bson::doc! { "campaign_id": null },
+    };
+    if let Some(category) = filter.category {
+        query.insert("item_type.type", bson::to_bson(&category)?);
+    }
+    if let Some(damage_type) = filter.damage_type {
+        query.insert("item_type.damage_type", bson::to_bson(&damage_type)?);
+    }
+    if let Some(name) = filter.name {
+        query.insert(
+            "name",
+            bson::doc! { "$regex": escape_regex(&name), "$options": "i" },
+        );
+    }
+
+    Ok(query)
+}
+
+// names are matched as a case-insensitive substring, so any regex syntax in
+// the search needs to be escaped
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if r"\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+pub struct MockItemStore {
+    pub on_insert_item: Box<dyn Fn(&Item) -> Result<(), Error> + Send + Sync>,
+    pub on_fetch_items: Box<dyn Fn() -> Result<Vec<Item>, Error> + Send + Sync>,
+    pub on_fetch_item_by_id: Box<dyn Fn(ItemId) -> Result<Option<Item>, Error> + Send + Sync>,
+    pub on_fetch_items_by_filter:
+        Box<dyn Fn(Option<CampaignId>, ItemFilter) -> Result<Vec<Item>, Error> + Send + Sync>,
+    pub on_update_item: Box<dyn Fn(Item) -> Result<Item, Error> + Send + Sync>,
+    pub on_delete_item: Box<dyn Fn(ItemId) -> Result<(), Error> + Send + Sync>,
+}
+
+#[cfg(test)]
+impl MockItemStore {
+    pub fn new() -> MockItemStore {
+        MockItemStore {
+            on_insert_item: Box::new(|_| unimplemented!("MockItemStore::insert_item")),
+            on_fetch_items: Box::new(|| unimplemented!("MockItemStore::fetch_items")),
+            on_fetch_item_by_id: Box::new(|_| unimplemented!("MockItemStore::fetch_item_by_id")),
+            on_fetch_items_by_filter: Box::new(|_, _| {
+                unimplemented!("MockItemStore::fetch_items_by_filter")
+            }),
+            on_update_item: Box::new(|_| unimplemented!("MockItemStore::update_item")),
+            on_delete_item: Box::new(|_| unimplemented!("MockItemStore::delete_item")),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl ItemStore for MockItemStore {
+    async fn insert_item(&self, item: &Item) -> Result<(), Error> {
+        (self.on_insert_item)(item)
+    }
+
+    async fn fetch_items(&self) -> Result<Vec<Item>, Error> {
+        (self.on_fetch_items)()
+    }
+
+    async fn fetch_item_by_id(&self, item_id: ItemId) -> Result<Option<Item>, Error> {
+        (self.on_fetch_item_by_id)(item_id)
+    }
+
+    async fn fetch_items_by_filter(
+        &self,
+        campaign_id: Option<CampaignId>,
+        filter: ItemFilter,
+    ) -> Result<Vec<Item>, Error> {
+        (self.on_fetch_items_by_filter)(campaign_id, filter)
+    }
+
+    async fn update_item(&self, item: Item) -> Result<Item, Error> {
+        (self.on_update_item)(item)
+    }
+
+    async fn delete_item(&self, item_id: ItemId) -> Result<(), Error> {
+        (self.on_delete_item)(item_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::test::longsword;
+    use crate::item::{DamageType, ItemCategory};
+
+    #[test]
+    fn filters_match_how_items_are_stored() {
+        let campaign_id = CampaignId::new();
+        let item = bson::to_document(&longsword(Some(campaign_id))).unwrap();
+        let filter = ItemFilter {
+            category: Some(ItemCategory::Weapon),
+            damage_type: Some(DamageType::Slashing),
+            name: Some("long.".to_string()),
+        };
+
+        let query = filter_query(Some(campaign_id), filter).unwrap();
+
+        let item_type = item.get_document("item_type").unwrap();
+        assert_eq!(
+            query.get("item_type.type"),
+            item_type.get("type"),
+            "the category doesn't match the stored item type"
+        );
+        assert_eq!(
+            query.get("item_type.damage_type"),
+            item_type.get("damage_type"),
+            "the damage type doesn't match the stored weapon"
+        );
+        let name = query.get_document("name").unwrap();
+        assert_eq!(name.get_str("$regex").unwrap(), r"long\.");
+        assert_eq!(name.get_str("$options").unwrap(), "i");
+        let campaigns = query.get_document("campaign_id").unwrap();
+        assert_eq!(
+            campaigns.get_array("$in").unwrap(),
+            &vec![bson::Bson::Null, item.get("campaign_id").unwrap().clone()]
+        );
+
+        let query = filter_query(None, ItemFilter::default()).unwrap();
+        assert_eq!(query, bson::doc! { "campaign_id": null });
+    }
 }
@@ -1,37 +1,57 @@
-use actix_web::get;
-use actix_web::web::{Data, Json, Path};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, post, put, HttpResponse};
 use serde::{Deserialize, Serialize};
 
+use crate::campaign::{self, CampaignId};
+use crate::currency::CopperPieces;
 use crate::database::Database;
 use crate::error::Error;
 
-use super::{manager, Item, ItemId, ItemType};
+use super::magic::MagicProperties;
+use super::{manager, Item, ItemFilter, ItemId, ItemType};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemBody {
     pub id: ItemId,
+    pub campaign_id: Option<CampaignId>,
     pub name: String,
     pub weight: i32,
-    pub value: i32,
+    pub value: CopperPieces,
     pub item_type: ItemType,
+    pub magic: Option<MagicProperties>,
 }
 
 impl ItemBody {
     pub fn render(item: Item) -> ItemBody {
         ItemBody {
             id: item.id,
+            campaign_id: item.campaign_id,
             name: item.name,
             weight: item.weight,
             value: item.value,
             item_type: item.item_type,
+            magic: item.magic,
         }
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateItemBody {
+    pub name: String,
+    pub weight: i32,
+    pub value: CopperPieces,
+    pub item_type: ItemType,
+    #[serde(default)]
+    pub magic: Option<MagicProperties>,
+}
+
 #[get("/items")]
 #[tracing::instrument(skip(db))]
-async fn get_items(db: Data<Box<dyn Database>>) -> Result<Json<Vec<ItemBody>>, Error> {
-    let items = manager::get_items(&***db).await?;
+async fn get_items(
+    db: Data<Box<dyn Database>>,
+    query: Query<ItemFilter>,
+) -> Result<Json<Vec<ItemBody>>, Error> {
+    let items = manager::search_items(&***db, None, query.into_inner()).await?;
 
     let body = items.into_iter().map(ItemBody::render).collect();
 
@@ -45,9 +65,188 @@ async fn get_item_by_id(
     params: Path<ItemId>,
 ) -> Result<Json<ItemBody>, Error> {
     let item_id = params.into_inner();
-    let item = manager::get_item_by_id(&***db, item_id)
+    let item = manager::get_item_by_id(&***db, None, item_id)
         .await?
         .ok_or(Error::ItemDoesNotExist { item_id })?;
 
     Ok(Json(ItemBody::render(item)))
 }
+
+#[post("/items")]
+#[tracing::instrument(skip(db))]
+async fn create_item(
+    db: Data<Box<dyn Database>>,
+    body: Json<CreateItemBody>,
+) -> Result<Json<ItemBody>, Error> {
+    let body = body.into_inner();
+
+    let item = manager::create_item(
+        &***db,
+        None,
+        body.name,
+        body.weight,
+        body.value,
+        body.item_type,
+        body.magic,
+    )
+    .await?;
+
+    Ok(Json(ItemBody::render(item)))
+}
+
+#[put("/items/{item_id}")]
+#[tracing::instrument(skip(db))]
+async fn update_item_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<ItemId>,
+    body: Json<CreateItemBody>,
+) -> Result<Json<ItemBody>, Error> {
+    let item_id = params.into_inner();
+    let item = manager::get_item_by_id(&***db, None, item_id)
+        .await?
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+    let body = body.into_inner();
+
+    let item = manager::update_item(
+        &***db,
+        item,
+        body.name,
+        body.weight,
+        body.value,
+        body.item_type,
+        body.magic,
+    )
+    .await?;
+
+    Ok(Json(ItemBody::render(item)))
+}
+
+#[delete("/items/{item_id}")]
+#[tracing::instrument(skip(db))]
+async fn delete_item_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<ItemId>,
+) -> Result<HttpResponse, Error> {
+    let item_id = params.into_inner();
+    let item = manager::get_item_by_id(&***db, None, item_id)
+        .await?
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+    manager::delete_item(&***db, item).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/campaigns/{campaign_id}/items")]
+#[tracing::instrument(skip(db))]
+async fn get_items_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    query: Query<ItemFilter>,
+) -> Result<Json<Vec<ItemBody>>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+
+    let items = manager::search_items(&***db, Some(campaign.id), query.into_inner()).await?;
+
+    let body = items.into_iter().map(ItemBody::render).collect();
+
+    Ok(Json(body))
+}
+
+#[post("/campaigns/{campaign_id}/items")]
+#[tracing::instrument(skip(db))]
+async fn create_item_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<CreateItemBody>,
+) -> Result<Json<ItemBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let item = manager::create_item(
+        &***db,
+        Some(campaign.id),
+        body.name,
+        body.weight,
+        body.value,
+        body.item_type,
+        body.magic,
+    )
+    .await?;
+
+    Ok(Json(ItemBody::render(item)))
+}
+
+#[get("/campaigns/{campaign_id}/items/{item_id}")]
+#[tracing::instrument(skip(db))]
+async fn get_item_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, ItemId)>,
+) -> Result<Json<ItemBody>, Error> {
+    let (campaign_id, item_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let item = manager::get_item_by_id(&***db, Some(campaign.id), item_id)
+        .await?
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+    Ok(Json(ItemBody::render(item)))
+}
+
+#[put("/campaigns/{campaign_id}/items/{item_id}")]
+#[tracing::instrument(skip(db))]
+async fn update_item_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, ItemId)>,
+    body: Json<CreateItemBody>,
+) -> Result<Json<ItemBody>, Error> {
+    let (campaign_id, item_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let item = manager::get_item_by_id(&***db, Some(campaign.id), item_id)
+        .await?
+        .filter(|item| item.campaign_id == Some(campaign.id))
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+    let body = body.into_inner();
+
+    let item = manager::update_item(
+        &***db,
+        item,
+        body.name,
+        body.weight,
+        body.value,
+        body.item_type,
+        body.magic,
+    )
+    .await?;
+
+    Ok(Json(ItemBody::render(item)))
+}
+
+#[delete("/campaigns/{campaign_id}/items/{item_id}")]
+#[tracing::instrument(skip(db))]
+async fn delete_item_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, ItemId)>,
+) -> Result<HttpResponse, Error> {
+    let (campaign_id, item_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let item = manager::get_item_by_id(&***db, Some(campaign.id), item_id)
+        .await?
+        .filter(|item| item.campaign_id == Some(campaign.id))
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+    manager::delete_item(&***db, item).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,107 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::operation::AbilityType;
+
+use super::{DamageType, DiceRoll};
+
+pub const MAXIMUM_ATTUNED_ITEMS: usize = 3;
+pub const MAXIMUM_MAGIC_BONUS: i32 = 3;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    VeryRare,
+    Legendary,
+    Artifact,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MagicProperties {
+    pub rarity: Rarity,
+    // added to attack and damage rolls for weapons or armor class for armor
+    #[serde(default)]
+    pub bonus: i32,
+    #[serde(default)]
+    pub requires_attunement: bool,
+    pub charges: Option<Charges>,
+    #[serde(default)]
+    pub effects: Vec<MagicEffect>,
+}
+
+impl MagicProperties {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0..=MAXIMUM_MAGIC_BONUS).contains(&self.bonus) {
+            return Err("a magic item's bonus must be between +0 and +3".to_string());
+        }
+        if let Some(charges) = &self.charges {
+            if charges.maximum < 1 {
+                return Err("a magic item with charges must have at least one".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Charges {
+    pub maximum: i32,
+    // the charges regained at dawn, all of them if not specified
+    pub recharge: Option<DiceRoll>,
+}
+
+impl Charges {
+    // returns the charges still expended after recharging
+    pub fn recharge<R: Rng>(&self, rng: &mut R, expended: i32) -> i32 {
+        let regained = match &self.recharge {
+            Some(roll) => roll.roll(rng),
+            None => self.maximum,
+        };
+
+        i32::clamp(expended - regained, 0, self.maximum)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum MagicEffect {
+    DamageResistance { damage_type: DamageType },
+    SaveAdvantage { ability: AbilityType },
+    SaveBonus { bonus: i32 },
+    ArmorClassBonus { bonus: i32 },
+    // sets the ability score unless it is already higher
+    AbilityScore { ability: AbilityType, score: i32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Dice;
+
+    #[test]
+    fn charges_recharge_without_exceeding_maximum() {
+        let mut rng = rand::thread_rng();
+        let charges = Charges {
+            maximum: 7,
+            recharge: Some(DiceRoll {
+                amount: 1,
+                dice: Dice::D6,
+                modifier: 1,
+            }),
+        };
+
+        let expended = charges.recharge(&mut rng, 7);
+        assert!((0..=5).contains(&expended));
+        assert_eq!(charges.recharge(&mut rng, 1), 0);
+
+        let charges = Charges {
+            maximum: 3,
+            recharge: None,
+        };
+        assert_eq!(charges.recharge(&mut rng, 3), 0);
+    }
+}
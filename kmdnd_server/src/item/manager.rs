@@ -1,7 +1,11 @@
+use crate::campaign::CampaignId;
+use crate::currency::CopperPieces;
 use crate::database::Database;
 use crate::error::Error;
+use crate::operation::spell;
 
-use super::{Item, ItemId};
+use super::magic::MagicProperties;
+use super::{Consumable, ConsumableEffect, Item, ItemFilter, ItemId, ItemType};
 
 #[tracing::instrument(skip(db))]
 pub async fn get_items(db: &dyn Database) -> Result<Vec<Item>, Error> {
@@ -11,19 +15,259 @@ pub async fn get_items(db: &dyn Database) -> Result<Vec<Item>, Error> {
 }
 
 #[tracing::instrument(skip(db))]
-pub async fn get_item_by_id(db: &dyn Database, item_id: ItemId) -> Result<Option<Item>, Error> {
-    let item = db.items().fetch_item_by_id(item_id).await?;
+pub async fn search_items(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    filter: ItemFilter,
+) -> Result<Vec<Item>, Error> {
+    let items = db
+        .items()
+        .fetch_items_by_filter(campaign_id, filter)
+        .await?;
+
+    Ok(items)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_item(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    name: String,
+    weight: i32,
+    value: CopperPieces,
+    item_type: ItemType,
+    magic: Option<MagicProperties>,
+) -> Result<Item, Error> {
+    let item = Item {
+        id: ItemId::new(),
+        campaign_id,
+        name,
+        weight,
+        value,
+        item_type,
+        magic,
+    };
+    validate_item(db, &item).await?;
+
+    db.items().insert_item(&item).await?;
 
     Ok(item)
 }
 
 #[tracing::instrument(skip(db))]
-pub async fn expect_item_by_id(db: &dyn Database, item_id: ItemId) -> Result<Item, Error> {
+pub async fn update_item(
+    db: &dyn Database,
+    mut item: Item,
+    name: String,
+    weight: i32,
+    value: CopperPieces,
+    item_type: ItemType,
+    magic: Option<MagicProperties>,
+) -> Result<Item, Error> {
+    item.name = name;
+    item.weight = weight;
+    item.value = value;
+    item.item_type = item_type;
+    item.magic = magic;
+    validate_item(db, &item).await?;
+
+    db.items().update_item(item).await
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn delete_item(db: &dyn Database, item: Item) -> Result<(), Error> {
+    let characters = db.characters().fetch_characters_by_item(item.id).await?;
+    if !characters.is_empty() {
+        return Err(Error::ItemInUse {
+            item_id: item.id,
+            character_ids: characters.iter().map(|character| character.id).collect(),
+        });
+    }
+
+    db.items().delete_item(item.id).await
+}
+
+async fn validate_item(db: &dyn Database, item: &Item) -> Result<(), Error> {
+    let mut result = if item.name.trim().is_empty() {
+        Err("an item must have a name".to_string())
+    } else if item.weight < 0 || item.value < 0 {
+        Err("an item cannot have a negative weight or value".to_string())
+    } else {
+        item.item_type.validate().and_then(|_| match &item.magic {
+            Some(magic) => magic.validate(),
+            None => Ok(()),
+        })
+    };
+
+    // scrolls can contain imported spells, so they're checked against the store
+    if let ItemType::Consumable(Consumable {
+        effect: ConsumableEffect::SpellScroll { spell, level },
+    }) = &item.item_type
+    {
+        if result.is_ok() {
+            result = match spell::get_spell_by_name(db, spell).await? {
+                Some(spell) if spell.level <= *level => Ok(()),
+                Some(_) => {
+                    Err("a spell scroll must be at least the level of its spell".to_string())
+                }
+                None => Err("a spell scroll must contain a known spell".to_string()),
+            };
+        }
+    }
+
+    result.map_err(|reason| Error::InvalidItem { reason })
+}
+
+// items are only visible to the campaign that owns them, or to every campaign
+// if they're in the global catalog
+#[tracing::instrument(skip(db))]
+pub async fn get_item_by_id(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    item_id: ItemId,
+) -> Result<Option<Item>, Error> {
     let item = db
         .items()
         .fetch_item_by_id(item_id)
+        .await?
+        .filter(|item| item.campaign_id.is_none() || item.campaign_id == campaign_id);
+
+    Ok(item)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn expect_item_by_id(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    item_id: ItemId,
+) -> Result<Item, Error> {
+    let item = get_item_by_id(db, campaign_id, item_id)
         .await?
         .ok_or(Error::ItemExpected { item_id })?;
 
     Ok(item)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::test::campaign;
+    use crate::character::test::character;
+    use crate::database::test::MockDatabase;
+    use crate::item::test::longsword;
+    use crate::item::WeaponProperty;
+    use std::sync::{Arc, Mutex};
+
+    #[actix_rt::test]
+    async fn only_valid_items_are_created() {
+        let mut db = MockDatabase::new();
+        db.spells.on_fetch_spell_by_name = Box::new(|_| Ok(None));
+        let called_insert = Arc::new(Mutex::new(false));
+        let called_insert_clone = Arc::clone(&called_insert);
+        db.items.on_insert_item = Box::new(move |_| {
+            *called_insert_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let mut greatsword = longsword(None).item_type.into_weapon().unwrap();
+        greatsword.properties = vec![WeaponProperty::Heavy, WeaponProperty::Light];
+        let unknown_scroll = ItemType::Consumable(Consumable {
+            effect: ConsumableEffect::SpellScroll {
+                spell: "Unheard Of".to_string(),
+                level: 9,
+            },
+        });
+        let invalid = [
+            ("".to_string(), 3, ItemType::Gear),
+            ("Anvil".to_string(), -1, ItemType::Gear),
+            ("Greatsword".to_string(), 6, ItemType::Weapon(greatsword)),
+            ("Scroll".to_string(), 0, unknown_scroll),
+        ];
+        for (name, weight, item_type) in invalid {
+            let result = create_item(&db, None, name, weight, 100, item_type, None).await;
+            assert!(matches!(result, Err(Error::InvalidItem { .. })));
+        }
+        assert!(!*called_insert.lock().unwrap(), "db.insert_item was called");
+
+        let longsword = longsword(None);
+        create_item(
+            &db,
+            None,
+            longsword.name,
+            longsword.weight,
+            longsword.value,
+            longsword.item_type,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(
+            *called_insert.lock().unwrap(),
+            "db.insert_item was not called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn carried_items_are_not_deleted() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let carrier = character(campaign.id);
+        let carrier_id = carrier.id;
+        db.characters.on_fetch_characters_by_item = Box::new(move |_| Ok(vec![carrier.clone()]));
+        let called_delete = Arc::new(Mutex::new(false));
+        let called_delete_clone = Arc::clone(&called_delete);
+        db.items.on_delete_item = Box::new(move |_| {
+            *called_delete_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let result = delete_item(&db, longsword(None)).await;
+
+        match result {
+            Err(Error::ItemInUse { character_ids, .. }) => {
+                assert_eq!(character_ids, vec![carrier_id])
+            }
+            _ => panic!("expected the item to be in use"),
+        }
+        assert!(!*called_delete.lock().unwrap(), "db.delete_item was called");
+    }
+
+    #[actix_rt::test]
+    async fn homebrew_items_stay_in_their_campaign() {
+        let mut db = MockDatabase::new();
+        let home = campaign();
+        let other = campaign();
+        let homebrew = longsword(Some(home.id));
+        let global = longsword(None);
+        let items = [homebrew.clone(), global.clone()];
+        db.items.on_fetch_item_by_id =
+            Box::new(move |item_id| Ok(items.iter().find(|item| item.id == item_id).cloned()));
+
+        assert!(get_item_by_id(&db, Some(home.id), homebrew.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(
+            get_item_by_id(&db, Some(other.id), homebrew.id)
+                .await
+                .unwrap()
+                .is_none(),
+            "another campaign's homebrew was visible"
+        );
+        assert!(get_item_by_id(&db, None, homebrew.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            expect_item_by_id(&db, Some(other.id), homebrew.id).await,
+            Err(Error::ItemExpected { .. })
+        ));
+
+        for campaign_id in [Some(home.id), Some(other.id), None] {
+            assert!(get_item_by_id(&db, campaign_id, global.id)
+                .await
+                .unwrap()
+                .is_some());
+        }
+    }
+}
@@ -1,10 +1,16 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::character::Character;
+use crate::campaign::CampaignId;
+use crate::character::{Character, ToolType};
+use crate::currency::CopperPieces;
 use crate::typedid::{TypedId, TypedIdMarker};
 
+use self::magic::MagicProperties;
+
 pub mod db;
 pub mod endpoints;
+pub mod magic;
 pub mod manager;
 pub use endpoints::*;
 
@@ -14,10 +20,14 @@ pub type ItemId = TypedId<Item>;
 pub struct Item {
     #[serde(rename = "_id")]
     pub id: ItemId,
+    // homebrew items belong to a single campaign, others are in the global
+    // catalog
+    pub campaign_id: Option<CampaignId>,
     pub name: String,
     pub weight: i32,
-    pub value: i32,
+    pub value: CopperPieces,
     pub item_type: ItemType,
+    pub magic: Option<MagicProperties>,
 }
 
 impl TypedIdMarker for Item {
@@ -31,9 +41,53 @@ impl TypedIdMarker for Item {
 pub enum ItemType {
     Weapon(Weapon),
     Armor(Armor),
+    Consumable(Consumable),
+    Gear,
+    Tool(Tool),
+    Container(Container),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ItemCategory {
+    Weapon,
+    Armor,
+    Consumable,
+    Gear,
+    Tool,
+    Container,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ItemFilter {
+    #[serde(rename = "type")]
+    pub category: Option<ItemCategory>,
+    pub damage_type: Option<DamageType>,
+    pub name: Option<String>,
 }
 
 impl ItemType {
+    pub fn category(&self) -> ItemCategory {
+        match self {
+            ItemType::Weapon(_) => ItemCategory::Weapon,
+            ItemType::Armor(_) => ItemCategory::Armor,
+            ItemType::Consumable(_) => ItemCategory::Consumable,
+            ItemType::Gear => ItemCategory::Gear,
+            ItemType::Tool(_) => ItemCategory::Tool,
+            ItemType::Container(_) => ItemCategory::Container,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ItemType::Weapon(weapon) => weapon.validate(),
+            ItemType::Armor(armor) => armor.validate(),
+            ItemType::Consumable(consumable) => consumable.validate(),
+            ItemType::Gear | ItemType::Tool(_) => Ok(()),
+            ItemType::Container(container) => container.validate(),
+        }
+    }
+
     pub fn as_armor(&self) -> Option<&Armor> {
         match self {
             ItemType::Armor(armor) => Some(armor),
@@ -41,12 +95,43 @@ impl ItemType {
         }
     }
 
+    // the number of hands the item takes up while it is equiped
+    pub fn hands_occupied(&self) -> i32 {
+        match self {
+            ItemType::Weapon(weapon)
+                if weapon
+                    .properties
+                    .iter()
+                    .any(|property| matches!(property, WeaponProperty::TwoHanded)) =>
+            {
+                2
+            }
+            ItemType::Weapon(_) => 1,
+            ItemType::Armor(armor) if armor.armor_type == ArmorType::Shield => 1,
+            _ => 0,
+        }
+    }
+
     pub fn into_weapon(self) -> Option<Weapon> {
         match self {
             ItemType::Weapon(weapon) => Some(weapon),
             _ => None,
         }
     }
+
+    pub fn as_container(&self) -> Option<&Container> {
+        match self {
+            ItemType::Container(container) => Some(container),
+            _ => None,
+        }
+    }
+
+    pub fn as_consumable(&self) -> Option<&Consumable> {
+        match self {
+            ItemType::Consumable(consumable) => Some(consumable),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -73,6 +158,55 @@ impl Weapon {
 
         melee_range
     }
+
+    pub fn is_ranged(&self) -> bool {
+        self.properties
+            .iter()
+            .any(|property| matches!(property, WeaponProperty::Ammunition(_)))
+    }
+
+    pub fn is_finesse(&self) -> bool {
+        self.properties
+            .iter()
+            .any(|property| matches!(property, WeaponProperty::Finesse))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let has = |f: fn(&WeaponProperty) -> bool| self.properties.iter().filter(|p| f(p)).count();
+
+        let ammunition = has(|p| matches!(p, WeaponProperty::Ammunition(_)));
+        let thrown = has(|p| matches!(p, WeaponProperty::Thrown(_)));
+        let light = has(|p| matches!(p, WeaponProperty::Light));
+        let heavy = has(|p| matches!(p, WeaponProperty::Heavy));
+        let loading = has(|p| matches!(p, WeaponProperty::Loading));
+        let two_handed = has(|p| matches!(p, WeaponProperty::TwoHanded));
+        let versatile = has(|p| matches!(p, WeaponProperty::Versatile { .. }));
+
+        if ammunition > 1 || thrown > 1 || versatile > 1 {
+            return Err("a weapon can only have one range and versatile damage".to_string());
+        }
+        if light > 0 && heavy > 0 {
+            return Err("a weapon cannot be both light and heavy".to_string());
+        }
+        if two_handed > 0 && versatile > 0 {
+            return Err("a weapon cannot be both two-handed and versatile".to_string());
+        }
+        if loading > 0 && ammunition == 0 {
+            return Err("only weapons that use ammunition can have loading".to_string());
+        }
+
+        for property in &self.properties {
+            if let WeaponProperty::Ammunition(range) | WeaponProperty::Thrown(range) = property {
+                if range.normal <= 0 || range.long < range.normal {
+                    return Err(
+                        "a weapon's long range must be at least its normal range".to_string()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -96,7 +230,7 @@ pub struct Range {
     pub long: i32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum DamageType {
     Acid,
@@ -114,7 +248,7 @@ pub enum DamageType {
     Thunder,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum Dice {
     D4,
@@ -125,6 +259,103 @@ pub enum Dice {
     D20,
 }
 
+impl Dice {
+    pub fn sides(&self) -> i32 {
+        match self {
+            Dice::D4 => 4,
+            Dice::D6 => 6,
+            Dice::D8 => 8,
+            Dice::D10 => 10,
+            Dice::D12 => 12,
+            Dice::D20 => 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DiceRoll {
+    pub amount: i32,
+    pub dice: Dice,
+    pub modifier: i32,
+}
+
+impl DiceRoll {
+    pub fn new(amount: i32, dice: Dice) -> DiceRoll {
+        DiceRoll {
+            amount,
+            dice,
+            modifier: 0,
+        }
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> i32 {
+        let rolled: i32 = (0..self.amount)
+            .map(|_| rng.gen_range(1..=self.dice.sides()))
+            .sum();
+
+        rolled + self.modifier
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Consumable {
+    pub effect: ConsumableEffect,
+}
+
+impl Consumable {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.effect {
+            ConsumableEffect::Healing { healing } => {
+                if healing.amount < 1 {
+                    return Err("a healing consumable must roll at least one die".to_string());
+                }
+            }
+            // the spell itself is checked against the spell catalog
+            ConsumableEffect::SpellScroll { level, .. } => {
+                if !(0..=9).contains(level) {
+                    return Err("a spell scroll must be between levels 0 and 9".to_string());
+                }
+            }
+            ConsumableEffect::Mundane => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ConsumableEffect {
+    Healing { healing: DiceRoll },
+    SpellScroll { spell: String, level: i32 },
+    // rations, oil and the like are used up without any effect to resolve
+    Mundane,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Tool {
+    pub tool_type: ToolType,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Container {
+    // in pounds
+    pub capacity: i32,
+    // a bag of holding weighs the same no matter what it holds
+    #[serde(default)]
+    pub weightless_contents: bool,
+}
+
+impl Container {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.capacity < 0 {
+            return Err("a container cannot have a negative capacity".to_string());
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Armor {
     pub base_armor_class: i32,
@@ -134,6 +365,20 @@ pub struct Armor {
 }
 
 impl Armor {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.base_armor_class < 0 {
+            return Err("armor cannot have a negative armor class".to_string());
+        }
+        if self.strength_requirement.is_some() && self.armor_type != ArmorType::Heavy {
+            return Err("only heavy armor can have a strength requirement".to_string());
+        }
+        if self.stealth_disadvantage && self.armor_type == ArmorType::Shield {
+            return Err("shields cannot impose stealth disadvantage".to_string());
+        }
+
+        Ok(())
+    }
+
     pub fn effective_armor_class(&self, character: &Character) -> i32 {
         let ac_from_dex = match self.armor_type {
             ArmorType::Light => character.stats.abilities.dexterity_modifier(),
@@ -154,3 +399,27 @@ pub enum ArmorType {
     Heavy,
     Shield,
 }
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    // a plain longsword, homebrew when given a campaign
+    pub fn longsword(campaign_id: Option<CampaignId>) -> Item {
+        Item {
+            id: ItemId::new(),
+            campaign_id,
+            name: "Longsword".to_string(),
+            weight: 3,
+            value: 1500,
+            item_type: ItemType::Weapon(Weapon {
+                damage_amount: Dice::D8,
+                damage_type: DamageType::Slashing,
+                properties: vec![WeaponProperty::Versatile {
+                    two_handed_damage: Dice::D10,
+                }],
+            }),
+            magic: None,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use actix_web::web::{self, Data, FormConfig, JsonConfig, PathConfig, QueryConfig};
 use actix_web::{App, HttpServer, ResponseError};
 use mongodb::Client;
@@ -9,28 +11,44 @@ extern crate derivative;
 
 mod campaign;
 mod character;
+mod currency;
 mod database;
 mod encounter;
 mod error;
 mod item;
+mod monster;
 mod operation;
 mod seed;
+mod srd;
 mod typedid;
 mod user;
 mod utils;
 mod violations;
 
-pub use campaign::endpoints::{CampaignBody, CreateCampaignBody};
+pub use campaign::{CampaignBody, CreateCampaignBody};
 pub use error::Error;
+pub use srd::{ImportReport, UnmappedEntry};
 
 use crate::database::{Database, MongoDatabase};
 
-#[actix_web::main]
-pub async fn run(should_seed: bool) -> Result<(), Error> {
+async fn connect() -> Result<MongoDatabase, Error> {
     let uri = "mongodb://localhost:27017";
     info!("connecting to db: {}", uri);
     let db = Client::with_uri_str(uri).await?.database("kmdnd");
-    let db = MongoDatabase::initialize(db).await?;
+
+    MongoDatabase::initialize(db).await
+}
+
+#[actix_web::main]
+pub async fn import_srd(directory: PathBuf) -> Result<ImportReport, Error> {
+    let db = connect().await?;
+
+    srd::import(&db, &directory).await
+}
+
+#[actix_web::main]
+pub async fn run(should_seed: bool) -> Result<(), Error> {
+    let db = connect().await?;
 
     if should_seed {
         seed::seed(&db).await?;
@@ -62,12 +80,29 @@ pub async fn run(should_seed: bool) -> Result<(), Error> {
             .service(character::endpoints::create_character_in_campaign)
             .service(character::endpoints::get_characters_in_campaign)
             .service(character::endpoints::get_character_in_campaign_by_id)
+            .service(character::endpoints::update_character_in_campaign_by_id)
+            .service(character::endpoints::delete_character_in_campaign_by_id)
             .service(character::endpoints::get_character_roll_stats)
+            .service(character::endpoints::level_up_character_in_campaign)
+            .service(character::endpoints::award_experience_in_campaign)
             .service(encounter::endpoints::create_encounter_in_campaign)
+            .service(encounter::endpoints::preview_encounter_in_campaign)
             .service(encounter::endpoints::get_encounters_in_campaign)
             .service(encounter::endpoints::get_current_encounter_in_campaign)
+            .service(encounter::endpoints::update_faction_in_current_encounter_in_campaign)
             .service(encounter::endpoints::finish_current_encounter_in_campaign)
             .service(encounter::endpoints::begin_current_encounter_in_campaign)
+            .service(encounter::endpoints::end_turn_in_current_encounter_in_campaign)
+            .service(operation::endpoints::get_operations_in_campaign)
+            .service(operation::endpoints::get_operation_by_id_in_campaign)
+            .service(operation::endpoints::approve_illegal_operation_in_campaign)
+            .service(operation::endpoints::reject_illegal_operation_in_campaign)
+            .service(operation::endpoints::submit_interaction_result_to_campaign_operation)
+            .service(operation::endpoints::cast_in_campaign)
+            .service(operation::endpoints::rest_in_campaign)
+            .service(operation::endpoints::change_inventory_in_campaign)
+            .service(operation::endpoints::use_item_in_campaign)
+            .service(operation::endpoints::trade_in_campaign)
             .service(operation::endpoints::get_operations_in_current_encounter_in_campaign)
             .service(operation::endpoints::get_operation_by_id_in_current_encounter_in_campaign)
             .service(operation::endpoints::approve_illegal_operation)
@@ -76,8 +111,28 @@ pub async fn run(should_seed: bool) -> Result<(), Error> {
             .service(operation::endpoints::roll_in_current_encounter_in_campaign)
             .service(operation::endpoints::move_in_current_encounter_in_campaign)
             .service(operation::endpoints::take_action_in_current_encounter_in_campaign)
+            .service(operation::endpoints::take_legendary_action_in_current_encounter_in_campaign)
+            .service(operation::endpoints::take_ai_turn_in_current_encounter_in_campaign)
+            .service(operation::endpoints::use_feature_in_current_encounter_in_campaign)
             .service(item::endpoints::get_items)
+            .service(item::endpoints::create_item)
+            .service(item::endpoints::update_item_by_id)
+            .service(item::endpoints::delete_item_by_id)
+            .service(item::endpoints::get_items_in_campaign)
+            .service(item::endpoints::create_item_in_campaign)
+            .service(item::endpoints::get_item_in_campaign_by_id)
+            .service(item::endpoints::update_item_in_campaign_by_id)
+            .service(item::endpoints::delete_item_in_campaign_by_id)
             .service(item::endpoints::get_item_by_id)
+            .service(monster::endpoints::get_monsters)
+            .service(monster::endpoints::create_monster)
+            .service(monster::endpoints::update_monster_by_id)
+            .service(monster::endpoints::get_monsters_in_campaign)
+            .service(monster::endpoints::create_monster_in_campaign)
+            .service(monster::endpoints::get_monster_in_campaign_by_id)
+            .service(monster::endpoints::update_monster_in_campaign_by_id)
+            .service(monster::endpoints::instantiate_monster_in_campaign)
+            .service(monster::endpoints::get_monster_by_id)
             .default_service(web::to(|| Error::PathNotFound.error_response()))
     })
     .bind("127.0.0.1:8080")?
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson;
+
+use crate::campaign::CampaignId;
+use crate::database::MongoMonsterStore;
+use crate::error::Error;
+
+use super::{Monster, MonsterId};
+
+#[async_trait]
+pub trait MonsterStore {
+    async fn insert_monster(&self, monster: &Monster) -> Result<(), Error>;
+
+    async fn fetch_monster_by_id(&self, monster_id: MonsterId) -> Result<Option<Monster>, Error>;
+
+    async fn fetch_monsters_by_campaign(
+        &self,
+        campaign_id: Option<CampaignId>,
+    ) -> Result<Vec<Monster>, Error>;
+
+    async fn update_monster(&self, monster: Monster) -> Result<Monster, Error>;
+}
+
+#[async_trait]
+impl MonsterStore for MongoMonsterStore {
+    #[tracing::instrument(skip(self))]
+    async fn insert_monster(&self, monster: &Monster) -> Result<(), Error> {
+        self.insert_one(monster, None).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_monster_by_id(&self, monster_id: MonsterId) -> Result<Option<Monster>, Error> {
+        let monster = self
+            .find_one(bson::doc! { "_id": monster_id }, None)
+            .await?;
+
+        Ok(monster)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_monsters_by_campaign(
+        &self,
+        campaign_id: Option<CampaignId>,
+    ) -> Result<Vec<Monster>, Error> {
+        let query = match campaign_id {
+            Some(campaign_id) => bson::doc! { "campaign_id": { "$in": [null, campaign_id] } },
+            None => bson::doc! { "campaign_id": null },
+        };
+
+        let monsters = self.find(query, None).await?.try_collect().await?;
+
+        Ok(monsters)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_monster(&self, monster: Monster) -> Result<Monster, Error> {
+        let result = self
+            .replace_one(bson::doc! { "_id": monster.id }, &monster, None)
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(Error::MonsterDoesNotExist {
+                monster_id: monster.id,
+            });
+        }
+
+        Ok(monster)
+    }
+}
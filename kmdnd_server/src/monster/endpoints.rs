@@ -0,0 +1,222 @@
+use actix_web::web::{Data, Json, Path};
+use actix_web::{get, post, put};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::{self, CampaignId};
+use crate::character::CharacterBody;
+use crate::database::Database;
+use crate::error::Error;
+
+use super::{manager, Monster, MonsterId, StatBlock};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MonsterBody {
+    pub id: MonsterId,
+    pub campaign_id: Option<CampaignId>,
+    pub name: String,
+    pub experience: i32,
+    pub stat_block: StatBlock,
+}
+
+impl MonsterBody {
+    pub fn render(monster: Monster) -> MonsterBody {
+        MonsterBody {
+            id: monster.id,
+            campaign_id: monster.campaign_id,
+            name: monster.name,
+            experience: monster
+                .stat_block
+                .challenge_rating
+                .experience()
+                .unwrap_or(0),
+            stat_block: monster.stat_block,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateMonsterBody {
+    pub name: String,
+    pub stat_block: StatBlock,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct InstantiateMonsterBody {
+    #[serde(default = "default_count")]
+    pub count: i32,
+    // the average from the hit dice is used otherwise
+    #[serde(default)]
+    pub roll_hit_points: bool,
+    #[serde(default)]
+    pub ai_controlled: bool,
+}
+
+fn default_count() -> i32 {
+    1
+}
+
+#[get("/monsters")]
+#[tracing::instrument(skip(db))]
+async fn get_monsters(db: Data<Box<dyn Database>>) -> Result<Json<Vec<MonsterBody>>, Error> {
+    let monsters = manager::get_monsters(&***db, None).await?;
+
+    let body = monsters.into_iter().map(MonsterBody::render).collect();
+
+    Ok(Json(body))
+}
+
+#[get("/monsters/{monster_id}")]
+#[tracing::instrument(skip(db))]
+async fn get_monster_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<MonsterId>,
+) -> Result<Json<MonsterBody>, Error> {
+    let monster_id = params.into_inner();
+    let monster = manager::get_monster_by_id(&***db, None, monster_id)
+        .await?
+        .ok_or(Error::MonsterDoesNotExist { monster_id })?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[post("/monsters")]
+#[tracing::instrument(skip(db))]
+async fn create_monster(
+    db: Data<Box<dyn Database>>,
+    body: Json<CreateMonsterBody>,
+) -> Result<Json<MonsterBody>, Error> {
+    let body = body.into_inner();
+
+    let monster = manager::create_monster(&***db, None, body.name, body.stat_block).await?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[put("/monsters/{monster_id}")]
+#[tracing::instrument(skip(db))]
+async fn update_monster_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<MonsterId>,
+    body: Json<CreateMonsterBody>,
+) -> Result<Json<MonsterBody>, Error> {
+    let monster_id = params.into_inner();
+    let monster = manager::get_monster_by_id(&***db, None, monster_id)
+        .await?
+        .ok_or(Error::MonsterDoesNotExist { monster_id })?;
+    let body = body.into_inner();
+
+    let monster = manager::update_monster(&***db, monster, body.name, body.stat_block).await?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[get("/campaigns/{campaign_id}/monsters")]
+#[tracing::instrument(skip(db))]
+async fn get_monsters_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+) -> Result<Json<Vec<MonsterBody>>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+
+    let monsters = manager::get_monsters(&***db, Some(campaign.id)).await?;
+
+    let body = monsters.into_iter().map(MonsterBody::render).collect();
+
+    Ok(Json(body))
+}
+
+#[post("/campaigns/{campaign_id}/monsters")]
+#[tracing::instrument(skip(db))]
+async fn create_monster_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<CreateMonsterBody>,
+) -> Result<Json<MonsterBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let monster =
+        manager::create_monster(&***db, Some(campaign.id), body.name, body.stat_block).await?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[get("/campaigns/{campaign_id}/monsters/{monster_id}")]
+#[tracing::instrument(skip(db))]
+async fn get_monster_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, MonsterId)>,
+) -> Result<Json<MonsterBody>, Error> {
+    let (campaign_id, monster_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let monster = manager::get_monster_by_id(&***db, Some(campaign.id), monster_id)
+        .await?
+        .ok_or(Error::MonsterDoesNotExist { monster_id })?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[put("/campaigns/{campaign_id}/monsters/{monster_id}")]
+#[tracing::instrument(skip(db))]
+async fn update_monster_in_campaign_by_id(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, MonsterId)>,
+    body: Json<CreateMonsterBody>,
+) -> Result<Json<MonsterBody>, Error> {
+    let (campaign_id, monster_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let monster = manager::get_monster_by_id(&***db, Some(campaign.id), monster_id)
+        .await?
+        .filter(|monster| monster.campaign_id == Some(campaign.id))
+        .ok_or(Error::MonsterDoesNotExist { monster_id })?;
+    let body = body.into_inner();
+
+    let monster = manager::update_monster(&***db, monster, body.name, body.stat_block).await?;
+
+    Ok(Json(MonsterBody::render(monster)))
+}
+
+#[post("/campaigns/{campaign_id}/monsters/{monster_id}/instantiate")]
+#[tracing::instrument(skip(db))]
+async fn instantiate_monster_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, MonsterId)>,
+    body: Json<InstantiateMonsterBody>,
+) -> Result<Json<Vec<CharacterBody>>, Error> {
+    let (campaign_id, monster_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let monster = manager::get_monster_by_id(&***db, Some(campaign.id), monster_id)
+        .await?
+        .ok_or(Error::MonsterDoesNotExist { monster_id })?;
+    let body = body.into_inner();
+
+    let characters = manager::instantiate_monster(
+        &***db,
+        &campaign,
+        monster,
+        body.count,
+        body.roll_hit_points,
+        body.ai_controlled,
+    )
+    .await?;
+
+    let body = stream::iter(characters)
+        .then(|character| CharacterBody::render(&***db, character))
+        .try_collect()
+        .await?;
+
+    Ok(Json(body))
+}
@@ -0,0 +1,164 @@
+use chrono::Utc;
+
+use crate::campaign::{Campaign, CampaignId};
+use crate::character::creation::AbilityScoreGeneration;
+use crate::character::{Character, CharacterId, CharacterOwner, Proficiencies};
+use crate::currency::Purse;
+use crate::database::Database;
+use crate::error::Error;
+
+use super::{Monster, MonsterId, MonsterInstance, StatBlock};
+
+#[tracing::instrument(skip(db))]
+pub async fn get_monsters(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+) -> Result<Vec<Monster>, Error> {
+    let monsters = db
+        .monsters()
+        .fetch_monsters_by_campaign(campaign_id)
+        .await?;
+
+    Ok(monsters)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_monster(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    name: String,
+    stat_block: StatBlock,
+) -> Result<Monster, Error> {
+    let monster = Monster {
+        id: MonsterId::new(),
+        campaign_id,
+        name,
+        stat_block,
+    };
+    validate_monster(&monster)?;
+
+    db.monsters().insert_monster(&monster).await?;
+
+    Ok(monster)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn update_monster(
+    db: &dyn Database,
+    mut monster: Monster,
+    name: String,
+    stat_block: StatBlock,
+) -> Result<Monster, Error> {
+    monster.name = name;
+    monster.stat_block = stat_block;
+    validate_monster(&monster)?;
+
+    db.monsters().update_monster(monster).await
+}
+
+fn validate_monster(monster: &Monster) -> Result<(), Error> {
+    let result = if monster.name.trim().is_empty() {
+        Err("a monster must have a name".to_string())
+    } else {
+        monster.stat_block.validate()
+    };
+
+    result.map_err(|reason| Error::InvalidMonster { reason })
+}
+
+// monsters are only visible to the campaign that owns them, or to every
+// campaign if they're in the global catalog
+#[tracing::instrument(skip(db))]
+pub async fn get_monster_by_id(
+    db: &dyn Database,
+    campaign_id: Option<CampaignId>,
+    monster_id: MonsterId,
+) -> Result<Option<Monster>, Error> {
+    let monster = db
+        .monsters()
+        .fetch_monster_by_id(monster_id)
+        .await?
+        .filter(|monster| monster.campaign_id.is_none() || monster.campaign_id == campaign_id);
+
+    Ok(monster)
+}
+
+// creates campaign owned characters from the template, numbered when there
+// are more than one
+#[tracing::instrument(skip(db))]
+pub async fn instantiate_monster(
+    db: &dyn Database,
+    campaign: &Campaign,
+    monster: Monster,
+    count: i32,
+    roll_hit_points: bool,
+    ai_controlled: bool,
+) -> Result<Vec<Character>, Error> {
+    if count < 1 {
+        return Err(Error::InvalidMonsterCount { count });
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut characters = vec![];
+    for number in 1..=count {
+        let stat_block = monster.stat_block.clone();
+        let hit_points = if roll_hit_points {
+            i32::max(stat_block.hit_dice.roll(&mut rng), 1)
+        } else {
+            stat_block.average_hit_points()
+        };
+
+        let name = if count == 1 {
+            monster.name.clone()
+        } else {
+            format!("{} {}", monster.name, number)
+        };
+
+        let now = Utc::now();
+        let mut character = Character {
+            id: CharacterId::new(),
+            owner: CharacterOwner::Campaign(campaign.id),
+            name,
+            created_at: now,
+            modified_at: now,
+            ability_scores: Some(stat_block.ability_scores.clone()),
+            ability_score_generation: AbilityScoreGeneration::Unspecified,
+            stats: Default::default(),
+            equipment: vec![],
+            purse: Purse::default(),
+            position: None,
+            current_hit_points: hit_points,
+            maximum_hit_points: hit_points,
+            experience: 0,
+            classes: vec![],
+            feature_uses: vec![],
+            attuned_items: vec![],
+            item_charges: vec![],
+            expended_hit_dice: vec![],
+            spell_slots: vec![],
+            race: None,
+            subrace: None,
+            background: None,
+            racial_traits: vec![],
+            proficiencies: Proficiencies {
+                armor: vec![],
+                tool: vec![],
+                saving_throws: stat_block.saving_throws.clone(),
+                skills: stat_block.skills.clone(),
+            },
+            conditions: vec![],
+            exhaustion: 0,
+            monster: Some(MonsterInstance {
+                monster_id: monster.id,
+                stat_block,
+            }),
+            ai_controlled,
+        };
+        character.recalculate_stats(db).await?;
+
+        db.characters().insert_character(&character).await?;
+        characters.push(character);
+    }
+
+    Ok(characters)
+}
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::CampaignId;
+use crate::character::race::Size;
+use crate::character::{CharacterAbilities, Condition};
+use crate::item::{DamageType, DiceRoll, Range};
+use crate::operation::{AbilityType, SkillType};
+use crate::typedid::{TypedId, TypedIdMarker};
+
+pub mod db;
+pub mod endpoints;
+pub mod manager;
+
+pub type MonsterId = TypedId<Monster>;
+
+// a template that any number of creatures can be instantiated from
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Monster {
+    #[serde(rename = "_id")]
+    pub id: MonsterId,
+    // homebrew monsters belong to a single campaign, others are in the global
+    // catalog
+    pub campaign_id: Option<CampaignId>,
+    pub name: String,
+    pub stat_block: StatBlock,
+}
+
+impl TypedIdMarker for Monster {
+    fn tag() -> &'static str {
+        "MON"
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatBlock {
+    pub size: Size,
+    pub creature_type: CreatureType,
+    pub armor_class: i32,
+    pub hit_dice: DiceRoll,
+    pub speed: i32,
+    pub ability_scores: CharacterAbilities,
+    #[serde(default)]
+    pub saving_throws: Vec<AbilityType>,
+    #[serde(default)]
+    pub skills: Vec<SkillType>,
+    #[serde(default)]
+    pub damage_vulnerabilities: Vec<DamageType>,
+    #[serde(default)]
+    pub damage_resistances: Vec<DamageType>,
+    #[serde(default)]
+    pub damage_immunities: Vec<DamageType>,
+    #[serde(default)]
+    pub condition_immunities: Vec<Condition>,
+    pub challenge_rating: ChallengeRating,
+    pub attacks: Vec<MonsterAttack>,
+    pub multiattack: Option<Multiattack>,
+    pub legendary_actions: Option<LegendaryActions>,
+}
+
+impl StatBlock {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.armor_class < 0 || self.speed < 0 {
+            return Err("a monster cannot have a negative armor class or speed".to_string());
+        }
+        if self.hit_dice.amount < 1 {
+            return Err("a monster must have at least one hit die".to_string());
+        }
+        if self.challenge_rating.experience().is_none() {
+            return Err("a monster must have a challenge rating from 0 to 30".to_string());
+        }
+        if let Some(multiattack) = &self.multiattack {
+            if multiattack.attacks < 2 {
+                return Err("a multiattack must make at least two attacks".to_string());
+            }
+        }
+        if let Some(legendary_actions) = &self.legendary_actions {
+            if legendary_actions.per_round < 1 {
+                return Err("a monster with legendary actions must have at least one".to_string());
+            }
+            for action in &legendary_actions.actions {
+                if !(1..=legendary_actions.per_round).contains(&action.cost) {
+                    return Err(format!(
+                        "the legendary action {} costs more than can be taken",
+                        action.name
+                    ));
+                }
+                if let Some(attack) = &action.attack {
+                    if self.attack(attack).is_none() {
+                        return Err(format!(
+                            "the legendary action {} refers to an unknown attack",
+                            action.name
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn attack(&self, name: &str) -> Option<&MonsterAttack> {
+        self.attacks.iter().find(|attack| attack.name == name)
+    }
+
+    pub fn legendary_action(&self, name: &str) -> Option<&LegendaryAction> {
+        self.legendary_actions
+            .as_ref()?
+            .actions
+            .iter()
+            .find(|action| action.name == name)
+    }
+
+    // the hit dice modifier already includes the constitution bonus
+    pub fn average_hit_points(&self) -> i32 {
+        let roll = &self.hit_dice;
+
+        i32::max(roll.amount * (roll.dice.sides() + 1) / 2 + roll.modifier, 1)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum CreatureType {
+    Aberration,
+    Beast,
+    Celestial,
+    Construct,
+    Dragon,
+    Elemental,
+    Fey,
+    Fiend,
+    Giant,
+    Humanoid,
+    Monstrosity,
+    Ooze,
+    Plant,
+    Undead,
+}
+
+// fractional below 1, so 0.125, 0.25 and 0.5 are valid as well
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ChallengeRating(pub f32);
+
+impl ChallengeRating {
+    const FRACTIONAL_EXPERIENCE: [(f32, i32); 4] = [(0.0, 10), (0.125, 25), (0.25, 50), (0.5, 100)];
+
+    const EXPERIENCE: [i32; 30] = [
+        200, 450, 700, 1100, 1800, 2300, 2900, 3900, 5000, 5900, 7200, 8400, 10000, 11500, 13000,
+        15000, 18000, 20000, 22000, 25000, 33000, 41000, 50000, 62000, 75000, 90000, 105000,
+        120000, 135000, 155000,
+    ];
+
+    // the experience awarded for defeating the monster
+    pub fn experience(&self) -> Option<i32> {
+        let ChallengeRating(rating) = *self;
+        if let Some((_, experience)) = Self::FRACTIONAL_EXPERIENCE
+            .iter()
+            .find(|(fraction, _)| *fraction == rating)
+        {
+            return Some(*experience);
+        }
+
+        if rating.fract() != 0.0 || !(1.0..=30.0).contains(&rating) {
+            return None;
+        }
+
+        Some(Self::EXPERIENCE[rating as usize - 1])
+    }
+
+    pub fn proficiency_bonus(&self) -> i32 {
+        let ChallengeRating(rating) = *self;
+        if rating < 5.0 {
+            2
+        } else {
+            (rating as i32 - 1) / 4 + 2
+        }
+    }
+}
+
+// a natural weapon or other attack listed in the stat block, its bonuses
+// already include the monster's ability and proficiency
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MonsterAttack {
+    pub name: String,
+    pub attack_bonus: i32,
+    #[serde(default = "default_reach")]
+    pub reach: f32,
+    // only for ranged attacks
+    pub range: Option<Range>,
+    pub damage: DiceRoll,
+    pub damage_type: DamageType,
+}
+
+fn default_reach() -> f32 {
+    5.0
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Multiattack {
+    pub attacks: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LegendaryActions {
+    // regained at the start of the monster's turn
+    pub per_round: i32,
+    pub actions: Vec<LegendaryAction>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LegendaryAction {
+    pub name: String,
+    #[serde(default = "default_legendary_action_cost")]
+    pub cost: i32,
+    // the name of the stat block's attack that is made, if any
+    pub attack: Option<String>,
+}
+
+fn default_legendary_action_cost() -> i32 {
+    1
+}
+
+// the template a creature was instantiated from, its stat block is copied so
+// later changes to the template don't affect creatures already in play
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MonsterInstance {
+    pub monster_id: MonsterId,
+    pub stat_block: StatBlock,
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::character::test::character;
+    use crate::character::Character;
+    use crate::item::Dice;
+
+    pub fn goblin() -> StatBlock {
+        StatBlock {
+            size: Size::Small,
+            creature_type: CreatureType::Humanoid,
+            armor_class: 15,
+            hit_dice: DiceRoll::new(2, Dice::D6),
+            speed: 30,
+            ability_scores: CharacterAbilities {
+                strength: 8,
+                dexterity: 14,
+                constitution: 10,
+                intelligence: 10,
+                wisdom: 8,
+                charisma: 8,
+            },
+            saving_throws: vec![],
+            skills: vec![],
+            damage_vulnerabilities: vec![],
+            damage_resistances: vec![],
+            damage_immunities: vec![],
+            condition_immunities: vec![],
+            challenge_rating: ChallengeRating(0.25),
+            attacks: vec![MonsterAttack {
+                name: "Scimitar".to_string(),
+                attack_bonus: 4,
+                reach: 5.0,
+                range: None,
+                damage: DiceRoll {
+                    amount: 1,
+                    dice: Dice::D6,
+                    modifier: 2,
+                },
+                damage_type: DamageType::Slashing,
+            }],
+            multiattack: None,
+            legendary_actions: None,
+        }
+    }
+
+    // a character instantiated from the goblin stat block
+    pub fn goblin_instance(campaign_id: CampaignId) -> Character {
+        let mut instance = character(campaign_id);
+        instance.name = "Goblin".to_string();
+        instance.race = None;
+        instance.monster = Some(MonsterInstance {
+            monster_id: MonsterId::new(),
+            stat_block: goblin(),
+        });
+        instance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test::MockDatabase;
+
+    #[test]
+    fn challenge_ratings_determine_experience_and_proficiency() {
+        assert_eq!(ChallengeRating(0.25).experience(), Some(50));
+        assert_eq!(ChallengeRating(1.0).experience(), Some(200));
+        assert_eq!(ChallengeRating(30.0).experience(), Some(155000));
+        assert_eq!(ChallengeRating(0.3).experience(), None);
+        assert_eq!(ChallengeRating(31.0).experience(), None);
+
+        assert_eq!(ChallengeRating(0.5).proficiency_bonus(), 2);
+        assert_eq!(ChallengeRating(5.0).proficiency_bonus(), 3);
+        assert_eq!(ChallengeRating(12.0).proficiency_bonus(), 4);
+        assert_eq!(ChallengeRating(17.0).proficiency_bonus(), 6);
+        assert_eq!(ChallengeRating(30.0).proficiency_bonus(), 9);
+    }
+
+    #[actix_rt::test]
+    async fn monsters_add_their_dexterity_to_initiative() {
+        let db = MockDatabase::new();
+        let mut goblin = test::goblin_instance(CampaignId::new());
+
+        goblin.recalculate_stats(&db).await.unwrap();
+
+        assert_eq!(goblin.stats.initiative, 2);
+    }
+}
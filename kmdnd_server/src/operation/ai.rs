@@ -0,0 +1,190 @@
+use rand::Rng;
+
+use crate::character::{Character, Position};
+use crate::item::{DamageType, Dice, DiceRoll, Item};
+use crate::operation::{AbilityType, AttackMethodBody};
+
+// a little slack so rounding never leaves a destination just out of range or
+// just beyond the movement left
+const SLACK: f32 = 0.01;
+
+// an attack a character can make on its own along with the rolls it makes for
+// it, the bonuses the attack pipeline applies itself are left out of the rolls
+#[derive(Clone, Debug)]
+pub struct AttackOption {
+    pub method: AttackMethodBody,
+    pub range: f32,
+    pub hit_modifier: i32,
+    pub damage: DiceRoll,
+    pub average_damage: f32,
+}
+
+impl AttackOption {
+    pub fn roll_hit<R: Rng>(&self, character: &Character, rng: &mut R) -> i32 {
+        character.roll_d20(rng) + self.hit_modifier
+    }
+
+    pub fn roll_damage<R: Rng>(&self, rng: &mut R) -> i32 {
+        self.damage.roll(rng)
+    }
+}
+
+// the attack with the most expected damage among the stat block's attacks, the
+// equiped weapons and an unarmed strike
+pub fn best_attack(character: &Character, weapons: &[Item]) -> AttackOption {
+    let abilities = &character.stats.abilities;
+    let strength = abilities.modifier(AbilityType::Strength);
+    let dexterity = abilities.modifier(AbilityType::Dexterity);
+    let proficiency_bonus = character.stats.proficiency_bonus;
+
+    let mut options = vec![AttackOption {
+        method: AttackMethodBody::Unarmed {
+            damage_type: DamageType::Bludgeoning,
+        },
+        range: 5.0,
+        hit_modifier: strength + proficiency_bonus,
+        damage: DiceRoll {
+            amount: 0,
+            dice: Dice::D4,
+            modifier: i32::max(1 + strength, 1),
+        },
+        average_damage: i32::max(1 + strength, 1) as f32,
+    }];
+
+    if let Some(stat_block) = character.stat_block() {
+        for attack in &stat_block.attacks {
+            options.push(AttackOption {
+                method: AttackMethodBody::Natural {
+                    name: attack.name.clone(),
+                },
+                range: match &attack.range {
+                    Some(range) => range.normal as f32,
+                    None => attack.reach,
+                },
+                hit_modifier: 0,
+                damage: DiceRoll::new(attack.damage.amount, attack.damage.dice),
+                average_damage: average(&attack.damage),
+            });
+        }
+    }
+
+    for item in weapons {
+        let weapon = match item.item_type.clone().into_weapon() {
+            Some(weapon) => weapon,
+            None => continue,
+        };
+
+        let ability_modifier = if weapon.is_ranged() {
+            dexterity
+        } else if weapon.is_finesse() {
+            i32::max(strength, dexterity)
+        } else {
+            strength
+        };
+        let magic_bonus = character
+            .active_magic(item)
+            .map(|magic| magic.bonus)
+            .unwrap_or(0);
+
+        let damage = DiceRoll {
+            amount: 1,
+            dice: weapon.damage_amount,
+            modifier: ability_modifier,
+        };
+        options.push(AttackOption {
+            method: AttackMethodBody::Weapon { weapon_id: item.id },
+            range: weapon.normal_range(),
+            hit_modifier: ability_modifier + proficiency_bonus,
+            average_damage: average(&damage) + magic_bonus as f32,
+            damage,
+        });
+    }
+
+    options
+        .into_iter()
+        .fold(None, |best: Option<AttackOption>, option| match best {
+            Some(best) if best.average_damage >= option.average_damage => Some(best),
+            _ => Some(option),
+        })
+        .expect("an unarmed strike is always possible")
+}
+
+fn average(roll: &DiceRoll) -> f32 {
+    roll.amount as f32 * (roll.dice.sides() + 1) as f32 / 2.0 + roll.modifier as f32
+}
+
+// the weakest hostile that can be reached this turn, or the nearest one if
+// none can be
+pub fn choose_target<'a>(
+    position: &Position,
+    reach: f32,
+    hostiles: &[&'a Character],
+) -> Option<&'a Character> {
+    let distance = |character: &Character| {
+        character
+            .position
+            .as_ref()
+            .map(|other| Position::distance(position, other))
+            .unwrap_or(f32::INFINITY)
+    };
+
+    let reachable = hostiles
+        .iter()
+        .filter(|hostile| distance(hostile) <= reach)
+        .min_by_key(|hostile| hostile.current_hit_points);
+    if let Some(target) = reachable {
+        return Some(target);
+    }
+
+    hostiles
+        .iter()
+        .filter(|hostile| hostile.position.is_some())
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .copied()
+}
+
+// the furthest point toward the target within the movement, stopping once the
+// target is in range
+pub fn approach(from: &Position, to: &Position, range: f32, movement: f32) -> Option<Position> {
+    let distance = Position::distance(from, to);
+    let feet = f32::min(movement - SLACK, distance - range + SLACK);
+    if distance <= range || feet <= 0.0 {
+        return None;
+    }
+
+    let ratio = feet / distance;
+    Some(Position {
+        x: from.x + (to.x - from.x) * ratio,
+        y: from.y + (to.y - from.y) * ratio,
+        z: from.z + (to.z - from.z) * ratio,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approach_stops_in_range_or_at_the_limit_of_movement() {
+        let from = Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let to = Position {
+            x: 40.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        let destination = approach(&from, &to, 5.0, 60.0).unwrap();
+        assert!(Position::distance(&destination, &to) <= 5.0);
+
+        let destination = approach(&from, &to, 5.0, 30.0).unwrap();
+        assert!(Position::distance(&from, &destination) <= 30.0);
+        assert!(destination.x > 29.0);
+
+        assert!(approach(&from, &to, 60.0, 30.0).is_none());
+        assert!(approach(&from, &to, 5.0, 0.0).is_none());
+    }
+}
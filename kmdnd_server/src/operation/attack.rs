@@ -1,18 +1,26 @@
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::Campaign;
-use crate::character::{self, Character, CharacterId, Position};
+use crate::character::{self, Character, CharacterId, Condition, Position};
 use crate::database::Database;
-use crate::encounter::Encounter;
+use crate::encounter::{self, Encounter, EncounterState};
 use crate::error::Error;
-use crate::item::{DamageType, Weapon};
-use crate::operation::{Interaction, InteractionId, RollType};
+use crate::item::{DamageType, Dice, DiceRoll, Weapon};
+use crate::monster::MonsterAttack;
+use crate::operation::{Interaction, InteractionId, Operation, RollType};
 use crate::violations::Violation;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attack {
     pub method: AttackMethod,
     pub targets: Vec<CharacterId>,
+    pub sneak_attack: Option<DiceRoll>,
+    // the targets with another of their enemies beside them, only a hit on
+    // one of these can carry the sneak attack dice
+    #[serde(default)]
+    pub sneak_attack_targets: Vec<CharacterId>,
+    pub attack_bonus: i32,
+    pub damage_bonus: i32,
 }
 
 impl Attack {
@@ -21,23 +29,12 @@ impl Attack {
         campaign: &Campaign,
         encounter: &Encounter,
         source_character: Character,
-        target_character_id: CharacterId,
+        target_character_ids: Vec<CharacterId>,
         method: AttackMethod,
+        magic_bonus: i32,
     ) -> Result<(Attack, Vec<Interaction>, Vec<Violation>), Error> {
-        let target_character =
-            character::manager::get_character_by_id(db, campaign, target_character_id)
-                .await?
-                .ok_or(Error::CharacterNotInCampaign {
-                    campaign_id: campaign.id,
-                    character_id: target_character_id,
-                })?;
-
-        if !encounter.character_ids.contains(&target_character_id) {
-            return Err(Error::CharacterNotInEncounter {
-                campaign_id: campaign.id,
-                encounter_id: encounter.id,
-                character_id: target_character_id,
-            });
+        if target_character_ids.is_empty() {
+            return Err(Error::AttackHasNoTargets);
         }
 
         let source_position =
@@ -47,37 +44,126 @@ impl Attack {
                 .ok_or(Error::CharacterDoesNotHavePosition {
                     character_id: source_character.id,
                 })?;
-        let target_position =
-            target_character
-                .position
-                .as_ref()
-                .ok_or(Error::CharacterDoesNotHavePosition {
-                    character_id: target_character.id,
-                })?;
 
         let mut violations = vec![];
 
-        let attack_range = method.normal_range();
-        let current_range = Position::distance(source_position, target_position);
-        if attack_range < current_range {
-            violations.push(Violation::AttackNotInRange {
+        let maximum_attacks = source_character.attacks_per_action();
+        if target_character_ids.len() as i32 > maximum_attacks {
+            violations.push(Violation::AttackCountExceeded {
                 request_character_id: source_character.id,
-                target_character_id: target_character.id,
-                attack_range,
-                current_range,
+                maximum_attacks,
+                request_attacks: target_character_ids.len() as i32,
             });
         }
 
-        let interactions = vec![Interaction {
-            id: InteractionId::new(),
-            character_id: source_character.id,
-            roll_type: RollType::Hit,
-            result: None,
-        }];
+        // sneak attack needs another enemy of the target beside it, since
+        // advantage isn't tracked
+        let others = match source_character.sneak_attack_dice() {
+            Some(_) => encounter::manager::get_encounter_characters(db, encounter).await?,
+            None => vec![],
+        };
+        let mut sneak_attack_targets = vec![];
+
+        let mut interactions = vec![];
+        for &target_character_id in &target_character_ids {
+            let target_character =
+                character::manager::get_character_by_id(db, campaign, target_character_id)
+                    .await?
+                    .ok_or(Error::CharacterNotInCampaign {
+                        campaign_id: campaign.id,
+                        character_id: target_character_id,
+                    })?;
+
+            if !encounter.character_ids.contains(&target_character_id) {
+                return Err(Error::CharacterNotInEncounter {
+                    campaign_id: campaign.id,
+                    encounter_id: encounter.id,
+                    character_id: target_character_id,
+                });
+            }
+
+            let target_position =
+                target_character
+                    .position
+                    .as_ref()
+                    .ok_or(Error::CharacterDoesNotHavePosition {
+                        character_id: target_character.id,
+                    })?;
+
+            if encounter.are_allies(&source_character, &target_character) {
+                violations.push(Violation::FriendlyFire {
+                    request_character_id: source_character.id,
+                    target_character_id,
+                });
+            }
+
+            let beside_enemy = others.iter().any(|other| {
+                other.id != source_character.id
+                    && encounter.are_hostile(other, &target_character)
+                    && !other.is_incapacitated()
+                    && other.position.is_some_and(|position| {
+                        Position::distance(&position, target_position) <= 5.0
+                    })
+            });
+            if beside_enemy && !sneak_attack_targets.contains(&target_character_id) {
+                sneak_attack_targets.push(target_character_id);
+            }
+
+            let attack_range = method.normal_range();
+            let current_range = Position::distance(source_position, target_position);
+            if attack_range < current_range {
+                violations.push(Violation::AttackNotInRange {
+                    request_character_id: source_character.id,
+                    target_character_id: target_character.id,
+                    attack_range,
+                    current_range,
+                });
+            }
+
+            interactions.push(Interaction {
+                id: InteractionId::new(),
+                character_id: source_character.id,
+                target_character_id: Some(target_character.id),
+                roll_type: RollType::Hit,
+                result: None,
+                bonus_dice: None,
+            });
+        }
+
+        let sneak_attack = match source_character.sneak_attack_dice() {
+            Some(dice) if method.is_finesse_or_ranged() && !sneak_attack_targets.is_empty() => {
+                Some(DiceRoll::new(dice, Dice::D6))
+            }
+            _ => None,
+        };
+        if sneak_attack.is_none() {
+            sneak_attack_targets.clear();
+        }
+
+        let damage_bonus = match source_character.rage_damage() {
+            Some(damage)
+                if method.is_melee()
+                    && source_character.conditions.contains(&Condition::Raging) =>
+            {
+                damage
+            }
+            _ => 0,
+        };
+
+        // only the dice are rolled for a stat block's attack since its bonuses
+        // are already known
+        let (natural_attack_bonus, natural_damage_bonus) = match &method {
+            AttackMethod::Natural(attack) => (attack.attack_bonus, attack.damage.modifier),
+            _ => (0, 0),
+        };
 
         let attack = Attack {
             method,
-            targets: vec![target_character.id],
+            targets: target_character_ids,
+            sneak_attack,
+            sneak_attack_targets,
+            attack_bonus: magic_bonus + natural_attack_bonus,
+            damage_bonus: damage_bonus + magic_bonus + natural_damage_bonus,
         };
 
         Ok((attack, interactions, violations))
@@ -87,34 +173,52 @@ impl Attack {
         &self,
         db: &dyn Database,
         campaign: &Campaign,
+        operation: &Operation,
         interaction: &Interaction,
         result: i32,
     ) -> Result<Vec<Interaction>, Error> {
         let new_interactions = match interaction.roll_type {
             RollType::Hit => {
-                let target_character_id = self.targets[0]; // TODO:
+                let target_character_id = interaction.expect_target_character_id()?;
                 let target_character =
                     character::manager::expect_character_by_id(db, campaign, target_character_id)
                         .await?;
 
-                if target_character.stats.armor_class <= result {
+                if target_character.stats.armor_class <= result + self.attack_bonus {
+                    // sneak attack lands with the first hit on an eligible
+                    // target and only once per turn
+                    let bonus_dice = match &self.sneak_attack {
+                        Some(dice)
+                            if self.sneak_attack_targets.contains(&target_character_id)
+                                && !used_sneak_attack(db, operation).await? =>
+                        {
+                            Some(dice.clone())
+                        }
+                        _ => None,
+                    };
+
                     vec![Interaction {
                         id: InteractionId::new(),
                         character_id: interaction.character_id,
+                        target_character_id: Some(target_character_id),
                         roll_type: RollType::Damage,
                         result: None,
+                        bonus_dice,
                     }]
                 } else {
                     vec![]
                 }
             }
             RollType::Damage => {
-                let target_character_id = self.targets[0]; // TODO:
+                let target_character_id = interaction.expect_target_character_id()?;
                 let target_character =
                     character::manager::expect_character_by_id(db, campaign, target_character_id)
                         .await?;
 
-                let new_hit_points = i32::max(target_character.current_hit_points - result, 0);
+                let damage = target_character
+                    .resisted_damage(result + self.damage_bonus, self.method.damage_type());
+
+                let new_hit_points = i32::max(target_character.current_hit_points - damage, 0);
                 character::manager::update_character_hit_points(
                     db,
                     target_character,
@@ -133,12 +237,47 @@ impl Attack {
     }
 }
 
+// whether the attacker already rolled sneak attack damage in this operation or
+// elsewhere during the same turn
+async fn used_sneak_attack(db: &dyn Database, operation: &Operation) -> Result<bool, Error> {
+    let carries_dice = |op: &Operation| {
+        op.interactions.iter().any(|interaction| {
+            interaction.character_id == operation.character_id && interaction.bonus_dice.is_some()
+        })
+    };
+
+    if carries_dice(operation) {
+        return Ok(true);
+    }
+
+    let turn_operations = match (operation.encounter_id, &operation.encounter_state) {
+        (
+            Some(encounter_id),
+            Some(EncounterState::Turn {
+                round,
+                character_id,
+            }),
+        ) => {
+            db.operations()
+                .fetch_operations_by_turn(encounter_id, *round, *character_id)
+                .await?
+        }
+        _ => vec![],
+    };
+
+    Ok(turn_operations
+        .iter()
+        .filter(|op| op.id != operation.id)
+        .any(carries_dice))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum AttackMethod {
     Unarmed(DamageType),
     Weapon(Weapon),
     ImprovisedWeapon(Weapon), // TODO: maybe Item
+    Natural(MonsterAttack),
 }
 
 impl AttackMethod {
@@ -147,6 +286,182 @@ impl AttackMethod {
             AttackMethod::Unarmed(_) => 5.0,
             AttackMethod::Weapon(weapon) => weapon.normal_range(),
             AttackMethod::ImprovisedWeapon(weapon) => weapon.normal_range(),
+            AttackMethod::Natural(attack) => match &attack.range {
+                Some(range) => range.normal as f32,
+                None => attack.reach,
+            },
+        }
+    }
+
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            AttackMethod::Unarmed(damage_type) => *damage_type,
+            AttackMethod::Weapon(weapon) => weapon.damage_type,
+            AttackMethod::ImprovisedWeapon(weapon) => weapon.damage_type,
+            AttackMethod::Natural(attack) => attack.damage_type,
+        }
+    }
+
+    pub fn is_melee(&self) -> bool {
+        match self {
+            AttackMethod::Unarmed(_) => true,
+            AttackMethod::Weapon(weapon) => !weapon.is_ranged(),
+            AttackMethod::ImprovisedWeapon(_) => true,
+            AttackMethod::Natural(attack) => attack.range.is_none(),
+        }
+    }
+
+    pub fn is_finesse_or_ranged(&self) -> bool {
+        match self {
+            AttackMethod::Weapon(weapon) => weapon.is_finesse() || weapon.is_ranged(),
+            AttackMethod::Natural(attack) => attack.range.is_some(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::test::campaign;
+    use crate::character::class::{Class, ClassLevel};
+    use crate::character::test::character;
+    use crate::database::test::MockDatabase;
+    use crate::encounter::test::encounter;
+    use crate::encounter::{Faction, Participant};
+    use crate::item::WeaponProperty;
+    use crate::operation::{Action, Legality, OperationId, OperationType};
+    use chrono::Utc;
+
+    fn at(campaign: &Campaign, x: f32, y: f32) -> Character {
+        let mut character = character(campaign.id);
+        character.position = Some(Position { x, y, z: 0.0 });
+        character
+    }
+
+    fn dagger() -> AttackMethod {
+        AttackMethod::Weapon(Weapon {
+            damage_amount: Dice::D4,
+            damage_type: DamageType::Piercing,
+            properties: vec![WeaponProperty::Finesse, WeaponProperty::Light],
+        })
+    }
+
+    fn attack_operation(
+        encounter: &Encounter,
+        attack: Attack,
+        interactions: Vec<Interaction>,
+    ) -> Operation {
+        let now = Utc::now();
+        Operation {
+            id: OperationId::new(),
+            campaign_id: encounter.campaign_id,
+            encounter_id: Some(encounter.id),
+            encounter_state: Some(encounter.state.clone()),
+            character_id: interactions[0].character_id,
+            created_at: now,
+            modified_at: now,
+            operation_type: OperationType::Action(Action::Attack(attack)),
+            interactions,
+            legality: Legality::Legal,
+        }
+    }
+
+    // a rogue with an ally beside the first goblin but not the second
+    #[actix_rt::test]
+    async fn sneak_attack_lands_once_on_an_eligible_target() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let mut rogue = at(&campaign, 0.0, 0.0);
+        rogue.classes = vec![ClassLevel {
+            class: Class::Rogue,
+            level: 3,
+            subclass: None,
+        }];
+        let ally = at(&campaign, 10.0, 0.0);
+        let flanked = at(&campaign, 5.0, 0.0);
+        let alone = at(&campaign, 0.0, 5.0);
+
+        let mut encounter = encounter(campaign.id, vec![rogue.id, ally.id, flanked.id, alone.id]);
+        encounter.factions = vec![
+            Participant {
+                character_id: rogue.id,
+                faction: Faction::Party,
+            },
+            Participant {
+                character_id: ally.id,
+                faction: Faction::Party,
+            },
+            Participant {
+                character_id: flanked.id,
+                faction: Faction::Enemies,
+            },
+            Participant {
+                character_id: alone.id,
+                faction: Faction::Enemies,
+            },
+        ];
+        encounter.state = EncounterState::Turn {
+            round: 1,
+            character_id: rogue.id,
+        };
+
+        let characters = vec![rogue.clone(), ally, flanked.clone(), alone.clone()];
+        let characters_clone = characters.clone();
+        db.characters.on_fetch_characters_by_campaign =
+            Box::new(move |_| Ok(characters_clone.clone()));
+        db.characters.on_fetch_character_by_campaign_and_id = Box::new(move |_, id| {
+            Ok(characters
+                .iter()
+                .find(|character| character.id == id)
+                .cloned())
+        });
+        db.operations.on_fetch_operations_by_turn = Box::new(|_, _, _| Ok(vec![]));
+
+        let (attack, interactions, _) = Attack::submit(
+            &db,
+            &campaign,
+            &encounter,
+            rogue,
+            vec![alone.id, flanked.id, flanked.id],
+            dagger(),
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(attack.sneak_attack_targets, vec![flanked.id]);
+        assert!(attack.sneak_attack.is_some());
+
+        let mut operation = attack_operation(&encounter, attack.clone(), interactions);
+        for index in 0..3 {
+            let interaction = operation.interactions[index].clone();
+            let damage = attack
+                .handle_interaction_result(&db, &campaign, &operation, &interaction, 30)
+                .await
+                .unwrap();
+            operation.interactions.extend(damage);
         }
+
+        let bonus_dice: Vec<_> = operation.interactions[3..]
+            .iter()
+            .map(|interaction| interaction.bonus_dice.is_some())
+            .collect();
+        assert_eq!(
+            bonus_dice,
+            vec![false, true, false],
+            "sneak attack should only land on the first hit against the flanked goblin"
+        );
+
+        // a second attack the same turn doesn't get it again
+        let earlier = operation.clone();
+        db.operations.on_fetch_operations_by_turn =
+            Box::new(move |_, _, _| Ok(vec![earlier.clone()]));
+        let interactions = vec![operation.interactions[2].clone()];
+        let later = attack_operation(&encounter, attack.clone(), interactions);
+        let damage = attack
+            .handle_interaction_result(&db, &campaign, &later, &later.interactions[0], 30)
+            .await
+            .unwrap();
+        assert!(damage[0].bonus_dice.is_none());
     }
 }
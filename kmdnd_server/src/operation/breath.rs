@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::character::class::{FeatureUse, LimitedFeature};
+use crate::character::race::{BreathShape, RacialTrait};
+use crate::character::{self, Character, CharacterId, Position};
+use crate::database::Database;
+use crate::encounter::Encounter;
+use crate::error::Error;
+use crate::item::{DamageType, Dice, DiceRoll};
+use crate::operation::{AbilityType, Interaction, InteractionId, Operation, RollType};
+use crate::violations::Violation;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreathWeapon {
+    pub toward: Position,
+    pub shape: BreathShape,
+    pub damage: DiceRoll,
+    pub damage_type: DamageType,
+    pub save: AbilityType,
+    pub difficulty_class: i32,
+    pub affected: Vec<CharacterId>,
+}
+
+impl BreathWeapon {
+    pub async fn submit(
+        db: &dyn Database,
+        campaign: &Campaign,
+        encounter: &Encounter,
+        source_character: Character,
+        toward: Position,
+    ) -> Result<(BreathWeapon, Vec<Interaction>, Vec<Violation>), Error> {
+        let ancestry = source_character
+            .draconic_ancestry()
+            .filter(|_| {
+                source_character.has_racial_trait(|t| matches!(t, RacialTrait::BreathWeapon))
+            })
+            .ok_or(Error::CharacterDoesNotHaveFeature {
+                character_id: source_character.id,
+                feature: LimitedFeature::BreathWeapon,
+            })?;
+
+        let source_position =
+            source_character
+                .position
+                .as_ref()
+                .ok_or(Error::CharacterDoesNotHavePosition {
+                    character_id: source_character.id,
+                })?;
+
+        let mut violations = vec![];
+        if source_character.feature_uses_expended(LimitedFeature::BreathWeapon) >= 1 {
+            violations.push(Violation::FeatureUsesExhausted {
+                request_character_id: source_character.id,
+                feature: LimitedFeature::BreathWeapon,
+                maximum_uses: 1,
+            });
+        }
+
+        let shape = ancestry.breath_shape();
+        let mut affected = vec![];
+        for &character_id in &encounter.character_ids {
+            if character_id == source_character.id {
+                continue;
+            }
+
+            let character =
+                character::manager::expect_character_by_id(db, campaign, character_id).await?;
+            let in_area = character
+                .position
+                .map(|position| shape.contains(source_position, &toward, &position))
+                .unwrap_or(false);
+
+            if in_area {
+                if encounter.are_allies(&source_character, &character) {
+                    violations.push(Violation::FriendlyFire {
+                        request_character_id: source_character.id,
+                        target_character_id: character_id,
+                    });
+                }
+                affected.push(character_id);
+            }
+        }
+
+        let difficulty_class = 8
+            + source_character
+                .stats
+                .abilities
+                .modifier(AbilityType::Constitution)
+            + source_character.stats.proficiency_bonus;
+
+        let breath_weapon = BreathWeapon {
+            toward,
+            shape,
+            damage: DiceRoll::new(breath_weapon_dice(source_character.level()), Dice::D6),
+            damage_type: ancestry.damage_type(),
+            save: ancestry.breath_save(),
+            difficulty_class,
+            affected,
+        };
+
+        let interactions = vec![Interaction {
+            id: InteractionId::new(),
+            character_id: source_character.id,
+            target_character_id: None,
+            roll_type: RollType::Damage,
+            result: None,
+            bonus_dice: None,
+        }];
+
+        Ok((breath_weapon, interactions, violations))
+    }
+
+    pub async fn expend_use(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        character_id: CharacterId,
+    ) -> Result<(), Error> {
+        let character =
+            character::manager::expect_character_by_id(db, campaign, character_id).await?;
+
+        let mut feature_uses = character.feature_uses.clone();
+        feature_uses.retain(|usage| usage.feature != LimitedFeature::BreathWeapon);
+        feature_uses.push(FeatureUse {
+            feature: LimitedFeature::BreathWeapon,
+            expended: 1,
+        });
+
+        character::manager::update_character_feature_uses(db, character, feature_uses).await?;
+
+        Ok(())
+    }
+
+    pub async fn handle_interaction_result(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        operation: &Operation,
+        interaction: &Interaction,
+        result: i32,
+    ) -> Result<Vec<Interaction>, Error> {
+        let new_interactions = match interaction.roll_type {
+            RollType::Damage => self
+                .affected
+                .iter()
+                .map(|&character_id| Interaction {
+                    id: InteractionId::new(),
+                    character_id,
+                    target_character_id: None,
+                    roll_type: RollType::Save(self.save),
+                    result: None,
+                    bonus_dice: None,
+                })
+                .collect(),
+            RollType::Save(ability) if ability == self.save => {
+                let target_character = character::manager::expect_character_by_id(
+                    db,
+                    campaign,
+                    interaction.character_id,
+                )
+                .await?;
+
+                let max_damage = operation
+                    .interactions
+                    .iter()
+                    .find(|i| i.roll_type == RollType::Damage)
+                    .and_then(|i| i.result)
+                    .ok_or_else(|| {
+                        Error::ExistentialState(
+                            "Expected breath weapon to have damage roll result".to_string(),
+                        )
+                    })?;
+
+                let damage = if result < self.difficulty_class {
+                    max_damage
+                } else {
+                    max_damage / 2
+                };
+                let damage = target_character.resisted_damage(damage, self.damage_type);
+
+                let new_hit_points = i32::max(target_character.current_hit_points - damage, 0);
+                character::manager::update_character_hit_points(
+                    db,
+                    target_character,
+                    new_hit_points,
+                )
+                .await?;
+
+                vec![]
+            }
+            _ => vec![],
+        };
+
+        Ok(new_interactions)
+    }
+}
+
+fn breath_weapon_dice(character_level: i32) -> i32 {
+    match character_level {
+        i32::MIN..=5 => 2,
+        6..=10 => 3,
+        11..=15 => 4,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breath_weapon_damage_scales_with_level() {
+        assert_eq!(breath_weapon_dice(1), 2);
+        assert_eq!(breath_weapon_dice(6), 3);
+        assert_eq!(breath_weapon_dice(11), 4);
+        assert_eq!(breath_weapon_dice(16), 5);
+    }
+}
@@ -244,3 +244,130 @@ impl OperationStore for MongoOperationStore {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+pub struct MockOperationStore {
+    pub on_insert_operation: Box<dyn Fn(&Operation) -> Result<(), Error> + Send + Sync>,
+    pub on_fetch_operation_by_id:
+        Box<dyn Fn(OperationId) -> Result<Option<Operation>, Error> + Send + Sync>,
+    pub on_fetch_operations_by_campaign:
+        Box<dyn Fn(CampaignId) -> Result<Vec<Operation>, Error> + Send + Sync>,
+    pub on_fetch_operations_by_encounter:
+        Box<dyn Fn(EncounterId) -> Result<Vec<Operation>, Error> + Send + Sync>,
+    pub on_fetch_operations_by_turn:
+        Box<dyn Fn(EncounterId, Round, CharacterId) -> Result<Vec<Operation>, Error> + Send + Sync>,
+    pub on_update_operation_interaction_result:
+        Box<dyn Fn(Operation, usize, i32) -> Result<Operation, Error> + Send + Sync>,
+    pub on_update_operation_push_interactions:
+        Box<dyn Fn(Operation, Vec<Interaction>) -> Result<Operation, Error> + Send + Sync>,
+    pub on_update_operation_legality:
+        Box<dyn Fn(Operation, Legality) -> Result<Operation, Error> + Send + Sync>,
+    pub on_delete_operation: Box<dyn Fn(OperationId) -> Result<(), Error> + Send + Sync>,
+}
+
+#[cfg(test)]
+impl MockOperationStore {
+    pub fn new() -> MockOperationStore {
+        MockOperationStore {
+            on_insert_operation: Box::new(|_| {
+                unimplemented!("MockOperationStore::insert_operation")
+            }),
+            on_fetch_operation_by_id: Box::new(|_| {
+                unimplemented!("MockOperationStore::fetch_operation_by_id")
+            }),
+            on_fetch_operations_by_campaign: Box::new(|_| {
+                unimplemented!("MockOperationStore::fetch_operations_by_campaign")
+            }),
+            on_fetch_operations_by_encounter: Box::new(|_| {
+                unimplemented!("MockOperationStore::fetch_operations_by_encounter")
+            }),
+            on_fetch_operations_by_turn: Box::new(|_, _, _| {
+                unimplemented!("MockOperationStore::fetch_operations_by_turn")
+            }),
+            on_update_operation_interaction_result: Box::new(|_, _, _| {
+                unimplemented!("MockOperationStore::update_operation_interaction_result")
+            }),
+            on_update_operation_push_interactions: Box::new(|_, _| {
+                unimplemented!("MockOperationStore::update_operation_push_interactions")
+            }),
+            on_update_operation_legality: Box::new(|_, _| {
+                unimplemented!("MockOperationStore::update_operation_legality")
+            }),
+            on_delete_operation: Box::new(|_| {
+                unimplemented!("MockOperationStore::delete_operation")
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl OperationStore for MockOperationStore {
+    async fn insert_operation(&self, operation: &Operation) -> Result<(), Error> {
+        (self.on_insert_operation)(operation)
+    }
+
+    async fn fetch_operation_by_id(
+        &self,
+        operation_id: OperationId,
+    ) -> Result<Option<Operation>, Error> {
+        (self.on_fetch_operation_by_id)(operation_id)
+    }
+
+    async fn fetch_operations_by_campaign(
+        &self,
+        campaign_id: CampaignId,
+    ) -> Result<Vec<Operation>, Error> {
+        (self.on_fetch_operations_by_campaign)(campaign_id)
+    }
+
+    async fn fetch_operations_by_encounter(
+        &self,
+        encounter_id: EncounterId,
+    ) -> Result<Vec<Operation>, Error> {
+        (self.on_fetch_operations_by_encounter)(encounter_id)
+    }
+
+    async fn fetch_operations_by_turn(
+        &self,
+        encounter_id: EncounterId,
+        round: Round,
+        character_id: CharacterId,
+    ) -> Result<Vec<Operation>, Error> {
+        (self.on_fetch_operations_by_turn)(encounter_id, round, character_id)
+    }
+
+    async fn update_operation_interaction_result(
+        &self,
+        operation: Operation,
+        interaction_index: usize,
+        interaction_result: i32,
+    ) -> Result<Operation, Error> {
+        (self.on_update_operation_interaction_result)(
+            operation,
+            interaction_index,
+            interaction_result,
+        )
+    }
+
+    async fn update_operation_push_interactions(
+        &self,
+        operation: Operation,
+        interactions: Vec<Interaction>,
+    ) -> Result<Operation, Error> {
+        (self.on_update_operation_push_interactions)(operation, interactions)
+    }
+
+    async fn update_operation_legality(
+        &self,
+        operation: Operation,
+        legality: Legality,
+    ) -> Result<Operation, Error> {
+        (self.on_update_operation_legality)(operation, legality)
+    }
+
+    async fn delete_operation(&self, operation_id: OperationId) -> Result<(), Error> {
+        (self.on_delete_operation)(operation_id)
+    }
+}
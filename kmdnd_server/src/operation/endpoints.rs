@@ -3,13 +3,17 @@ use actix_web::{get, post};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::campaign::{self, CampaignId};
-use crate::character::{CharacterId, Position};
+use crate::campaign::{self, Campaign, CampaignId};
+use crate::character::class::LimitedFeature;
+use crate::character::{Character, CharacterId, Position};
+use crate::currency::Purse;
 use crate::database::Database;
 use crate::encounter::{self, EncounterId, EncounterState};
 use crate::error::Error;
-use crate::item::{self, DamageType, ItemId};
+use crate::item::{self, DamageType, Dice, ItemId};
 use crate::operation::attack::AttackMethod;
+use crate::operation::inventory::InventoryChange;
+use crate::operation::rest::RestType;
 use crate::operation::{Interaction, InteractionId, Legality};
 use crate::utils::SuccessBody;
 
@@ -65,6 +69,7 @@ pub struct ActionBody {
 pub enum ActionTypeBody {
     Attack(AttackBody),
     CastSpell(CastBody),
+    BreathWeapon { toward: Position },
     Dash,
     Disengage,
     Dodge,
@@ -77,14 +82,90 @@ pub enum ActionTypeBody {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct AttackBody {
-    pub target_character_id: CharacterId,
+    pub target_character_ids: Vec<CharacterId>,
     pub method: AttackMethodBody,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct LegendaryActionBody {
+    pub character_id: CharacterId,
+    pub name: String,
+    #[serde(default)]
+    pub target_character_ids: Vec<CharacterId>,
+    pub ignore_violations: bool,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct CastBody {
     pub name: String,
-    pub target: SpellTarget,
+    pub targets: Vec<SpellTarget>,
+    pub slot_level: Option<i32>,
+    #[serde(default)]
+    pub ritual: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CampaignCastBody {
+    pub character_id: CharacterId,
+    pub cast: CastBody,
+    pub ignore_violations: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RestBody {
+    pub character_id: CharacterId,
+    pub rest_type: RestType,
+    #[serde(default)]
+    pub hit_dice: Vec<Dice>,
+    #[serde(default)]
+    pub attune: Vec<ItemId>,
+    #[serde(default)]
+    pub end_attunement: Vec<ItemId>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct InventoryBody {
+    pub character_id: CharacterId,
+    pub change: InventoryChange,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ItemUseBody {
+    pub character_id: CharacterId,
+    pub item_id: ItemId,
+    // the character given a potion, otherwise the user drinks it
+    pub target_character_id: Option<CharacterId>,
+    // the targets of a spell cast from a scroll
+    #[serde(default)]
+    pub targets: Vec<SpellTarget>,
+    #[serde(default)]
+    pub ignore_violations: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TradeBody {
+    pub character_id: CharacterId,
+    pub trade: TradeTypeBody,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum TradeTypeBody {
+    Buy {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    Sell {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    GiveCoins {
+        coins: Purse,
+        target_character_id: Option<CharacterId>,
+    },
+    TakeCoins {
+        coins: Purse,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -93,26 +174,38 @@ pub enum AttackMethodBody {
     Unarmed { damage_type: DamageType },
     Weapon { weapon_id: ItemId },
     ImprovisedWeapon { weapon_id: ItemId },
+    // an attack from the creature's stat block
+    Natural { name: String },
 }
 
 impl AttackMethodBody {
-    pub async fn into_attack_method(self, db: &dyn Database) -> Result<AttackMethod, Error> {
+    // also returns the bonus to attack and damage rolls of a magic weapon
+    pub async fn into_attack_method(
+        self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        character: &Character,
+    ) -> Result<(AttackMethod, i32), Error> {
         let attack_method = match self {
-            AttackMethodBody::Unarmed { damage_type } => AttackMethod::Unarmed(damage_type),
+            AttackMethodBody::Unarmed { damage_type } => (AttackMethod::Unarmed(damage_type), 0),
             AttackMethodBody::Weapon { weapon_id } => {
-                let item = item::manager::get_item_by_id(db, weapon_id)
+                let item = item::manager::get_item_by_id(db, Some(campaign.id), weapon_id)
                     .await?
                     .ok_or(Error::ItemDoesNotExist { item_id: weapon_id })?;
 
+                let magic_bonus = character
+                    .active_magic(&item)
+                    .map(|magic| magic.bonus)
+                    .unwrap_or(0);
                 let weapon = item
                     .item_type
                     .into_weapon()
                     .ok_or(Error::ItemIsNotAWeapon { item_id: item.id })?;
 
-                AttackMethod::Weapon(weapon)
+                (AttackMethod::Weapon(weapon), magic_bonus)
             }
             AttackMethodBody::ImprovisedWeapon { weapon_id } => {
-                let item = item::manager::get_item_by_id(db, weapon_id)
+                let item = item::manager::get_item_by_id(db, Some(campaign.id), weapon_id)
                     .await?
                     .ok_or(Error::ItemDoesNotExist { item_id: weapon_id })?;
 
@@ -121,7 +214,18 @@ impl AttackMethodBody {
                     .into_weapon()
                     .ok_or(Error::ItemIsNotAWeapon { item_id: item.id })?;
 
-                AttackMethod::ImprovisedWeapon(weapon)
+                (AttackMethod::ImprovisedWeapon(weapon), 0)
+            }
+            AttackMethodBody::Natural { name } => {
+                let attack = character
+                    .stat_block()
+                    .and_then(|stat_block| stat_block.attack(&name))
+                    .ok_or(Error::MonsterActionNotFound {
+                        character_id: character.id,
+                        name: name.clone(),
+                    })?;
+
+                (AttackMethod::Natural(attack.clone()), 0)
             }
         };
 
@@ -129,6 +233,13 @@ impl AttackMethodBody {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct FeatureBody {
+    pub character_id: CharacterId,
+    pub feature: LimitedFeature,
+    pub ignore_violations: bool,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct RollBody {
     pub character_id: CharacterId,
@@ -147,6 +258,234 @@ pub struct SubmitInteractionBody {
     result: i32,
 }
 
+#[get("/campaigns/{campaign_id}/operations")]
+#[tracing::instrument(skip(db))]
+async fn get_operations_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+) -> Result<Json<Vec<OperationBody>>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+
+    let operations = manager::get_operations_in_campaign(&***db, &campaign).await?;
+
+    let body = operations.into_iter().map(OperationBody::render).collect();
+
+    Ok(Json(body))
+}
+
+#[get("/campaigns/{campaign_id}/operations/{operation_id}")]
+#[tracing::instrument(skip(db))]
+async fn get_operation_by_id_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, OperationId)>,
+) -> Result<Json<OperationBody>, Error> {
+    let (campaign_id, operation_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let operation = manager::get_operation_by_id_in_campaign(&***db, &campaign, operation_id)
+        .await?
+        .ok_or(Error::OperationNotFoundInCampaign {
+            campaign_id: campaign.id,
+            operation_id,
+        })?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/operations/{operation_id}/approve")]
+#[tracing::instrument(skip(db))]
+async fn approve_illegal_operation_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, OperationId)>,
+) -> Result<Json<SuccessBody>, Error> {
+    let (campaign_id, operation_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let operation = manager::get_operation_by_id_in_campaign(&***db, &campaign, operation_id)
+        .await?
+        .ok_or(Error::OperationNotFoundInCampaign {
+            campaign_id: campaign.id,
+            operation_id,
+        })?;
+
+    // operations in an encounter are resolved through the encounter
+    if let Some(encounter_id) = operation.encounter_id {
+        return Err(Error::OperationIsInEncounter {
+            operation_id,
+            encounter_id,
+        });
+    }
+
+    manager::approve_illegal_operation(&***db, &campaign, operation).await?;
+
+    Ok(Json(SuccessBody {}))
+}
+
+#[post("/campaigns/{campaign_id}/operations/{operation_id}/reject")]
+#[tracing::instrument(skip(db))]
+async fn reject_illegal_operation_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, OperationId)>,
+) -> Result<Json<SuccessBody>, Error> {
+    let (campaign_id, operation_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let operation = manager::get_operation_by_id_in_campaign(&***db, &campaign, operation_id)
+        .await?
+        .ok_or(Error::OperationNotFoundInCampaign {
+            campaign_id: campaign.id,
+            operation_id,
+        })?;
+
+    // operations in an encounter are resolved through the encounter
+    if let Some(encounter_id) = operation.encounter_id {
+        return Err(Error::OperationIsInEncounter {
+            operation_id,
+            encounter_id,
+        });
+    }
+
+    manager::reject_illegal_operation(&***db, &campaign, operation).await?;
+
+    Ok(Json(SuccessBody {}))
+}
+
+#[post("/campaigns/{campaign_id}/operations/{operation_id}/interactions")]
+#[tracing::instrument(skip(db))]
+async fn submit_interaction_result_to_campaign_operation(
+    db: Data<Box<dyn Database>>,
+    params: Path<(CampaignId, OperationId)>,
+    body: Json<SubmitInteractionBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let (campaign_id, operation_id) = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let operation = manager::get_operation_by_id_in_campaign(&***db, &campaign, operation_id)
+        .await?
+        .ok_or(Error::OperationNotFoundInCampaign {
+            campaign_id: campaign.id,
+            operation_id,
+        })?;
+
+    // operations in an encounter are resolved through the encounter
+    if let Some(encounter_id) = operation.encounter_id {
+        return Err(Error::OperationIsInEncounter {
+            operation_id,
+            encounter_id,
+        });
+    }
+
+    let operation = manager::submit_interaction_result(
+        &***db,
+        &campaign,
+        None,
+        operation,
+        body.interaction_id,
+        body.character_id,
+        body.result,
+    )
+    .await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/cast")]
+#[tracing::instrument(skip(db))]
+async fn cast_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<CampaignCastBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_cast_operation(&***db, &campaign, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/rest")]
+#[tracing::instrument(skip(db))]
+async fn rest_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<RestBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_rest_operation(&***db, &campaign, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/inventory")]
+#[tracing::instrument(skip(db))]
+async fn change_inventory_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<InventoryBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_inventory_operation(&***db, &campaign, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/use-item")]
+#[tracing::instrument(skip(db))]
+async fn use_item_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<ItemUseBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_item_use_operation(&***db, &campaign, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+#[post("/campaigns/{campaign_id}/trade")]
+#[tracing::instrument(skip(db))]
+async fn trade_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<TradeBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_trade_operation(&***db, campaign, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
 #[get("/campaigns/{campaign_id}/encounters/CURRENT/operations")]
 #[tracing::instrument(skip(db))]
 async fn get_operations_in_current_encounter_in_campaign(
@@ -219,7 +558,7 @@ async fn approve_illegal_operation(
                 operation_id,
             })?;
 
-    manager::approve_illegal_operation(&***db, &campaign, &encounter, operation).await?;
+    manager::approve_illegal_operation(&***db, &campaign, operation).await?;
 
     Ok(Json(SuccessBody {}))
 }
@@ -247,7 +586,7 @@ async fn reject_illegal_operation(
                 operation_id,
             })?;
 
-    manager::reject_illegal_operation(&***db, &campaign, &encounter, operation).await?;
+    manager::reject_illegal_operation(&***db, &campaign, operation).await?;
 
     Ok(Json(SuccessBody {}))
 }
@@ -279,7 +618,7 @@ async fn submit_interaction_result_to_operation(
     let operation = manager::submit_interaction_result(
         &***db,
         &campaign,
-        &encounter,
+        Some(&encounter),
         operation,
         body.interaction_id,
         body.character_id,
@@ -368,3 +707,74 @@ async fn take_action_in_current_encounter_in_campaign(
 
     Ok(Json(OperationBody::render(operation)))
 }
+
+#[post("/campaigns/{campaign_id}/encounters/CURRENT/legendary-action")]
+#[tracing::instrument(skip(db))]
+async fn take_legendary_action_in_current_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<LegendaryActionBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let encounter = encounter::manager::get_current_encounter(&***db, &campaign)
+        .await?
+        .ok_or(Error::CurrentEncounterNotFound {
+            campaign_id: campaign.id,
+        })?;
+    let body = body.into_inner();
+
+    let operation =
+        manager::create_legendary_action_operation(&***db, &campaign, &encounter, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
+
+// the server plays its characters as their turns come up, this plays one again
+#[post("/campaigns/{campaign_id}/encounters/CURRENT/ai-turn")]
+#[tracing::instrument(skip(db))]
+async fn take_ai_turn_in_current_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+) -> Result<Json<Vec<OperationBody>>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let encounter = encounter::manager::get_current_encounter(&***db, &campaign)
+        .await?
+        .ok_or(Error::CurrentEncounterNotFound {
+            campaign_id: campaign.id,
+        })?;
+
+    let operations = manager::take_ai_turn(&***db, &campaign, &encounter).await?;
+
+    let body = operations.into_iter().map(OperationBody::render).collect();
+
+    Ok(Json(body))
+}
+
+#[post("/campaigns/{campaign_id}/encounters/CURRENT/feature")]
+#[tracing::instrument(skip(db))]
+async fn use_feature_in_current_encounter_in_campaign(
+    db: Data<Box<dyn Database>>,
+    params: Path<CampaignId>,
+    body: Json<FeatureBody>,
+) -> Result<Json<OperationBody>, Error> {
+    let campaign_id = params.into_inner();
+    let campaign = campaign::manager::get_campaign_by_id(&***db, campaign_id)
+        .await?
+        .ok_or(Error::CampaignNotFound { campaign_id })?;
+    let encounter = encounter::manager::get_current_encounter(&***db, &campaign)
+        .await?
+        .ok_or(Error::CurrentEncounterNotFound {
+            campaign_id: campaign.id,
+        })?;
+    let body = body.into_inner();
+
+    let operation = manager::create_feature_operation(&***db, &campaign, &encounter, body).await?;
+
+    Ok(Json(OperationBody::render(operation)))
+}
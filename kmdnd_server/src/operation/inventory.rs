@@ -0,0 +1,455 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character::{Character, CharacterId, EquipmentEntry, ItemChargeUse};
+use crate::error::Error;
+use crate::item::{ArmorType, Item, ItemId};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum InventoryChange {
+    Give {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    Drop {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    Transfer {
+        item_id: ItemId,
+        quantity: i32,
+        target_character_id: CharacterId,
+    },
+    SetQuantity {
+        item_id: ItemId,
+        quantity: i32,
+    },
+    Equip {
+        item_id: ItemId,
+    },
+    Unequip {
+        item_id: ItemId,
+    },
+    UseCharges {
+        item_id: ItemId,
+        charges: i32,
+    },
+    // moves the whole stack into the container, or out of any container if
+    // none is given
+    Store {
+        item_id: ItemId,
+        container_id: Option<ItemId>,
+    },
+}
+
+impl InventoryChange {
+    pub fn item_id(&self) -> ItemId {
+        match *self {
+            InventoryChange::Give { item_id, .. }
+            | InventoryChange::Drop { item_id, .. }
+            | InventoryChange::Transfer { item_id, .. }
+            | InventoryChange::SetQuantity { item_id, .. }
+            | InventoryChange::Equip { item_id }
+            | InventoryChange::Unequip { item_id }
+            | InventoryChange::UseCharges { item_id, .. }
+            | InventoryChange::Store { item_id, .. } => item_id,
+        }
+    }
+}
+
+pub fn add_items(equipment: &mut Vec<EquipmentEntry>, item_id: ItemId, quantity: i32) {
+    match equipment.iter_mut().find(|entry| entry.item_id == item_id) {
+        Some(entry) => entry.quantity += quantity,
+        None => equipment.push(EquipmentEntry {
+            equiped: false,
+            quantity,
+            item_id,
+            container_id: None,
+        }),
+    }
+}
+
+pub fn remove_items(
+    character_id: CharacterId,
+    equipment: &mut Vec<EquipmentEntry>,
+    item_id: ItemId,
+    quantity: i32,
+) -> Result<(), Error> {
+    let entry = equipment
+        .iter_mut()
+        .find(|entry| entry.item_id == item_id)
+        .ok_or(Error::ItemNotInInventory {
+            character_id,
+            item_id,
+        })?;
+
+    if entry.quantity < quantity {
+        return Err(Error::InsufficientItemQuantity {
+            character_id,
+            item_id,
+            quantity: entry.quantity,
+        });
+    }
+
+    entry.quantity -= quantity;
+    equipment.retain(|entry| entry.quantity > 0);
+    release_orphaned_contents(equipment);
+
+    Ok(())
+}
+
+pub fn set_quantity(equipment: &mut Vec<EquipmentEntry>, item_id: ItemId, quantity: i32) {
+    match equipment.iter_mut().find(|entry| entry.item_id == item_id) {
+        Some(entry) => entry.quantity = quantity,
+        None => add_items(equipment, item_id, quantity),
+    }
+
+    equipment.retain(|entry| entry.quantity > 0);
+    release_orphaned_contents(equipment);
+}
+
+// the contents of a container that is no longer carried are kept loose
+fn release_orphaned_contents(equipment: &mut [EquipmentEntry]) {
+    let carried: Vec<ItemId> = equipment.iter().map(|entry| entry.item_id).collect();
+    for entry in equipment.iter_mut() {
+        if matches!(entry.container_id, Some(container_id) if !carried.contains(&container_id)) {
+            entry.container_id = None;
+        }
+    }
+}
+
+// whether the container is the item itself or is stored somewhere inside it
+pub fn is_stored_within(
+    equipment: &[EquipmentEntry],
+    container_id: ItemId,
+    item_id: ItemId,
+) -> bool {
+    let mut current = Some(container_id);
+    while let Some(id) = current {
+        if id == item_id {
+            return true;
+        }
+
+        current = equipment
+            .iter()
+            .find(|entry| entry.item_id == id)
+            .and_then(|entry| entry.container_id);
+    }
+
+    false
+}
+
+// stored items can't be equiped, so storing an item takes it off
+pub fn store_items(
+    character_id: CharacterId,
+    equipment: &mut [EquipmentEntry],
+    item_id: ItemId,
+    container_id: Option<ItemId>,
+) -> Result<(), Error> {
+    let entry = equipment
+        .iter_mut()
+        .find(|entry| entry.item_id == item_id)
+        .ok_or(Error::ItemNotInInventory {
+            character_id,
+            item_id,
+        })?;
+
+    entry.container_id = container_id;
+    if container_id.is_some() {
+        entry.equiped = false;
+    }
+
+    Ok(())
+}
+
+// the weight of everything in the container, or everything carried if no
+// container is given, including the contents of nested containers unless they
+// hold them weightlessly
+pub fn stored_weight(
+    equipment: &[EquipmentEntry],
+    items: &[Item],
+    container_id: Option<ItemId>,
+) -> i32 {
+    equipment
+        .iter()
+        .filter(|entry| entry.container_id == container_id)
+        .filter_map(|entry| {
+            let item = items.iter().find(|item| item.id == entry.item_id)?;
+            let contents_weight = match item.item_type.as_container() {
+                Some(container) if !container.weightless_contents => {
+                    stored_weight(equipment, items, Some(item.id))
+                }
+                _ => 0,
+            };
+
+            Some(item.weight * entry.quantity + contents_weight)
+        })
+        .sum()
+}
+
+// the first container, from the given one out to the one carrying it all, that
+// can't hold the weight stored in it
+pub fn overfilled_container(
+    equipment: &[EquipmentEntry],
+    items: &[Item],
+    container_id: ItemId,
+) -> Option<(ItemId, i32)> {
+    let mut current = Some(container_id);
+    while let Some(id) = current {
+        let container = items
+            .iter()
+            .find(|item| item.id == id)
+            .and_then(|item| item.item_type.as_container());
+        if let Some(container) = container {
+            if stored_weight(equipment, items, Some(id)) > container.capacity {
+                return Some((id, container.capacity));
+            }
+        }
+
+        current = equipment
+            .iter()
+            .find(|entry| entry.item_id == id)
+            .and_then(|entry| entry.container_id);
+    }
+
+    None
+}
+
+// a stack of containers is a single entry, so only a container carried on its
+// own can have anything stored in it
+pub fn stacked_container_with_contents(equipment: &[EquipmentEntry]) -> Option<ItemId> {
+    equipment
+        .iter()
+        .filter(|entry| entry.quantity > 1)
+        .find(|entry| {
+            equipment
+                .iter()
+                .any(|stored| stored.container_id == Some(entry.item_id))
+        })
+        .map(|entry| entry.item_id)
+}
+
+pub fn set_equiped(
+    character_id: CharacterId,
+    equipment: &mut [EquipmentEntry],
+    item_id: ItemId,
+    equiped: bool,
+) -> Result<(), Error> {
+    let entry = equipment
+        .iter_mut()
+        .find(|entry| entry.item_id == item_id)
+        .ok_or(Error::ItemNotInInventory {
+            character_id,
+            item_id,
+        })?;
+
+    entry.equiped = equiped;
+    if equiped {
+        entry.container_id = None;
+    }
+
+    Ok(())
+}
+
+// only one suit of armor and one shield can be worn at a time, so equipping
+// either replaces whatever was worn before
+pub fn replaces_worn_armor(item: &Item, worn: &Item) -> bool {
+    let is_shield = |item: &Item| {
+        item.item_type
+            .as_armor()
+            .map(|armor| armor.armor_type == ArmorType::Shield)
+    };
+
+    match (is_shield(item), is_shield(worn)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+// an item that requires attunement can only have its charges used by a
+// character attuned to it
+pub fn expend_charges(character: &mut Character, item: &Item, charges: i32) -> Result<(), Error> {
+    if !character
+        .equipment
+        .iter()
+        .any(|entry| entry.item_id == item.id)
+    {
+        return Err(Error::ItemNotInInventory {
+            character_id: character.id,
+            item_id: item.id,
+        });
+    }
+    if item
+        .magic
+        .as_ref()
+        .and_then(|magic| magic.charges.as_ref())
+        .is_none()
+    {
+        return Err(Error::ItemHasNoCharges { item_id: item.id });
+    }
+    if character.active_magic(item).is_none() {
+        return Err(Error::ItemNotAttuned {
+            character_id: character.id,
+            item_id: item.id,
+        });
+    }
+
+    let remaining = character.item_charges_remaining(item);
+    if remaining < charges {
+        return Err(Error::InsufficientItemCharges {
+            item_id: item.id,
+            remaining,
+        });
+    }
+
+    match character
+        .item_charges
+        .iter_mut()
+        .find(|usage| usage.item_id == item.id)
+    {
+        Some(usage) => usage.expended += charges,
+        None => character.item_charges.push(ItemChargeUse {
+            item_id: item.id,
+            expended: charges,
+        }),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::{Container, ItemType};
+
+    #[test]
+    fn removing_items_drops_empty_entries() {
+        let character_id = CharacterId::new();
+        let item_id = ItemId::new();
+        let mut equipment = vec![];
+
+        add_items(&mut equipment, item_id, 2);
+        add_items(&mut equipment, item_id, 1);
+        assert_eq!(equipment.len(), 1);
+        assert_eq!(equipment[0].quantity, 3);
+
+        assert!(remove_items(character_id, &mut equipment, item_id, 4).is_err());
+        assert!(remove_items(character_id, &mut equipment, item_id, 3).is_ok());
+        assert!(equipment.is_empty());
+    }
+
+    #[test]
+    fn weightless_containers_exclude_their_contents() {
+        let item = |weight, item_type| Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Item".to_string(),
+            weight,
+            value: 0,
+            item_type,
+            magic: None,
+        };
+        let container = |weightless_contents| {
+            ItemType::Container(Container {
+                capacity: 500,
+                weightless_contents,
+            })
+        };
+
+        let backpack = item(5, container(false));
+        let bag_of_holding = item(15, container(true));
+        let rope = item(10, ItemType::Gear);
+        let torch = item(1, ItemType::Gear);
+        let items = vec![
+            backpack.clone(),
+            bag_of_holding.clone(),
+            rope.clone(),
+            torch.clone(),
+        ];
+
+        let mut equipment = vec![];
+        for item in &items {
+            add_items(&mut equipment, item.id, 1);
+        }
+        add_items(&mut equipment, torch.id, 4);
+
+        let character_id = CharacterId::new();
+        store_items(
+            character_id,
+            &mut equipment,
+            bag_of_holding.id,
+            Some(backpack.id),
+        )
+        .unwrap();
+        store_items(
+            character_id,
+            &mut equipment,
+            rope.id,
+            Some(bag_of_holding.id),
+        )
+        .unwrap();
+        store_items(character_id, &mut equipment, torch.id, Some(backpack.id)).unwrap();
+
+        assert_eq!(
+            stored_weight(&equipment, &items, Some(bag_of_holding.id)),
+            10
+        );
+        assert_eq!(stored_weight(&equipment, &items, Some(backpack.id)), 20);
+        assert_eq!(stored_weight(&equipment, &items, None), 25);
+        assert!(is_stored_within(&equipment, bag_of_holding.id, backpack.id));
+        assert!(!is_stored_within(
+            &equipment,
+            backpack.id,
+            bag_of_holding.id
+        ));
+
+        remove_items(character_id, &mut equipment, backpack.id, 1).unwrap();
+        assert_eq!(stored_weight(&equipment, &items, None), 20);
+    }
+
+    #[test]
+    fn capacity_is_checked_up_the_chain_and_stacked_containers_stay_empty() {
+        let item = |weight, item_type| Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Item".to_string(),
+            weight,
+            value: 0,
+            item_type,
+            magic: None,
+        };
+        let container = |capacity| {
+            ItemType::Container(Container {
+                capacity,
+                weightless_contents: false,
+            })
+        };
+
+        let sack = item(1, container(15));
+        let pouch = item(1, container(25));
+        let rope = item(10, ItemType::Gear);
+        let items = vec![sack.clone(), pouch.clone(), rope.clone()];
+
+        let mut equipment = vec![];
+        add_items(&mut equipment, sack.id, 1);
+        add_items(&mut equipment, pouch.id, 1);
+        add_items(&mut equipment, rope.id, 1);
+
+        let character_id = CharacterId::new();
+        store_items(character_id, &mut equipment, pouch.id, Some(sack.id)).unwrap();
+        store_items(character_id, &mut equipment, rope.id, Some(pouch.id)).unwrap();
+        assert_eq!(overfilled_container(&equipment, &items, pouch.id), None);
+
+        // the pouch can hold a second rope, but the sack it is in can't
+        add_items(&mut equipment, rope.id, 1);
+        assert_eq!(
+            overfilled_container(&equipment, &items, pouch.id),
+            Some((sack.id, 15))
+        );
+        set_quantity(&mut equipment, rope.id, 1);
+
+        assert_eq!(stacked_container_with_contents(&equipment), None);
+        add_items(&mut equipment, pouch.id, 1);
+        assert_eq!(stacked_container_with_contents(&equipment), Some(pouch.id));
+    }
+}
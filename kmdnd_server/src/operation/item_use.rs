@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::character::{self, CharacterId};
+use crate::database::Database;
+use crate::error::Error;
+use crate::item::ItemId;
+use crate::violations::Violation;
+
+use super::inventory;
+use super::spell::Cast;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemUse {
+    pub item_id: ItemId,
+    pub effect: ItemUseEffect,
+}
+
+impl ItemUse {
+    // the item is used up one from the stack at a time, and a potion heals
+    // whoever it was given to
+    pub async fn expend_use(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        character_id: CharacterId,
+    ) -> Result<(), Error> {
+        let mut character =
+            character::manager::expect_character_by_id(db, campaign, character_id).await?;
+        inventory::remove_items(character.id, &mut character.equipment, self.item_id, 1)?;
+
+        if let ItemUseEffect::Healing {
+            target_character_id,
+            result,
+            ..
+        } = self.effect
+        {
+            if target_character_id == character.id {
+                character.current_hit_points = i32::min(
+                    character.current_hit_points + result,
+                    character.maximum_hit_points,
+                );
+            } else {
+                let mut target_character =
+                    character::manager::expect_character_by_id(db, campaign, target_character_id)
+                        .await?;
+                target_character.current_hit_points = i32::min(
+                    target_character.current_hit_points + result,
+                    target_character.maximum_hit_points,
+                );
+                db.characters().update_character(target_character).await?;
+            }
+        }
+
+        character.recalculate_stats(db).await?;
+        db.characters().update_character(character).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ItemUseEffect {
+    Healing {
+        target_character_id: CharacterId,
+        result: i32,
+        hit_points_regained: i32,
+    },
+    CastSpell(Cast),
+    Consumed,
+}
+
+// a spell cast from a scroll needs no spell slot or material components
+pub fn applies_to_scroll(violation: &Violation) -> bool {
+    !matches!(
+        violation,
+        Violation::CastSpellSlotUnavailable { .. } | Violation::CastMaterialComponentMissing { .. }
+    )
+}
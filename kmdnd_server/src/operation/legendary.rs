@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use super::attack::Attack;
+
+// a legendary action is taken at the end of another creature's turn and
+// doesn't use the monster's own action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegendaryActionUse {
+    pub name: String,
+    pub cost: i32,
+    pub attack: Option<Attack>,
+}
@@ -1,17 +1,31 @@
 use chrono::Utc;
-use rand::Rng;
 
 use crate::campaign::Campaign;
-use crate::character::{CharacterId, Position};
+use crate::character::class::{FeatureUse, LimitedFeature};
+use crate::character::{self, CharacterId, Condition, Position};
+use crate::currency::Purse;
 use crate::database::Database;
-use crate::encounter::{Encounter, EncounterState};
+use crate::encounter::{self, Encounter, EncounterState};
 use crate::error::Error;
-use crate::operation::attack::Attack;
+use crate::item::magic::MAXIMUM_ATTUNED_ITEMS;
+use crate::item::{self, ConsumableEffect};
+use crate::operation::ai;
+use crate::operation::attack::{Attack, AttackMethod};
+use crate::operation::breath::BreathWeapon;
+use crate::operation::inventory::{self, InventoryChange};
+use crate::operation::item_use::{self, ItemUse, ItemUseEffect};
+use crate::operation::legendary::LegendaryActionUse;
+use crate::operation::rest::{Rest, RestType};
 use crate::operation::spell::Cast;
+use crate::operation::trade::{self, Trade};
 use crate::operation::{Action, ActionTypeBody, InteractionId, Legality};
 use crate::violations::Violation;
 
-use super::{ActionBody, Move, Operation, OperationId, OperationType, RollType};
+use super::{
+    ActionBody, AttackBody, CampaignCastBody, CastBody, FeatureBody, InventoryBody, ItemUseBody,
+    LegendaryActionBody, Move, Operation, OperationId, OperationType, RestBody, RollType,
+    TradeBody, TradeTypeBody,
+};
 
 #[tracing::instrument(skip(db))]
 pub async fn get_operations_in_encounter(
@@ -39,18 +53,48 @@ pub async fn get_operation_by_id_in_encounter(
     Ok(operation)
 }
 
+#[tracing::instrument(skip(db))]
+pub async fn get_operations_in_campaign(
+    db: &dyn Database,
+    campaign: &Campaign,
+) -> Result<Vec<Operation>, Error> {
+    let operations = db
+        .operations()
+        .fetch_operations_by_campaign(campaign.id)
+        .await?;
+
+    Ok(operations)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn get_operation_by_id_in_campaign(
+    db: &dyn Database,
+    campaign: &Campaign,
+    operation_id: OperationId,
+) -> Result<Option<Operation>, Error> {
+    let operation = db
+        .operations()
+        .fetch_operation_by_id(operation_id)
+        .await?
+        .filter(|operation| operation.campaign_id == campaign.id);
+
+    Ok(operation)
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn approve_illegal_operation(
     db: &dyn Database,
     campaign: &Campaign,
-    encounter: &Encounter,
     operation: Operation,
 ) -> Result<(), Error> {
     match operation.legality.clone() {
         Legality::IllegalPending { violations } => {
-            db.operations()
+            let operation = db
+                .operations()
                 .update_operation_legality(operation, Legality::IllegalApproved { violations })
                 .await?;
+
+            expend_resources(db, campaign, &operation).await?;
         }
         legality => {
             return Err(Error::OperationIsNotPending {
@@ -63,11 +107,40 @@ pub async fn approve_illegal_operation(
     Ok(())
 }
 
+// what an operation uses up is only taken once it stands, so a rejected
+// operation leaves the character as it was
+async fn expend_resources(
+    db: &dyn Database,
+    campaign: &Campaign,
+    operation: &Operation,
+) -> Result<(), Error> {
+    match &operation.operation_type {
+        OperationType::Action(Action::CastSpell(cast)) => {
+            cast.consume_material_components(db, campaign, operation.character_id)
+                .await?;
+            cast.expend_spell_slot(db, campaign, operation.character_id)
+                .await?;
+        }
+        OperationType::Action(Action::BreathWeapon(breath_weapon)) => {
+            breath_weapon
+                .expend_use(db, campaign, operation.character_id)
+                .await?;
+        }
+        OperationType::UseItem(item_use) => {
+            item_use
+                .expend_use(db, campaign, operation.character_id)
+                .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
 #[tracing::instrument(skip(db))]
 pub async fn reject_illegal_operation(
     db: &dyn Database,
     campaign: &Campaign,
-    encounter: &Encounter,
     operation: Operation,
 ) -> Result<(), Error> {
     match operation.legality.clone() {
@@ -89,7 +162,7 @@ pub async fn reject_illegal_operation(
 pub async fn submit_interaction_result(
     db: &dyn Database,
     campaign: &Campaign,
-    encounter: &Encounter,
+    encounter: Option<&Encounter>,
     mut operation: Operation,
     interaction_id: InteractionId,
     character_id: CharacterId,
@@ -118,24 +191,44 @@ pub async fn submit_interaction_result(
         OperationType::Action(action) => match action {
             Action::Attack(attack) => {
                 attack
-                    .handle_interaction_result(db, &campaign, &interaction, result)
+                    .handle_interaction_result(db, campaign, &operation, interaction, result)
                     .await?
             }
             Action::CastSpell(cast) => {
                 cast.handle_interaction_result(
                     db,
-                    &campaign,
-                    &encounter,
+                    campaign,
+                    encounter,
                     &operation,
-                    &interaction,
+                    interaction,
                     result,
                 )
                 .await?
             }
+            Action::BreathWeapon(breath_weapon) => {
+                breath_weapon
+                    .handle_interaction_result(db, campaign, &operation, interaction, result)
+                    .await?
+            }
             _ => {
                 vec![]
             }
         },
+        OperationType::UseItem(ItemUse {
+            effect: ItemUseEffect::CastSpell(cast),
+            ..
+        }) => {
+            cast.handle_interaction_result(db, campaign, encounter, &operation, interaction, result)
+                .await?
+        }
+        OperationType::LegendaryAction(LegendaryActionUse {
+            attack: Some(attack),
+            ..
+        }) => {
+            attack
+                .handle_interaction_result(db, campaign, &operation, interaction, result)
+                .await?
+        }
         _ => {
             vec![]
         }
@@ -201,7 +294,7 @@ pub async fn create_roll_operation(
         });
     }
 
-    let result = rand::thread_rng().gen_range(1..=20) + character.stats.initiative;
+    let result = character.roll_d20(&mut rand::thread_rng()) + character.stats.initiative;
 
     let now = Utc::now();
     let operation = Operation {
@@ -257,7 +350,7 @@ pub async fn create_move_operation(
         .ok_or(Error::CharacterDoesNotHavePosition { character_id })?;
 
     let desired_position = position;
-    let feet = Position::distance(&current_position, &desired_position);
+    let feet = Position::distance(current_position, &desired_position);
     let mut violations = vec![];
 
     if let EncounterState::Turn {
@@ -301,6 +394,22 @@ pub async fn create_move_operation(
         return Err(Error::OperationViolatesRules { violations });
     }
 
+    let opportunity_attacks = encounter::manager::get_encounter_characters(db, encounter)
+        .await?
+        .into_iter()
+        .filter(|other| {
+            encounter.are_hostile(&current_character, other) && !other.is_incapacitated()
+        })
+        .filter(|other| {
+            let reach = other.melee_reach();
+            other.position.is_some_and(|position| {
+                Position::distance(current_position, &position) <= reach
+                    && Position::distance(&desired_position, &position) > reach
+            })
+        })
+        .map(|other| other.id)
+        .collect();
+
     let now = Utc::now();
     let operation = Operation {
         id: OperationId::new(),
@@ -313,6 +422,7 @@ pub async fn create_move_operation(
         operation_type: OperationType::Move(Move {
             to_position: desired_position,
             feet,
+            opportunity_attacks,
         }),
         interactions: vec![],
         legality: if violations.is_empty() {
@@ -354,7 +464,12 @@ pub async fn create_action_operation(
         });
     }
 
-    if let EncounterState::Turn { character_id, .. } = encounter.state {
+    let mut turn_violations = vec![];
+    if let EncounterState::Turn {
+        round,
+        character_id,
+    } = encounter.state
+    {
         if character_id != body.character_id {
             return Err(Error::NotThisPlayersTurn {
                 campaign_id: campaign.id,
@@ -363,38 +478,241 @@ pub async fn create_action_operation(
                 current_character_id: character_id,
             });
         }
+
+        let operations = db
+            .operations()
+            .fetch_operations_by_turn(encounter.id, round, character_id)
+            .await?;
+
+        let actions_taken = operations
+            .iter()
+            .filter(|op| matches!(op.operation_type, OperationType::Action(_)))
+            .count() as i32;
+        let action_surges = operations
+            .iter()
+            .filter(|op| {
+                matches!(
+                    op.operation_type,
+                    OperationType::UseFeature {
+                        feature: LimitedFeature::ActionSurge
+                    }
+                )
+            })
+            .count() as i32;
+
+        let maximum_actions = 1 + action_surges;
+        if actions_taken >= maximum_actions {
+            turn_violations.push(Violation::ActionCountExceeded {
+                request_character_id: body.character_id,
+                maximum_actions,
+            });
+        }
     }
 
-    let (action, interactions, violations) = match body.action_type {
+    let (action, interactions, mut violations) = match body.action_type {
         ActionTypeBody::Attack(attack) => {
-            let attack_method = attack.method.into_attack_method(db).await?;
+            let (attack_method, magic_bonus) = attack
+                .method
+                .into_attack_method(db, campaign, &source_character)
+                .await?;
 
             let (attack, interactions, violations) = Attack::submit(
                 db,
-                &campaign,
-                &encounter,
+                campaign,
+                encounter,
                 source_character,
-                attack.target_character_id,
+                attack.target_character_ids,
                 attack_method,
+                magic_bonus,
             )
             .await?;
 
             (Action::Attack(attack), interactions, violations)
         }
         ActionTypeBody::CastSpell(cast) => {
-            let (cast, interactions, violations) = Cast::submit(
+            let (cast, interactions, violations) =
+                Cast::submit(db, campaign, Some(encounter), source_character, cast).await?;
+
+            (Action::CastSpell(cast), interactions, violations)
+        }
+        ActionTypeBody::BreathWeapon { toward } => {
+            let (breath_weapon, interactions, violations) =
+                BreathWeapon::submit(db, campaign, encounter, source_character, toward).await?;
+
+            (
+                Action::BreathWeapon(breath_weapon),
+                interactions,
+                violations,
+            )
+        }
+        _ => unimplemented!("the action is not yet implemented"),
+    };
+    violations.extend(turn_violations);
+
+    if !body.ignore_violations && !violations.is_empty() {
+        return Err(Error::OperationViolatesRules { violations });
+    }
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: Some(encounter.id),
+        encounter_state: Some(encounter.state.clone()),
+        character_id: body.character_id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::Action(action),
+        interactions,
+        legality: if violations.is_empty() {
+            Legality::Legal
+        } else {
+            Legality::IllegalPending { violations }
+        },
+    };
+
+    db.operations().insert_operation(&operation).await?;
+
+    if matches!(operation.legality, Legality::Legal) {
+        expend_resources(db, campaign, &operation).await?;
+    }
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_legendary_action_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: &Encounter,
+    body: LegendaryActionBody,
+) -> Result<Operation, Error> {
+    let source_character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    if !encounter.character_ids.contains(&body.character_id) {
+        return Err(Error::CharacterNotInEncounter {
+            campaign_id: campaign.id,
+            encounter_id: encounter.id,
+            character_id: body.character_id,
+        });
+    }
+
+    let stat_block = source_character
+        .stat_block()
+        .ok_or(Error::MonsterActionNotFound {
+            character_id: body.character_id,
+            name: body.name.clone(),
+        })?;
+    let legendary_action =
+        stat_block
+            .legendary_action(&body.name)
+            .ok_or(Error::MonsterActionNotFound {
+                character_id: body.character_id,
+                name: body.name.clone(),
+            })?;
+    let maximum_actions = stat_block
+        .legendary_actions
+        .as_ref()
+        .map(|legendary_actions| legendary_actions.per_round)
+        .unwrap_or(0);
+    let cost = legendary_action.cost;
+    let attack = legendary_action
+        .attack
+        .as_ref()
+        .and_then(|attack| stat_block.attack(attack))
+        .cloned();
+
+    let (round, character_id) = match encounter.state {
+        EncounterState::Turn {
+            round,
+            character_id,
+        } => (round, character_id),
+        _ => {
+            return Err(Error::EncounterHasNotBegun {
+                campaign_id: campaign.id,
+                encounter_id: encounter.id,
+            })
+        }
+    };
+
+    let mut violations = vec![];
+    if character_id == body.character_id {
+        violations.push(Violation::LegendaryActionOnOwnTurn {
+            request_character_id: body.character_id,
+        });
+    }
+
+    // legendary actions are regained at the start of the monster's turn, so
+    // only those taken since its last turn began are spent
+    let turn_index = |character_id: CharacterId| {
+        encounter
+            .character_ids
+            .iter()
+            .position(|&id| id == character_id)
+    };
+    let monster_index = turn_index(body.character_id);
+    let last_turn_started = match (turn_index(character_id), monster_index) {
+        (Some(current_index), Some(monster_index)) if current_index >= monster_index => {
+            (round, monster_index)
+        }
+        (_, monster_index) => (round - 1, monster_index.unwrap_or(0)),
+    };
+
+    let operations = db
+        .operations()
+        .fetch_operations_by_encounter(encounter.id)
+        .await?;
+    let current_actions: i32 = operations
+        .iter()
+        .filter(|op| op.character_id == body.character_id)
+        .filter(|op| match op.encounter_state {
+            Some(EncounterState::Turn {
+                round,
+                character_id,
+            }) => turn_index(character_id)
+                .map(|index| (round, index) >= last_turn_started)
+                .unwrap_or(false),
+            _ => false,
+        })
+        .filter_map(|op| match &op.operation_type {
+            OperationType::LegendaryAction(legendary_action) => Some(legendary_action.cost),
+            _ => None,
+        })
+        .sum();
+
+    if current_actions + cost > maximum_actions {
+        violations.push(Violation::LegendaryActionsExhausted {
+            request_character_id: body.character_id,
+            maximum_actions,
+            current_actions,
+            request_actions: cost,
+        });
+    }
+
+    let (attack, interactions) = match attack {
+        Some(attack) => {
+            let (attack, interactions, attack_violations) = Attack::submit(
                 db,
-                &campaign,
-                &encounter,
+                campaign,
+                encounter,
                 source_character,
-                cast.name,
-                cast.target,
+                body.target_character_ids,
+                AttackMethod::Natural(attack),
+                0,
             )
             .await?;
+            violations.extend(attack_violations);
 
-            (Action::CastSpell(cast), interactions, violations)
+            (Some(attack), interactions)
         }
-        _ => unimplemented!("the action is not yet implemented"),
+        None => (None, vec![]),
     };
 
     if !body.ignore_violations && !violations.is_empty() {
@@ -410,7 +728,68 @@ pub async fn create_action_operation(
         character_id: body.character_id,
         created_at: now,
         modified_at: now,
-        operation_type: OperationType::Action(action),
+        operation_type: OperationType::LegendaryAction(LegendaryActionUse {
+            name: body.name,
+            cost,
+            attack,
+        }),
+        interactions,
+        legality: if violations.is_empty() {
+            Legality::Legal
+        } else {
+            Legality::IllegalPending { violations }
+        },
+    };
+
+    db.operations().insert_operation(&operation).await?;
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_cast_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    body: CampaignCastBody,
+) -> Result<Operation, Error> {
+    let source_character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    let current_encounter = db
+        .encounters()
+        .fetch_current_encounter_by_campaign(campaign.id)
+        .await?;
+    if let Some(current_encounter) = current_encounter {
+        if current_encounter.character_ids.contains(&body.character_id) {
+            return Err(Error::CharacterInCurrentEncounter {
+                character_id: body.character_id,
+            });
+        }
+    }
+
+    let (cast, interactions, violations) =
+        Cast::submit(db, campaign, None, source_character, body.cast).await?;
+
+    if !body.ignore_violations && !violations.is_empty() {
+        return Err(Error::OperationViolatesRules { violations });
+    }
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: None,
+        encounter_state: None,
+        character_id: body.character_id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::Action(Action::CastSpell(cast.clone())),
         interactions,
         legality: if violations.is_empty() {
             Legality::Legal
@@ -421,5 +800,1358 @@ pub async fn create_action_operation(
 
     db.operations().insert_operation(&operation).await?;
 
+    if matches!(operation.legality, Legality::Legal) {
+        expend_resources(db, campaign, &operation).await?;
+    }
+
     Ok(operation)
 }
+
+#[tracing::instrument(skip(db))]
+pub async fn create_rest_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    body: RestBody,
+) -> Result<Operation, Error> {
+    let mut character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    let current_encounter = db
+        .encounters()
+        .fetch_current_encounter_by_campaign(campaign.id)
+        .await?;
+    if let Some(current_encounter) = current_encounter {
+        return Err(Error::CurrentEncounterAlreadyExists {
+            campaign_id: campaign.id,
+            encounter_id: current_encounter.id,
+        });
+    }
+
+    // attuning to an item or ending attunement takes a short rest
+    for &item_id in &body.end_attunement {
+        if !character.attuned_items.contains(&item_id) {
+            return Err(Error::ItemNotAttuned {
+                character_id: character.id,
+                item_id,
+            });
+        }
+    }
+    character
+        .attuned_items
+        .retain(|item_id| !body.end_attunement.contains(item_id));
+
+    for &item_id in &body.attune {
+        let item = item::manager::get_item_by_id(db, Some(campaign.id), item_id)
+            .await?
+            .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+        if !character
+            .equipment
+            .iter()
+            .any(|entry| entry.item_id == item_id)
+        {
+            return Err(Error::ItemNotInInventory {
+                character_id: character.id,
+                item_id,
+            });
+        }
+        if !item.magic.is_some_and(|magic| magic.requires_attunement) {
+            return Err(Error::ItemDoesNotRequireAttunement { item_id });
+        }
+        if !character.attuned_items.contains(&item_id) {
+            character.attuned_items.push(item_id);
+        }
+    }
+
+    if character.attuned_items.len() > MAXIMUM_ATTUNED_ITEMS {
+        return Err(Error::AttunementLimitExceeded {
+            character_id: character.id,
+            maximum: MAXIMUM_ATTUNED_ITEMS,
+        });
+    }
+
+    // item charges are regained at dawn, which a long rest is assumed to span
+    if body.rest_type == RestType::Long {
+        for usage in &mut character.item_charges {
+            let charges = item::manager::get_item_by_id(db, Some(campaign.id), usage.item_id)
+                .await?
+                .and_then(|item| item.magic)
+                .and_then(|magic| magic.charges);
+
+            if let Some(charges) = charges {
+                usage.expended = charges.recharge(&mut rand::thread_rng(), usage.expended);
+            }
+        }
+        character.item_charges.retain(|usage| usage.expended > 0);
+    }
+
+    let mut rest = Rest::take(
+        &mut rand::thread_rng(),
+        &mut character,
+        body.rest_type,
+        body.hit_dice,
+    )?;
+    rest.attuned = body.attune;
+    rest.attunement_ended = body.end_attunement;
+
+    character.recalculate_stats(db).await?;
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: None,
+        encounter_state: None,
+        character_id: character.id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::Rest(rest),
+        interactions: vec![],
+        legality: Legality::Legal,
+    };
+
+    db.characters().update_character(character).await?;
+    db.operations().insert_operation(&operation).await?;
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_inventory_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    body: InventoryBody,
+) -> Result<Operation, Error> {
+    let mut character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    let change = body.change;
+    let item_id = change.item_id();
+    let item = item::manager::get_item_by_id(db, Some(campaign.id), item_id)
+        .await?
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+    let mut target_character = None;
+    match change {
+        InventoryChange::Give { quantity, .. }
+        | InventoryChange::Drop { quantity, .. }
+        | InventoryChange::Transfer { quantity, .. }
+            if quantity < 1 =>
+        {
+            return Err(Error::InvalidItemQuantity { quantity });
+        }
+        InventoryChange::SetQuantity { quantity, .. } if quantity < 0 => {
+            return Err(Error::InvalidItemQuantity { quantity });
+        }
+        InventoryChange::Give { quantity, .. } => {
+            inventory::add_items(&mut character.equipment, item_id, quantity);
+        }
+        InventoryChange::Drop { quantity, .. } => {
+            inventory::remove_items(character.id, &mut character.equipment, item_id, quantity)?;
+        }
+        InventoryChange::Transfer {
+            quantity,
+            target_character_id,
+            ..
+        } => {
+            if target_character_id == character.id {
+                return Err(Error::InventoryTransferToSelf {
+                    character_id: character.id,
+                });
+            }
+
+            let mut target = db
+                .characters()
+                .fetch_character_by_campaign_and_id(campaign.id, target_character_id)
+                .await?
+                .ok_or(Error::CharacterNotInCampaign {
+                    campaign_id: campaign.id,
+                    character_id: target_character_id,
+                })?;
+
+            inventory::remove_items(character.id, &mut character.equipment, item_id, quantity)?;
+            inventory::add_items(&mut target.equipment, item_id, quantity);
+            target.recalculate_stats(db).await?;
+            target_character = Some(target);
+        }
+        InventoryChange::SetQuantity { quantity, .. } => {
+            inventory::set_quantity(&mut character.equipment, item_id, quantity);
+        }
+        InventoryChange::Equip { .. } => {
+            for worn in character.equiped_items(db).await? {
+                if worn.id != item_id && inventory::replaces_worn_armor(&item, &worn) {
+                    inventory::set_equiped(character.id, &mut character.equipment, worn.id, false)?;
+                }
+            }
+
+            inventory::set_equiped(character.id, &mut character.equipment, item_id, true)?;
+        }
+        InventoryChange::Unequip { .. } => {
+            inventory::set_equiped(character.id, &mut character.equipment, item_id, false)?;
+        }
+        InventoryChange::UseCharges { charges, .. } if charges < 1 => {
+            return Err(Error::InvalidItemQuantity { quantity: charges });
+        }
+        InventoryChange::UseCharges { charges, .. } => {
+            inventory::expend_charges(&mut character, &item, charges)?;
+        }
+        InventoryChange::Store {
+            container_id: None, ..
+        } => {
+            inventory::store_items(character.id, &mut character.equipment, item_id, None)?;
+        }
+        InventoryChange::Store {
+            container_id: Some(container_id),
+            ..
+        } => {
+            let container_item = item::manager::get_item_by_id(db, Some(campaign.id), container_id)
+                .await?
+                .ok_or(Error::ItemDoesNotExist {
+                    item_id: container_id,
+                })?;
+            if container_item.item_type.as_container().is_none() {
+                return Err(Error::ItemIsNotAContainer {
+                    item_id: container_id,
+                });
+            }
+
+            if !character
+                .equipment
+                .iter()
+                .any(|entry| entry.item_id == container_id)
+            {
+                return Err(Error::ItemNotInInventory {
+                    character_id: character.id,
+                    item_id: container_id,
+                });
+            }
+            if inventory::is_stored_within(&character.equipment, container_id, item_id) {
+                return Err(Error::InvalidContainer {
+                    item_id,
+                    container_id,
+                });
+            }
+
+            inventory::store_items(
+                character.id,
+                &mut character.equipment,
+                item_id,
+                Some(container_id),
+            )?;
+
+            let carried_items = character.carried_items(db).await?;
+            if let Some((container_id, capacity)) =
+                inventory::overfilled_container(&character.equipment, &carried_items, container_id)
+            {
+                return Err(Error::ContainerCapacityExceeded {
+                    container_id,
+                    capacity,
+                });
+            }
+        }
+    }
+
+    let stacked_container = inventory::stacked_container_with_contents(&character.equipment)
+        .or_else(|| {
+            target_character
+                .as_ref()
+                .and_then(|target| inventory::stacked_container_with_contents(&target.equipment))
+        });
+    if let Some(container_id) = stacked_container {
+        return Err(Error::StackedContainerHasContents { container_id });
+    }
+
+    character.recalculate_stats(db).await?;
+
+    let current_encounter = db
+        .encounters()
+        .fetch_current_encounter_by_campaign(campaign.id)
+        .await?;
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: current_encounter.as_ref().map(|encounter| encounter.id),
+        encounter_state: current_encounter.map(|encounter| encounter.state),
+        character_id: character.id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::Inventory(change),
+        interactions: vec![],
+        legality: Legality::Legal,
+    };
+
+    db.characters().update_character(character).await?;
+    if let Some(target_character) = target_character {
+        db.characters().update_character(target_character).await?;
+    }
+    db.operations().insert_operation(&operation).await?;
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_item_use_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    body: ItemUseBody,
+) -> Result<Operation, Error> {
+    let character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    let item_id = body.item_id;
+    let item = item::manager::get_item_by_id(db, Some(campaign.id), item_id)
+        .await?
+        .ok_or(Error::ItemDoesNotExist { item_id })?;
+    let consumable = item
+        .item_type
+        .as_consumable()
+        .ok_or(Error::ItemIsNotConsumable { item_id })?;
+
+    let current_encounter = db
+        .encounters()
+        .fetch_current_encounter_by_campaign(campaign.id)
+        .await?;
+
+    let target_character = match body.target_character_id {
+        Some(target_character_id) if target_character_id != character.id => Some(
+            db.characters()
+                .fetch_character_by_campaign_and_id(campaign.id, target_character_id)
+                .await?
+                .ok_or(Error::CharacterNotInCampaign {
+                    campaign_id: campaign.id,
+                    character_id: target_character_id,
+                })?,
+        ),
+        _ => None,
+    };
+
+    let mut interactions = vec![];
+    let mut violations = vec![];
+    let effect = match &consumable.effect {
+        ConsumableEffect::Healing { healing } => {
+            let result = i32::max(healing.roll(&mut rand::thread_rng()), 0);
+
+            let target = target_character.as_ref().unwrap_or(&character);
+            let hit_points_regained = i32::min(
+                result,
+                target.maximum_hit_points - target.current_hit_points,
+            );
+
+            ItemUseEffect::Healing {
+                target_character_id: target.id,
+                result,
+                hit_points_regained,
+            }
+        }
+        ConsumableEffect::SpellScroll { spell, level } => {
+            let cast = CastBody {
+                name: spell.clone(),
+                targets: body.targets,
+                slot_level: Some(*level),
+                ritual: false,
+            };
+
+            let (cast, cast_interactions, cast_violations) = Cast::submit(
+                db,
+                campaign,
+                current_encounter.as_ref(),
+                character.clone(),
+                cast,
+            )
+            .await?;
+
+            interactions = cast_interactions;
+            violations = cast_violations
+                .into_iter()
+                .filter(item_use::applies_to_scroll)
+                .collect();
+
+            ItemUseEffect::CastSpell(cast)
+        }
+        ConsumableEffect::Mundane => ItemUseEffect::Consumed,
+    };
+
+    if !body.ignore_violations && !violations.is_empty() {
+        return Err(Error::OperationViolatesRules { violations });
+    }
+
+    // the item has to be carried, though it's only used up once the use stands
+    inventory::remove_items(character.id, &mut character.equipment.clone(), item_id, 1)?;
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: current_encounter.as_ref().map(|encounter| encounter.id),
+        encounter_state: current_encounter.map(|encounter| encounter.state),
+        character_id: character.id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::UseItem(ItemUse { item_id, effect }),
+        interactions,
+        legality: if violations.is_empty() {
+            Legality::Legal
+        } else {
+            Legality::IllegalPending { violations }
+        },
+    };
+
+    db.operations().insert_operation(&operation).await?;
+
+    if matches!(operation.legality, Legality::Legal) {
+        expend_resources(db, campaign, &operation).await?;
+    }
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_trade_operation(
+    db: &dyn Database,
+    campaign: Campaign,
+    body: TradeBody,
+) -> Result<Operation, Error> {
+    let mut character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    let mut treasury = None;
+    let mut target_character = None;
+    let trade = match body.trade {
+        TradeTypeBody::Buy { quantity, .. } | TradeTypeBody::Sell { quantity, .. }
+            if quantity < 1 =>
+        {
+            return Err(Error::InvalidItemQuantity { quantity });
+        }
+        TradeTypeBody::GiveCoins { coins, .. } | TradeTypeBody::TakeCoins { coins }
+            if !coins.is_valid() || coins.total() == 0 =>
+        {
+            return Err(Error::InvalidCoins { coins });
+        }
+        TradeTypeBody::Buy { item_id, quantity } => {
+            let item = item::manager::get_item_by_id(db, Some(campaign.id), item_id)
+                .await?
+                .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+            let price = trade::buy_price(&item, quantity);
+            if !character.purse.pay(price) {
+                return Err(Error::InsufficientFunds {
+                    character_id: character.id,
+                    price,
+                });
+            }
+            inventory::add_items(&mut character.equipment, item_id, quantity);
+
+            Trade::Buy {
+                item_id,
+                quantity,
+                price,
+            }
+        }
+        TradeTypeBody::Sell { item_id, quantity } => {
+            let item = item::manager::get_item_by_id(db, Some(campaign.id), item_id)
+                .await?
+                .ok_or(Error::ItemDoesNotExist { item_id })?;
+
+            let price = trade::sell_price(&item, quantity);
+            inventory::remove_items(character.id, &mut character.equipment, item_id, quantity)?;
+            character.purse.add(&Purse::from_copper(price));
+
+            Trade::Sell {
+                item_id,
+                quantity,
+                price,
+            }
+        }
+        TradeTypeBody::GiveCoins {
+            coins,
+            target_character_id,
+        } => {
+            if !character.purse.remove(&coins) {
+                return Err(Error::PurseLacksCoins {
+                    character_id: character.id,
+                    coins,
+                });
+            }
+
+            match target_character_id {
+                Some(target_character_id) if target_character_id == character.id => {
+                    return Err(Error::InventoryTransferToSelf {
+                        character_id: character.id,
+                    });
+                }
+                Some(target_character_id) => {
+                    let mut target = db
+                        .characters()
+                        .fetch_character_by_campaign_and_id(campaign.id, target_character_id)
+                        .await?
+                        .ok_or(Error::CharacterNotInCampaign {
+                            campaign_id: campaign.id,
+                            character_id: target_character_id,
+                        })?;
+
+                    target.purse.add(&coins);
+                    target_character = Some(target);
+                }
+                None => {
+                    let mut party_treasury = campaign.treasury.clone();
+                    party_treasury.add(&coins);
+                    treasury = Some(party_treasury);
+                }
+            }
+
+            Trade::GiveCoins {
+                coins,
+                target_character_id,
+            }
+        }
+        TradeTypeBody::TakeCoins { coins } => {
+            let mut party_treasury = campaign.treasury.clone();
+            if !party_treasury.remove(&coins) {
+                return Err(Error::TreasuryLacksCoins {
+                    campaign_id: campaign.id,
+                    coins,
+                });
+            }
+
+            character.purse.add(&coins);
+            treasury = Some(party_treasury);
+
+            Trade::TakeCoins { coins }
+        }
+    };
+
+    let current_encounter = db
+        .encounters()
+        .fetch_current_encounter_by_campaign(campaign.id)
+        .await?;
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: current_encounter.as_ref().map(|encounter| encounter.id),
+        encounter_state: current_encounter.map(|encounter| encounter.state),
+        character_id: character.id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::Trade(trade),
+        interactions: vec![],
+        legality: Legality::Legal,
+    };
+
+    character.recalculate_stats(db).await?;
+    db.characters().update_character(character).await?;
+    if let Some(target_character) = target_character {
+        let purse = target_character.purse.clone();
+        db.characters()
+            .update_character_purse(target_character, purse)
+            .await?;
+    }
+    if let Some(treasury) = treasury {
+        db.campaigns()
+            .update_campaign_treasury(campaign, treasury)
+            .await?;
+    }
+    db.operations().insert_operation(&operation).await?;
+
+    Ok(operation)
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_feature_operation(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: &Encounter,
+    body: FeatureBody,
+) -> Result<Operation, Error> {
+    let character = db
+        .characters()
+        .fetch_character_by_campaign_and_id(campaign.id, body.character_id)
+        .await?
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id: body.character_id,
+        })?;
+
+    if !encounter.character_ids.contains(&body.character_id) {
+        return Err(Error::CharacterNotInEncounter {
+            campaign_id: campaign.id,
+            encounter_id: encounter.id,
+            character_id: body.character_id,
+        });
+    }
+
+    if let EncounterState::Turn { character_id, .. } = encounter.state {
+        if character_id != body.character_id {
+            return Err(Error::NotThisPlayersTurn {
+                campaign_id: campaign.id,
+                encounter_id: encounter.id,
+                request_character_id: body.character_id,
+                current_character_id: character_id,
+            });
+        }
+    }
+
+    let maximum_uses =
+        character
+            .feature_uses_total(body.feature)
+            .ok_or(Error::CharacterDoesNotHaveFeature {
+                character_id: character.id,
+                feature: body.feature,
+            })?;
+
+    let mut violations = vec![];
+    let expended = character.feature_uses_expended(body.feature);
+    if expended >= maximum_uses {
+        violations.push(Violation::FeatureUsesExhausted {
+            request_character_id: character.id,
+            feature: body.feature,
+            maximum_uses,
+        });
+    }
+
+    if !body.ignore_violations && !violations.is_empty() {
+        return Err(Error::OperationViolatesRules { violations });
+    }
+
+    let now = Utc::now();
+    let operation = Operation {
+        id: OperationId::new(),
+        campaign_id: campaign.id,
+        encounter_id: Some(encounter.id),
+        encounter_state: Some(encounter.state.clone()),
+        character_id: character.id,
+        created_at: now,
+        modified_at: now,
+        operation_type: OperationType::UseFeature {
+            feature: body.feature,
+        },
+        interactions: vec![],
+        legality: if violations.is_empty() {
+            Legality::Legal
+        } else {
+            Legality::IllegalPending { violations }
+        },
+    };
+
+    db.operations().insert_operation(&operation).await?;
+
+    let mut feature_uses = character.feature_uses.clone();
+    match feature_uses
+        .iter_mut()
+        .find(|usage| usage.feature == body.feature)
+    {
+        Some(usage) => usage.expended = i32::min(usage.expended + 1, maximum_uses),
+        None => feature_uses.push(FeatureUse {
+            feature: body.feature,
+            expended: 1,
+        }),
+    }
+    let character =
+        character::manager::update_character_feature_uses(db, character, feature_uses).await?;
+
+    if body.feature == LimitedFeature::Rage && !character.conditions.contains(&Condition::Raging) {
+        let mut conditions = character.conditions.clone();
+        conditions.push(Condition::Raging);
+        character::manager::update_character_conditions(db, character, conditions).await?;
+    }
+
+    Ok(operation)
+}
+
+// takes the turn of a character controlled by the server, closing in on a
+// hostile and attacking it with every roll made here
+#[tracing::instrument(skip(db))]
+pub async fn take_ai_turn(
+    db: &dyn Database,
+    campaign: &Campaign,
+    encounter: &Encounter,
+) -> Result<Vec<Operation>, Error> {
+    let (round, character_id) = match encounter.state {
+        EncounterState::Turn {
+            round,
+            character_id,
+        } => (round, character_id),
+        _ => {
+            return Err(Error::EncounterHasNotBegun {
+                campaign_id: campaign.id,
+                encounter_id: encounter.id,
+            })
+        }
+    };
+
+    let characters = encounter::manager::get_encounter_characters(db, encounter).await?;
+    let character = characters
+        .iter()
+        .find(|character| character.id == character_id)
+        .ok_or(Error::CharacterNotInCampaign {
+            campaign_id: campaign.id,
+            character_id,
+        })?;
+
+    if !character.ai_controlled {
+        return Err(Error::CharacterNotAiControlled { character_id });
+    }
+    if character.is_incapacitated() {
+        return Ok(vec![]);
+    }
+
+    let mut position = character
+        .position
+        .ok_or(Error::CharacterDoesNotHavePosition { character_id })?;
+
+    let turn_operations = db
+        .operations()
+        .fetch_operations_by_turn(encounter.id, round, character_id)
+        .await?;
+    let already_moved_feet: f32 = turn_operations
+        .iter()
+        .filter_map(|op| op.operation_type.as_move())
+        .map(|mov| mov.feet)
+        .sum();
+    let already_acted = turn_operations
+        .iter()
+        .any(|op| matches!(op.operation_type, OperationType::Action(_)));
+
+    let mut weapons = vec![];
+    for entry in &character.equipment {
+        if entry.equiped {
+            let item =
+                item::manager::expect_item_by_id(db, Some(campaign.id), entry.item_id).await?;
+            weapons.push(item);
+        }
+    }
+    let attack = ai::best_attack(character, &weapons);
+
+    let hostiles: Vec<_> = characters
+        .iter()
+        .filter(|other| encounter.are_hostile(character, other) && other.current_hit_points > 0)
+        .collect();
+    let movement = f32::max(character.stats.speed as f32 - already_moved_feet, 0.0);
+    let target = match ai::choose_target(&position, attack.range + movement, &hostiles) {
+        Some(target) => target,
+        None => return Ok(vec![]),
+    };
+    let target_position = target
+        .position
+        .as_ref()
+        .ok_or(Error::CharacterDoesNotHavePosition {
+            character_id: target.id,
+        })?;
+
+    let mut operations = vec![];
+    if let Some(destination) = ai::approach(&position, target_position, attack.range, movement) {
+        let operation =
+            create_move_operation(db, campaign, encounter, character_id, destination, false)
+                .await?;
+        operations.push(operation);
+        position = destination;
+    }
+
+    if already_acted || Position::distance(&position, target_position) > attack.range {
+        return Ok(operations);
+    }
+
+    let body = ActionBody {
+        character_id,
+        action_type: ActionTypeBody::Attack(AttackBody {
+            target_character_ids: vec![target.id; character.attacks_per_action() as usize],
+            method: attack.method.clone(),
+        }),
+        ignore_violations: false,
+    };
+    let mut operation = create_action_operation(db, campaign, encounter, body).await?;
+
+    let mut rng = rand::thread_rng();
+    while let Some(interaction) = operation
+        .interactions
+        .iter()
+        .find(|interaction| {
+            interaction.result.is_none() && interaction.character_id == character_id
+        })
+        .cloned()
+    {
+        let result = match interaction.roll_type {
+            RollType::Hit => attack.roll_hit(character, &mut rng),
+            RollType::Damage => {
+                let bonus_damage = interaction
+                    .bonus_dice
+                    .as_ref()
+                    .map(|dice| dice.roll(&mut rng))
+                    .unwrap_or(0);
+                attack.roll_damage(&mut rng) + bonus_damage
+            }
+            _ => break,
+        };
+
+        operation = submit_interaction_result(
+            db,
+            campaign,
+            Some(encounter),
+            operation,
+            interaction.id,
+            character_id,
+            result,
+        )
+        .await?;
+    }
+    operations.push(operation);
+
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::test::campaign;
+    use crate::character::test::character;
+    use crate::character::{Character, EquipmentEntry, SpellSlot};
+    use crate::database::test::MockDatabase;
+    use crate::encounter::test::encounter;
+    use crate::item::{Consumable, Dice, DiceRoll, Item, ItemId, ItemType};
+    use crate::monster::test::goblin_instance;
+    use crate::monster::{LegendaryAction, LegendaryActions};
+    use crate::operation::spell::{
+        CastingTime, MagicSchool, Spell, SpellComponent, SpellDuration, SpellEffect, SpellRange,
+        SpellScaling, SpellTargetType,
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn diamond() -> Item {
+        Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Diamond".to_string(),
+            weight: 0,
+            value: 30000,
+            item_type: ItemType::Gear,
+            magic: None,
+        }
+    }
+
+    // costs a consumed diamond and a slot, and can't be cast while silenced
+    fn spell() -> Spell {
+        Spell {
+            name: "Glimmer".to_string(),
+            level: 1,
+            school: MagicSchool::Evocation,
+            casting_time: CastingTime::Action(1),
+            range: SpellRange::Personal,
+            target: SpellTargetType::None,
+            components: vec![
+                SpellComponent::Verbal,
+                SpellComponent::Material {
+                    name: "Diamond".to_string(),
+                    cost: Some(30000),
+                    consumed: true,
+                },
+            ],
+            duration: SpellDuration::Instantaneous,
+            concentration: false,
+            ritual: false,
+            effect: SpellEffect::None,
+            scaling: SpellScaling::None,
+            description: "".to_string(),
+        }
+    }
+
+    fn silenced_caster(campaign: &Campaign, diamond: &Item) -> Character {
+        let mut caster = character(campaign.id);
+        caster.conditions = vec![Condition::Silenced];
+        caster.equipment = vec![EquipmentEntry {
+            equiped: false,
+            quantity: 1,
+            item_id: diamond.id,
+            container_id: None,
+        }];
+        caster.spell_slots = vec![SpellSlot {
+            level: 1,
+            total: 2,
+            expended: 0,
+            pact_magic: false,
+        }];
+        caster
+    }
+
+    fn mock_cast(db: &mut MockDatabase, caster: Character, diamond: Item) {
+        db.spells.on_fetch_spell_by_name = Box::new(|_| Ok(Some(spell())));
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(caster.clone())));
+        db.items.on_fetch_item_by_id = Box::new(move |_| Ok(Some(diamond.clone())));
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.operations.on_insert_operation = Box::new(|_| Ok(()));
+    }
+
+    #[actix_rt::test]
+    async fn rejected_cast_leaves_materials_and_slots() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let diamond = diamond();
+        let caster = silenced_caster(&campaign, &diamond);
+        let caster_id = caster.id;
+        mock_cast(&mut db, caster, diamond);
+
+        let called_update_equipment = Arc::new(Mutex::new(false));
+        let called_update_equipment_clone = Arc::clone(&called_update_equipment);
+        db.characters.on_update_character_equipment = Box::new(move |character, _| {
+            *called_update_equipment_clone.lock().unwrap() = true;
+            Ok(character)
+        });
+        let called_update_spell_slots = Arc::new(Mutex::new(false));
+        let called_update_spell_slots_clone = Arc::clone(&called_update_spell_slots);
+        db.characters.on_update_character_spell_slots = Box::new(move |character, _| {
+            *called_update_spell_slots_clone.lock().unwrap() = true;
+            Ok(character)
+        });
+        let called_delete = Arc::new(Mutex::new(false));
+        let called_delete_clone = Arc::clone(&called_delete);
+        db.operations.on_delete_operation = Box::new(move |_| {
+            *called_delete_clone.lock().unwrap() = true;
+            Ok(())
+        });
+
+        let body = CampaignCastBody {
+            character_id: caster_id,
+            cast: CastBody {
+                name: "Glimmer".to_string(),
+                targets: vec![],
+                slot_level: None,
+                ritual: false,
+            },
+            ignore_violations: true,
+        };
+        let operation = create_cast_operation(&db, &campaign, body).await.unwrap();
+        assert!(matches!(
+            operation.legality,
+            Legality::IllegalPending { .. }
+        ));
+
+        reject_illegal_operation(&db, &campaign, operation)
+            .await
+            .unwrap();
+
+        assert!(
+            *called_delete.lock().unwrap(),
+            "db.delete_operation was not called"
+        );
+        assert!(
+            !*called_update_equipment.lock().unwrap(),
+            "db.update_character_equipment was called"
+        );
+        assert!(
+            !*called_update_spell_slots.lock().unwrap(),
+            "db.update_character_spell_slots was called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn approved_cast_consumes_materials_and_slots() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let diamond = diamond();
+        let caster = silenced_caster(&campaign, &diamond);
+        let caster_id = caster.id;
+        mock_cast(&mut db, caster, diamond);
+
+        let updated_equipment = Arc::new(Mutex::new(None));
+        let updated_equipment_clone = Arc::clone(&updated_equipment);
+        db.characters.on_update_character_equipment = Box::new(move |character, equipment| {
+            *updated_equipment_clone.lock().unwrap() = Some(equipment);
+            Ok(character)
+        });
+        let updated_spell_slots = Arc::new(Mutex::new(None));
+        let updated_spell_slots_clone = Arc::clone(&updated_spell_slots);
+        db.characters.on_update_character_spell_slots = Box::new(move |character, spell_slots| {
+            *updated_spell_slots_clone.lock().unwrap() = Some(spell_slots);
+            Ok(character)
+        });
+        db.operations.on_update_operation_legality = Box::new(|mut operation, legality| {
+            operation.legality = legality;
+            Ok(operation)
+        });
+
+        let body = CampaignCastBody {
+            character_id: caster_id,
+            cast: CastBody {
+                name: "Glimmer".to_string(),
+                targets: vec![],
+                slot_level: None,
+                ritual: false,
+            },
+            ignore_violations: true,
+        };
+        let operation = create_cast_operation(&db, &campaign, body).await.unwrap();
+        assert!(
+            updated_equipment.lock().unwrap().is_none(),
+            "materials were consumed before approval"
+        );
+        assert!(
+            updated_spell_slots.lock().unwrap().is_none(),
+            "a slot was expended before approval"
+        );
+
+        approve_illegal_operation(&db, &campaign, operation)
+            .await
+            .unwrap();
+
+        let equipment = updated_equipment.lock().unwrap().clone();
+        assert!(
+            equipment.expect("materials were not consumed").is_empty(),
+            "the diamond was not consumed"
+        );
+        let spell_slots = updated_spell_slots.lock().unwrap().clone();
+        assert_eq!(
+            spell_slots.expect("a slot was not expended")[0].expended,
+            1,
+            "the slot was not expended"
+        );
+    }
+
+    fn detect_magic() -> CastBody {
+        CastBody {
+            name: "Detect Magic".to_string(),
+            targets: vec![],
+            slot_level: None,
+            ritual: true,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn rituals_take_ten_minutes_longer_without_a_slot() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let caster = character(campaign.id);
+        let caster_id = caster.id;
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(caster.clone())));
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.operations.on_insert_operation = Box::new(|_| Ok(()));
+
+        let body = CampaignCastBody {
+            character_id: caster_id,
+            cast: detect_magic(),
+            ignore_violations: false,
+        };
+        let operation = create_cast_operation(&db, &campaign, body).await.unwrap();
+
+        assert!(matches!(operation.legality, Legality::Legal));
+        match operation.operation_type {
+            OperationType::Action(Action::CastSpell(cast)) => {
+                assert!(matches!(cast.casting_time, CastingTime::Minute(10)));
+                assert_eq!(cast.slot_level, None);
+            }
+            _ => panic!("expected a cast"),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn rituals_are_not_cast_during_encounters() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let mut caster = character(campaign.id);
+        caster.position = Some(Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        let caster_id = caster.id;
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(caster.clone())));
+        db.operations.on_fetch_operations_by_turn = Box::new(|_, _, _| Ok(vec![]));
+
+        let mut encounter = encounter(campaign.id, vec![caster_id]);
+        encounter.state = EncounterState::Turn {
+            round: 0,
+            character_id: caster_id,
+        };
+        let body = ActionBody {
+            character_id: caster_id,
+            action_type: ActionTypeBody::CastSpell(detect_magic()),
+            ignore_violations: false,
+        };
+        let result = create_action_operation(&db, &campaign, &encounter, body).await;
+
+        match result {
+            Err(Error::OperationViolatesRules { violations }) => assert!(matches!(
+                violations.as_slice(),
+                [Violation::CastingTimeExceedsTurn { .. }]
+            )),
+            other => panic!("expected the ritual to exceed the turn, got {:?}", other),
+        }
+    }
+
+    fn potion_of_healing() -> Item {
+        Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Potion of Healing".to_string(),
+            weight: 0,
+            value: 5000,
+            item_type: ItemType::Consumable(Consumable {
+                effect: ConsumableEffect::Healing {
+                    healing: DiceRoll {
+                        amount: 2,
+                        dice: Dice::D4,
+                        modifier: 2,
+                    },
+                },
+            }),
+            magic: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn drinking_a_potion_heals_and_uses_one_up() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let potion = potion_of_healing();
+        let mut drinker = character(campaign.id);
+        drinker.current_hit_points = 1;
+        drinker.maximum_hit_points = 20;
+        drinker.equipment = vec![EquipmentEntry {
+            equiped: false,
+            quantity: 2,
+            item_id: potion.id,
+            container_id: None,
+        }];
+        let drinker_id = drinker.id;
+        let potion_id = potion.id;
+
+        let stored = Arc::new(Mutex::new(drinker));
+        let stored_clone = Arc::clone(&stored);
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(stored_clone.lock().unwrap().clone())));
+        let stored_clone = Arc::clone(&stored);
+        db.characters.on_update_character = Box::new(move |character| {
+            *stored_clone.lock().unwrap() = character.clone();
+            Ok(character)
+        });
+        db.items.on_fetch_item_by_id = Box::new(move |_| Ok(Some(potion.clone())));
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.campaigns.on_fetch_campaign_by_id = Box::new(|_| Ok(None));
+        db.operations.on_insert_operation = Box::new(|_| Ok(()));
+
+        let body = || ItemUseBody {
+            character_id: drinker_id,
+            item_id: potion_id,
+            target_character_id: None,
+            targets: vec![],
+            ignore_violations: false,
+        };
+        let operation = create_item_use_operation(&db, &campaign, body())
+            .await
+            .unwrap();
+
+        let result = match operation.operation_type {
+            OperationType::UseItem(ItemUse {
+                effect: ItemUseEffect::Healing { result, .. },
+                ..
+            }) => result,
+            _ => panic!("expected the potion to heal"),
+        };
+        assert!((4..=10).contains(&result));
+        let drinker = stored.lock().unwrap().clone();
+        assert_eq!(drinker.current_hit_points, 1 + result);
+        assert_eq!(drinker.equipment[0].quantity, 1);
+
+        create_item_use_operation(&db, &campaign, body())
+            .await
+            .unwrap();
+        let drinker = stored.lock().unwrap().clone();
+        assert!(
+            drinker.equipment.is_empty(),
+            "the last potion was not used up"
+        );
+
+        let result = create_item_use_operation(&db, &campaign, body()).await;
+        assert!(result.is_err(), "a potion was drunk that wasn't carried");
+    }
+
+    #[actix_rt::test]
+    async fn casting_outside_the_encounter_is_refused_to_its_characters() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let caster = character(campaign.id);
+        let caster_id = caster.id;
+        let current = encounter(campaign.id, vec![caster_id]);
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(caster.clone())));
+        db.encounters.on_fetch_current_encounter_by_campaign =
+            Box::new(move |_| Ok(Some(current.clone())));
+
+        let body = CampaignCastBody {
+            character_id: caster_id,
+            cast: detect_magic(),
+            ignore_violations: false,
+        };
+        let result = create_cast_operation(&db, &campaign, body).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::CharacterInCurrentEncounter { character_id }) if character_id == caster_id
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn rejected_scroll_is_not_used_up() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let scroll = Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Spell Scroll".to_string(),
+            weight: 0,
+            value: 5000,
+            item_type: ItemType::Consumable(Consumable {
+                effect: ConsumableEffect::SpellScroll {
+                    spell: "Detect Magic".to_string(),
+                    level: 1,
+                },
+            }),
+            magic: None,
+        };
+        let reader = silenced_caster(&campaign, &scroll);
+        let reader_id = reader.id;
+        let scroll_id = scroll.id;
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(reader.clone())));
+        db.items.on_fetch_item_by_id = Box::new(move |_| Ok(Some(scroll.clone())));
+        db.encounters.on_fetch_current_encounter_by_campaign = Box::new(|_| Ok(None));
+        db.operations.on_insert_operation = Box::new(|_| Ok(()));
+        db.operations.on_delete_operation = Box::new(|_| Ok(()));
+        let called_update = Arc::new(Mutex::new(false));
+        let called_update_clone = Arc::clone(&called_update);
+        db.characters.on_update_character = Box::new(move |character| {
+            *called_update_clone.lock().unwrap() = true;
+            Ok(character)
+        });
+
+        let body = ItemUseBody {
+            character_id: reader_id,
+            item_id: scroll_id,
+            target_character_id: None,
+            targets: vec![],
+            ignore_violations: true,
+        };
+        let operation = create_item_use_operation(&db, &campaign, body)
+            .await
+            .unwrap();
+        assert!(matches!(
+            operation.legality,
+            Legality::IllegalPending { .. }
+        ));
+
+        reject_illegal_operation(&db, &campaign, operation)
+            .await
+            .unwrap();
+
+        assert!(
+            !*called_update.lock().unwrap(),
+            "db.update_character was called"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn legendary_actions_come_back_at_the_start_of_the_monsters_turn() {
+        let mut db = MockDatabase::new();
+        let campaign = campaign();
+        let mut dragon = goblin_instance(campaign.id);
+        if let Some(monster) = dragon.monster.as_mut() {
+            monster.stat_block.legendary_actions = Some(LegendaryActions {
+                per_round: 3,
+                actions: vec![LegendaryAction {
+                    name: "Wing Attack".to_string(),
+                    cost: 2,
+                    attack: None,
+                }],
+            });
+        }
+        let fighter = character(campaign.id);
+        let rogue = character(campaign.id);
+        let dragon_id = dragon.id;
+        let mut encounter = encounter(campaign.id, vec![dragon.id, fighter.id, rogue.id]);
+
+        let wing_attack = |round, character_id| {
+            let now = Utc::now();
+            Operation {
+                id: OperationId::new(),
+                campaign_id: campaign.id,
+                encounter_id: Some(encounter.id),
+                encounter_state: Some(EncounterState::Turn {
+                    round,
+                    character_id,
+                }),
+                character_id: dragon_id,
+                created_at: now,
+                modified_at: now,
+                operation_type: OperationType::LegendaryAction(LegendaryActionUse {
+                    name: "Wing Attack".to_string(),
+                    cost: 2,
+                    attack: None,
+                }),
+                interactions: vec![],
+                legality: Legality::Legal,
+            }
+        };
+        // one before the dragon's last turn and one after it
+        let operations = vec![wing_attack(0, rogue.id), wing_attack(1, fighter.id)];
+        db.operations.on_fetch_operations_by_encounter = Box::new(move |_| Ok(operations.clone()));
+        db.characters.on_fetch_character_by_campaign_and_id =
+            Box::new(move |_, _| Ok(Some(dragon.clone())));
+
+        let body = || LegendaryActionBody {
+            character_id: dragon_id,
+            name: "Wing Attack".to_string(),
+            target_character_ids: vec![],
+            ignore_violations: false,
+        };
+
+        let result = create_legendary_action_operation(&db, &campaign, &encounter, body()).await;
+        assert!(matches!(result, Err(Error::EncounterHasNotBegun { .. })));
+
+        encounter.state = EncounterState::Turn {
+            round: 1,
+            character_id: rogue.id,
+        };
+        let result = create_legendary_action_operation(&db, &campaign, &encounter, body()).await;
+        match result {
+            Err(Error::OperationViolatesRules { violations }) => assert!(matches!(
+                violations.as_slice(),
+                [Violation::LegendaryActionsExhausted {
+                    current_actions: 2,
+                    ..
+                }]
+            )),
+            other => panic!(
+                "expected the legendary actions to be spent, got {:?}",
+                other
+            ),
+        }
+    }
+}
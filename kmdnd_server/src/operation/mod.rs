@@ -3,22 +3,38 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 use crate::campaign::CampaignId;
+use crate::character::class::LimitedFeature;
 use crate::character::CharacterId;
 use crate::character::Position;
 use crate::encounter::EncounterId;
 use crate::encounter::EncounterState;
+use crate::error::Error;
+use crate::item::DiceRoll;
 use crate::typedid::{TypedId, TypedIdMarker};
 use crate::violations::Violation;
 
+pub mod ai;
 pub mod attack;
+pub mod breath;
 pub mod db;
 pub mod endpoints;
+pub mod inventory;
+pub mod item_use;
+pub mod legendary;
 pub mod manager;
+pub mod rest;
 pub mod spell;
+pub mod trade;
 pub use endpoints::*;
 
 use attack::Attack;
+use breath::BreathWeapon;
+use inventory::InventoryChange;
+use item_use::ItemUse;
+use legendary::LegendaryActionUse;
+use rest::Rest;
 use spell::Cast;
+use trade::Trade;
 
 pub type OperationId = TypedId<Operation>;
 
@@ -52,6 +68,12 @@ pub enum OperationType {
     Action(Action),
     Bonus { name: String },
     Roll { roll: RollType, result: i32 },
+    UseFeature { feature: LimitedFeature },
+    Rest(Rest),
+    Inventory(InventoryChange),
+    Trade(Trade),
+    UseItem(ItemUse),
+    LegendaryAction(LegendaryActionUse),
 }
 
 impl OperationType {
@@ -189,6 +211,17 @@ pub enum AbilityType {
     Charisma,
 }
 
+impl AbilityType {
+    pub const ALL: &'static [AbilityType] = &[
+        AbilityType::Strength,
+        AbilityType::Dexterity,
+        AbilityType::Constitution,
+        AbilityType::Intelligence,
+        AbilityType::Wisdom,
+        AbilityType::Charisma,
+    ];
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum SkillType {
@@ -213,6 +246,27 @@ pub enum SkillType {
 }
 
 impl SkillType {
+    pub const ALL: &'static [SkillType] = &[
+        SkillType::Acrobatics,
+        SkillType::AnimalHandling,
+        SkillType::Arcana,
+        SkillType::Athletics,
+        SkillType::Deception,
+        SkillType::History,
+        SkillType::Insight,
+        SkillType::Intimidation,
+        SkillType::Investigation,
+        SkillType::Medicine,
+        SkillType::Nature,
+        SkillType::Perception,
+        SkillType::Performance,
+        SkillType::Persuasion,
+        SkillType::Religion,
+        SkillType::SleightOfHand,
+        SkillType::Stealth,
+        SkillType::Survival,
+    ];
+
     pub fn ability(&self) -> AbilityType {
         match self {
             SkillType::Acrobatics => AbilityType::Dexterity,
@@ -241,6 +295,9 @@ impl SkillType {
 pub struct Move {
     to_position: Position,
     feet: f32,
+    // hostiles whose reach the move leaves, who can make an opportunity attack
+    #[serde(default)]
+    opportunity_attacks: Vec<CharacterId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -248,6 +305,7 @@ pub struct Move {
 pub enum Action {
     Attack(Attack),
     CastSpell(Cast),
+    BreathWeapon(BreathWeapon),
     Dash,
     Disengage,
     Dodge,
@@ -266,14 +324,35 @@ pub enum SpellTarget {
     None,
 }
 
+impl SpellTarget {
+    pub fn character_id(&self) -> Option<CharacterId> {
+        match self {
+            SpellTarget::Creature { character_id } => Some(*character_id),
+            _ => None,
+        }
+    }
+}
+
 pub type InteractionId = TypedId<Interaction>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Interaction {
     id: InteractionId,
     character_id: CharacterId,
+    target_character_id: Option<CharacterId>,
     roll_type: RollType,
     result: Option<i32>,
+    // dice rolled along with the usual ones, like sneak attack on a hit
+    #[serde(default)]
+    bonus_dice: Option<DiceRoll>,
+}
+
+impl Interaction {
+    pub fn expect_target_character_id(&self) -> Result<CharacterId, Error> {
+        self.target_character_id.ok_or_else(|| {
+            Error::ExistentialState(format!("Expected interaction {} to have a target", self.id))
+        })
+    }
 }
 
 impl TypedIdMarker for Interaction {
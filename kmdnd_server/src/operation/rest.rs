@@ -0,0 +1,174 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::character::{Character, Condition, HitDiceUse};
+use crate::error::Error;
+use crate::item::{Dice, DiceRoll, ItemId};
+use crate::operation::AbilityType;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum RestType {
+    Short,
+    Long,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HitDieRoll {
+    pub dice: Dice,
+    pub result: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rest {
+    pub rest_type: RestType,
+    pub hit_dice_rolls: Vec<HitDieRoll>,
+    pub hit_points_regained: i32,
+    pub attuned: Vec<ItemId>,
+    pub attunement_ended: Vec<ItemId>,
+}
+
+impl Rest {
+    // applies the rest to the character, rolling any hit dice spent on a
+    // short rest along with the character's constitution modifier
+    pub fn take<R: Rng>(
+        rng: &mut R,
+        character: &mut Character,
+        rest_type: RestType,
+        hit_dice: Vec<Dice>,
+    ) -> Result<Rest, Error> {
+        let starting_hit_points = character.current_hit_points;
+        let mut hit_dice_rolls = vec![];
+
+        match rest_type {
+            RestType::Short => {
+                let constitution_modifier = character
+                    .stats
+                    .abilities
+                    .modifier(AbilityType::Constitution);
+
+                for dice in hit_dice {
+                    if character.hit_dice_remaining(dice) < 1 {
+                        return Err(Error::CharacterLacksHitDice {
+                            character_id: character.id,
+                            dice,
+                        });
+                    }
+
+                    match character
+                        .expended_hit_dice
+                        .iter_mut()
+                        .find(|usage| usage.dice == dice)
+                    {
+                        Some(usage) => usage.expended += 1,
+                        None => character
+                            .expended_hit_dice
+                            .push(HitDiceUse { dice, expended: 1 }),
+                    }
+
+                    let result =
+                        i32::max(rng.gen_range(1..=dice.sides()) + constitution_modifier, 0);
+                    character.current_hit_points = i32::min(
+                        character.current_hit_points + result,
+                        character.maximum_hit_points,
+                    );
+
+                    hit_dice_rolls.push(HitDieRoll { dice, result });
+                }
+
+                for usage in &mut character.feature_uses {
+                    if usage.feature.recharges_on_short_rest() {
+                        usage.expended = 0;
+                    }
+                }
+
+                for slot in &mut character.spell_slots {
+                    if slot.pact_magic {
+                        slot.expended = 0;
+                    }
+                }
+            }
+            RestType::Long => {
+                character.current_hit_points = character.maximum_hit_points;
+                character.expended_hit_dice =
+                    recover_hit_dice(&character.hit_dice(), &character.expended_hit_dice);
+
+                for slot in &mut character.spell_slots {
+                    slot.expended = 0;
+                }
+
+                for usage in &mut character.feature_uses {
+                    usage.expended = 0;
+                }
+
+                character.exhaustion = i32::max(character.exhaustion - 1, 0);
+                character
+                    .conditions
+                    .retain(|condition| *condition != Condition::Raging);
+            }
+        }
+
+        Ok(Rest {
+            rest_type,
+            hit_dice_rolls,
+            hit_points_regained: character.current_hit_points - starting_hit_points,
+            attuned: vec![],
+            attunement_ended: vec![],
+        })
+    }
+}
+
+// a long rest recovers up to half of the character's total hit dice (minimum
+// of one), with the largest dice recovered first
+pub fn recover_hit_dice(hit_dice: &[DiceRoll], expended: &[HitDiceUse]) -> Vec<HitDiceUse> {
+    let total: i32 = hit_dice.iter().map(|roll| roll.amount).sum();
+    let mut recoverable = i32::max(total / 2, 1);
+
+    let mut expended = expended.to_vec();
+    expended.sort_by_key(|usage| -usage.dice.sides());
+    for usage in &mut expended {
+        let recovered = i32::min(usage.expended, recoverable);
+        usage.expended -= recovered;
+        recoverable -= recovered;
+    }
+
+    expended.retain(|usage| usage.expended > 0);
+    expended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_rest_recovers_half_of_hit_dice() {
+        let hit_dice = vec![DiceRoll::new(3, Dice::D10), DiceRoll::new(2, Dice::D6)];
+        let expended = vec![
+            HitDiceUse {
+                dice: Dice::D10,
+                expended: 2,
+            },
+            HitDiceUse {
+                dice: Dice::D6,
+                expended: 2,
+            },
+        ];
+
+        let remaining = recover_hit_dice(&hit_dice, &expended);
+
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].dice, Dice::D6);
+        assert_eq!(remaining[0].expended, 2);
+    }
+
+    #[test]
+    fn long_rest_recovers_at_least_one_hit_die() {
+        let hit_dice = vec![DiceRoll::new(1, Dice::D8)];
+        let expended = vec![HitDiceUse {
+            dice: Dice::D8,
+            expended: 1,
+        }];
+
+        assert!(recover_hit_dice(&hit_dice, &expended).is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use mongodb::bson;
+use mongodb::options::ReplaceOptions;
+
+use crate::database::MongoSpellStore;
+use crate::error::Error;
+
+use super::Spell;
+
+#[async_trait]
+pub trait SpellStore {
+    async fn upsert_spell(&self, spell: &Spell) -> Result<(), Error>;
+
+    async fn fetch_spell_by_name(&self, name: &str) -> Result<Option<Spell>, Error>;
+}
+
+#[async_trait]
+impl SpellStore for MongoSpellStore {
+    #[tracing::instrument(skip(self))]
+    async fn upsert_spell(&self, spell: &Spell) -> Result<(), Error> {
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.replace_one(bson::doc! { "name": &spell.name }, spell, options)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_spell_by_name(&self, name: &str) -> Result<Option<Spell>, Error> {
+        let spell = self.find_one(bson::doc! { "name": name }, None).await?;
+
+        Ok(spell)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::type_complexity)]
+pub struct MockSpellStore {
+    pub on_upsert_spell: Box<dyn Fn(&Spell) -> Result<(), Error> + Send + Sync>,
+    pub on_fetch_spell_by_name: Box<dyn Fn(&str) -> Result<Option<Spell>, Error> + Send + Sync>,
+}
+
+#[cfg(test)]
+impl MockSpellStore {
+    pub fn new() -> MockSpellStore {
+        MockSpellStore {
+            on_upsert_spell: Box::new(|_| unimplemented!("MockSpellStore::upsert_spell")),
+            on_fetch_spell_by_name: Box::new(|_| {
+                unimplemented!("MockSpellStore::fetch_spell_by_name")
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl SpellStore for MockSpellStore {
+    async fn upsert_spell(&self, spell: &Spell) -> Result<(), Error> {
+        (self.on_upsert_spell)(spell)
+    }
+
+    async fn fetch_spell_by_name(&self, name: &str) -> Result<Option<Spell>, Error> {
+        (self.on_fetch_spell_by_name)(name)
+    }
+}
@@ -0,0 +1,1138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::Campaign;
+use crate::character::{self, Character, CharacterId, Position};
+use crate::currency::CopperPieces;
+use crate::database::Database;
+use crate::encounter::{self, Encounter};
+use crate::error::Error;
+use crate::item::{self, DamageType, Dice, DiceRoll};
+use crate::operation::{AbilityType, InteractionId, RollType, SpellTarget};
+use crate::violations::Violation;
+
+use super::{CastBody, Interaction, Operation};
+
+pub mod db;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cast {
+    pub spell: String,
+    pub slot_level: Option<i32>,
+    pub ritual: bool,
+    pub casting_time: CastingTime,
+    pub targets: Vec<SpellTarget>,
+    pub damage: Option<DiceRoll>,
+}
+
+impl Cast {
+    pub async fn submit(
+        db: &dyn Database,
+        campaign: &Campaign,
+        encounter: Option<&Encounter>,
+        source_character: Character,
+        body: CastBody,
+    ) -> Result<(Cast, Vec<Interaction>, Vec<Violation>), Error> {
+        let CastBody {
+            name,
+            targets,
+            slot_level,
+            ritual,
+        } = body;
+        let spell = get_spell_by_name(db, &name)
+            .await?
+            .ok_or(Error::SpellDoesNotExist { name })?;
+
+        if ritual && !spell.ritual {
+            return Err(Error::SpellIsNotRitual { name: spell.name });
+        }
+
+        let mut violations = spell.check_components(db, &source_character).await?;
+
+        let casting_time = if ritual {
+            spell.casting_time.as_ritual()
+        } else {
+            spell.casting_time.clone()
+        };
+
+        if encounter.is_some() && !casting_time.fits_in_turn() {
+            violations.push(Violation::CastingTimeExceedsTurn {
+                request_character_id: source_character.id,
+                casting_time: casting_time.clone(),
+            });
+        }
+
+        // cantrips are cast at will, rituals are cast without expending a
+        // slot, everything else expends a slot
+        let slot_level = if spell.level == 0 || ritual {
+            None
+        } else {
+            let slot_level = slot_level.unwrap_or(spell.level);
+            if slot_level < spell.level {
+                return Err(Error::CastUsesInvalidSlotLevel {
+                    spell_level: spell.level,
+                    slot_level,
+                });
+            }
+
+            if !source_character.has_spell_slot(slot_level) {
+                violations.push(Violation::CastSpellSlotUnavailable {
+                    request_character_id: source_character.id,
+                    slot_level,
+                });
+            }
+
+            Some(slot_level)
+        };
+
+        let expected_count = spell.target_count(source_character.level(), slot_level);
+        if targets.len() != expected_count {
+            return Err(Error::CastUsesWrongTargetCount {
+                expected_count,
+                provided_count: targets.len(),
+            });
+        }
+
+        // positions are only tracked loosely outside of an encounter
+        let source_position = match (encounter, source_character.position) {
+            (_, Some(position)) => Some(position),
+            (Some(_), None) => {
+                return Err(Error::CharacterDoesNotHavePosition {
+                    character_id: source_character.id,
+                })
+            }
+            (None, None) => None,
+        };
+
+        for target in &targets {
+            let target_position = match (spell.target, target) {
+                (SpellTargetType::Creature, SpellTarget::Creature { character_id }) => {
+                    let character_id = *character_id;
+                    let target_character =
+                        character::manager::get_character_by_id(db, campaign, character_id)
+                            .await?
+                            .ok_or(Error::CharacterNotInCampaign {
+                                campaign_id: campaign.id,
+                                character_id,
+                            })?;
+
+                    if let Some(encounter) = encounter {
+                        if !encounter.character_ids.contains(&character_id) {
+                            return Err(Error::CharacterNotInEncounter {
+                                campaign_id: campaign.id,
+                                encounter_id: encounter.id,
+                                character_id,
+                            });
+                        }
+                    }
+
+                    match (encounter, target_character.position) {
+                        (_, Some(position)) => position,
+                        (Some(_), None) => {
+                            return Err(Error::CharacterDoesNotHavePosition { character_id })
+                        }
+                        (None, None) => continue,
+                    }
+                }
+                (SpellTargetType::Position, SpellTarget::Position { position }) => *position,
+                (SpellTargetType::None, SpellTarget::None) => continue,
+                (expected_type, provided_type) => {
+                    return Err(Error::CastUsesWrongTargetType {
+                        expected_type,
+                        provided_type: provided_type.clone(),
+                    })
+                }
+            };
+
+            let source_position = match &source_position {
+                Some(source_position) => source_position,
+                None => continue,
+            };
+
+            let spell_range = spell.range.feet();
+            let cast_distance = Position::distance(source_position, &target_position);
+            if cast_distance > spell_range {
+                violations.push(Violation::CastNotInRange {
+                    request_character_id: source_character.id,
+                    target_position,
+                    spell_range,
+                    current_range: cast_distance,
+                });
+            }
+        }
+
+        // harmful spells warn about allies, or the caster, caught by them
+        if let (Some(encounter), false) = (encounter, matches!(spell.effect, SpellEffect::None)) {
+            let characters = encounter::manager::get_encounter_characters(db, encounter).await?;
+            let is_friendly = |character_id: CharacterId| {
+                character_id == source_character.id
+                    || characters.iter().any(|character| {
+                        character.id == character_id
+                            && encounter.are_allies(&source_character, character)
+                    })
+            };
+
+            for target in &targets {
+                let affected: Vec<CharacterId> = match (target, &spell.effect) {
+                    (SpellTarget::Creature { character_id }, _) => vec![*character_id],
+                    (
+                        SpellTarget::Position { position },
+                        SpellEffect::Save {
+                            radius: Some(radius),
+                            ..
+                        },
+                    ) => characters
+                        .iter()
+                        .filter(|character| {
+                            character.position.is_some_and(|character_position| {
+                                character_position.distance(position) <= *radius
+                            })
+                        })
+                        .map(|character| character.id)
+                        .collect(),
+                    _ => vec![],
+                };
+
+                for character_id in affected {
+                    if is_friendly(character_id) {
+                        violations.push(Violation::FriendlyFire {
+                            request_character_id: source_character.id,
+                            target_character_id: character_id,
+                        });
+                    }
+                }
+            }
+        }
+
+        let interactions = match &spell.effect {
+            SpellEffect::Attack { .. } => targets
+                .iter()
+                .map(|target| Interaction {
+                    id: InteractionId::new(),
+                    character_id: source_character.id,
+                    target_character_id: target.character_id(),
+                    roll_type: RollType::Hit,
+                    result: None,
+                    bonus_dice: None,
+                })
+                .collect(),
+            SpellEffect::Missile { .. } => targets
+                .iter()
+                .map(|target| Interaction {
+                    id: InteractionId::new(),
+                    character_id: source_character.id,
+                    target_character_id: target.character_id(),
+                    roll_type: RollType::Damage,
+                    result: None,
+                    bonus_dice: None,
+                })
+                .collect(),
+            SpellEffect::Save { .. } => vec![Interaction {
+                id: InteractionId::new(),
+                character_id: source_character.id,
+                target_character_id: None,
+                roll_type: RollType::Damage,
+                result: None,
+                bonus_dice: None,
+            }],
+            SpellEffect::None => vec![],
+        };
+
+        let cast = Cast {
+            damage: spell.damage(source_character.level(), slot_level),
+            spell: spell.name,
+            slot_level,
+            ritual,
+            casting_time,
+            targets,
+        };
+
+        Ok((cast, interactions, violations))
+    }
+
+    pub async fn consume_material_components(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        character_id: CharacterId,
+    ) -> Result<(), Error> {
+        let spell = self.expect_spell(db).await?;
+
+        let mut character =
+            character::manager::expect_character_by_id(db, campaign, character_id).await?;
+        for component in &spell.components {
+            if let SpellComponent::Material {
+                name,
+                cost: Some(cost),
+                consumed: true,
+            } = component
+            {
+                let index = match find_material(db, &character, name, *cost).await? {
+                    Some(index) => index,
+                    None => continue, // missing materials are reported as violations
+                };
+
+                let mut equipment = character.equipment.clone();
+                equipment[index].quantity -= 1;
+                if equipment[index].quantity <= 0 {
+                    equipment.remove(index);
+                }
+
+                character =
+                    character::manager::update_character_equipment(db, character, equipment)
+                        .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn expend_spell_slot(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        character_id: CharacterId,
+    ) -> Result<(), Error> {
+        let slot_level = match self.slot_level {
+            Some(slot_level) => slot_level,
+            None => return Ok(()),
+        };
+
+        let character =
+            character::manager::expect_character_by_id(db, campaign, character_id).await?;
+
+        let mut spell_slots = character.spell_slots.clone();
+        let slot = spell_slots
+            .iter_mut()
+            .find(|slot| slot.level == slot_level && slot.expended < slot.total);
+
+        // a missing slot is reported as a violation
+        if let Some(slot) = slot {
+            slot.expended += 1;
+            character::manager::update_character_spell_slots(db, character, spell_slots).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn handle_interaction_result(
+        &self,
+        db: &dyn Database,
+        campaign: &Campaign,
+        encounter: Option<&Encounter>,
+        operation: &Operation,
+        interaction: &Interaction,
+        result: i32,
+    ) -> Result<Vec<Interaction>, Error> {
+        let spell = self.expect_spell(db).await?;
+
+        let new_interactions = match (&spell.effect, interaction.roll_type) {
+            (SpellEffect::Attack { .. }, RollType::Hit) => {
+                let target_character_id = interaction.expect_target_character_id()?;
+                let target_character =
+                    character::manager::expect_character_by_id(db, campaign, target_character_id)
+                        .await?;
+
+                if target_character.stats.armor_class <= result {
+                    vec![Interaction {
+                        id: InteractionId::new(),
+                        character_id: interaction.character_id,
+                        target_character_id: Some(target_character_id),
+                        roll_type: RollType::Damage,
+                        result: None,
+                        bonus_dice: None,
+                    }]
+                } else {
+                    vec![]
+                }
+            }
+            (SpellEffect::Attack { .. }, RollType::Damage)
+            | (SpellEffect::Missile { .. }, RollType::Damage) => {
+                let target_character_id = interaction.expect_target_character_id()?;
+                let target_character =
+                    character::manager::expect_character_by_id(db, campaign, target_character_id)
+                        .await?;
+
+                let damage = match spell.effect.damage_type() {
+                    Some(damage_type) => target_character.resisted_damage(result, damage_type),
+                    None => result,
+                };
+
+                let new_hit_points = i32::max(target_character.current_hit_points - damage, 0);
+                character::manager::update_character_hit_points(
+                    db,
+                    target_character,
+                    new_hit_points,
+                )
+                .await?;
+
+                vec![]
+            }
+            (
+                SpellEffect::Save {
+                    ability, radius, ..
+                },
+                RollType::Damage,
+            ) => {
+                let mut affected_characters = vec![];
+                for target in &self.targets {
+                    match (target, radius) {
+                        (SpellTarget::Position { position }, Some(radius)) => {
+                            // outside of an encounter, anyone in the campaign
+                            // could be caught in the area
+                            let characters = match encounter {
+                                Some(encounter) => {
+                                    let mut characters = vec![];
+                                    for &character_id in &encounter.character_ids {
+                                        let character = character::manager::expect_character_by_id(
+                                            db,
+                                            campaign,
+                                            character_id,
+                                        )
+                                        .await?;
+
+                                        characters.push(character);
+                                    }
+                                    characters
+                                }
+                                None => character::manager::get_characters(db, campaign).await?,
+                            };
+
+                            for character in characters {
+                                let in_range = character
+                                    .position
+                                    .map(|character_position| {
+                                        character_position.distance(position) <= *radius
+                                    })
+                                    .unwrap_or(false);
+
+                                if in_range {
+                                    affected_characters.push(character.id);
+                                }
+                            }
+                        }
+                        (SpellTarget::Creature { character_id }, None) => {
+                            affected_characters.push(*character_id);
+                        }
+                        (unexpected_target, _) => {
+                            return Err(Error::CastUsesWrongTargetType {
+                                expected_type: spell.target,
+                                provided_type: unexpected_target.clone(),
+                            })
+                        }
+                    }
+                }
+
+                affected_characters
+                    .into_iter()
+                    .map(|character_id| Interaction {
+                        id: InteractionId::new(),
+                        character_id,
+                        target_character_id: None,
+                        roll_type: RollType::Save(*ability),
+                        result: None,
+                        bonus_dice: None,
+                    })
+                    .collect()
+            }
+            (
+                SpellEffect::Save {
+                    ability,
+                    damage_type,
+                    half_on_success,
+                    ..
+                },
+                RollType::Save(save_ability),
+            ) if *ability == save_ability => {
+                let target_character = character::manager::expect_character_by_id(
+                    db,
+                    campaign,
+                    interaction.character_id,
+                )
+                .await?;
+
+                let damage_interaction = operation
+                    .interactions
+                    .iter()
+                    .find(|i| i.roll_type == RollType::Damage)
+                    .ok_or_else(|| {
+                        Error::ExistentialState(format!(
+                            "Expected {} to have damage roll interaction",
+                            spell.name
+                        ))
+                    })?;
+                let max_damage = damage_interaction.result.ok_or_else(|| {
+                    Error::ExistentialState(format!(
+                        "Expected {} damage roll to have result",
+                        spell.name
+                    ))
+                })?;
+
+                let caster = character::manager::expect_character_by_id(
+                    db,
+                    campaign,
+                    operation.character_id,
+                )
+                .await?;
+                let difficulty_class = caster.spell_save_difficulty_class();
+                let damage = if result < difficulty_class {
+                    max_damage
+                } else if *half_on_success {
+                    max_damage / 2
+                } else {
+                    0
+                };
+                let damage = target_character.resisted_damage(damage, *damage_type);
+
+                let new_hit_points = i32::max(target_character.current_hit_points - damage, 0);
+                character::manager::update_character_hit_points(
+                    db,
+                    target_character,
+                    new_hit_points,
+                )
+                .await?;
+
+                vec![]
+            }
+            _ => vec![],
+        };
+
+        Ok(new_interactions)
+    }
+
+    async fn expect_spell(&self, db: &dyn Database) -> Result<Spell, Error> {
+        get_spell_by_name(db, &self.spell).await?.ok_or_else(|| {
+            Error::ExistentialState(format!("Expected spell {} to exist", self.spell))
+        })
+    }
+}
+
+// the spells built into the server take precedence over imported ones
+#[tracing::instrument(skip(db))]
+pub async fn get_spell_by_name(db: &dyn Database, name: &str) -> Result<Option<Spell>, Error> {
+    match Spell::fetch_spell_by_name(name) {
+        Some(spell) => Ok(Some(spell)),
+        None => db.spells().fetch_spell_by_name(name).await,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spell {
+    pub name: String,
+    pub level: i32,
+    pub school: MagicSchool,
+    pub casting_time: CastingTime,
+    pub range: SpellRange,
+    pub target: SpellTargetType,
+    pub components: Vec<SpellComponent>,
+    pub duration: SpellDuration,
+    pub concentration: bool,
+    pub ritual: bool,
+    pub effect: SpellEffect,
+    pub scaling: SpellScaling,
+    pub description: String,
+}
+
+impl Spell {
+    pub fn fetch_spell_by_name(name: &str) -> Option<Spell> {
+        match name {
+            "Detect Magic" => Some(Spell {
+                name: "Detect Magic".to_string(),
+                level: 1,
+                school: MagicSchool::Divination,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Personal,
+                target: SpellTargetType::None,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Minute(10),
+                concentration: true,
+                ritual: true,
+                effect: SpellEffect::None,
+                scaling: SpellScaling::None,
+                description: "You sense the presence of magic within 30 feet of you".to_string(),
+            }),
+            "Eldritch Blast" => Some(Spell {
+                name: "Eldritch Blast".to_string(),
+                level: 0,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(120.0),
+                target: SpellTargetType::Creature,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Attack {
+                    damage: DiceRoll::new(1, Dice::D10),
+                    damage_type: DamageType::Force,
+                    beams: 1,
+                },
+                scaling: SpellScaling::CantripBeams,
+                description: "A beam of crackling energy streaks toward a creature".to_string(),
+            }),
+            "Fire Bolt" => Some(Spell {
+                name: "Fire Bolt".to_string(),
+                level: 0,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(120.0),
+                target: SpellTargetType::Creature,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Attack {
+                    damage: DiceRoll::new(1, Dice::D10),
+                    damage_type: DamageType::Fire,
+                    beams: 1,
+                },
+                scaling: SpellScaling::CantripDamage,
+                description: "You hurl a mote of fire at a creature".to_string(),
+            }),
+            "Fireball" => Some(Spell {
+                name: "Fireball".to_string(),
+                level: 3,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(150.0),
+                target: SpellTargetType::Position,
+                components: vec![
+                    SpellComponent::Verbal,
+                    SpellComponent::Somatic,
+                    SpellComponent::Material {
+                        name: "Bat guano and sulfur".to_string(),
+                        cost: None,
+                        consumed: false,
+                    },
+                ],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Save {
+                    ability: AbilityType::Dexterity,
+                    damage: DiceRoll::new(8, Dice::D6),
+                    damage_type: DamageType::Fire,
+                    half_on_success: true,
+                    radius: Some(20.0),
+                },
+                scaling: SpellScaling::SlotDamage,
+                description: "blows shit up".to_string(),
+            }),
+            "Identify" => Some(Spell {
+                name: "Identify".to_string(),
+                level: 1,
+                school: MagicSchool::Divination,
+                casting_time: CastingTime::Minute(1),
+                range: SpellRange::Touch,
+                target: SpellTargetType::None,
+                components: vec![
+                    SpellComponent::Verbal,
+                    SpellComponent::Somatic,
+                    SpellComponent::Material {
+                        name: "Pearl".to_string(),
+                        cost: Some(10000),
+                        consumed: false,
+                    },
+                ],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: true,
+                effect: SpellEffect::None,
+                scaling: SpellScaling::None,
+                description: "You learn the properties of one magic item you touch".to_string(),
+            }),
+            "Magic Missile" => Some(Spell {
+                name: "Magic Missile".to_string(),
+                level: 1,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(120.0),
+                target: SpellTargetType::Creature,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Missile {
+                    damage: DiceRoll {
+                        amount: 1,
+                        dice: Dice::D4,
+                        modifier: 1,
+                    },
+                    damage_type: DamageType::Force,
+                    darts: 3,
+                },
+                scaling: SpellScaling::SlotBeams,
+                description: "You create three glowing darts of magical force".to_string(),
+            }),
+            "Sacred Flame" => Some(Spell {
+                name: "Sacred Flame".to_string(),
+                level: 0,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(60.0),
+                target: SpellTargetType::Creature,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Save {
+                    ability: AbilityType::Dexterity,
+                    damage: DiceRoll::new(1, Dice::D8),
+                    damage_type: DamageType::Radiant,
+                    half_on_success: false,
+                    radius: None,
+                },
+                scaling: SpellScaling::CantripDamage,
+                description: "Flame-like radiance descends on a creature".to_string(),
+            }),
+            "Scorching Ray" => Some(Spell {
+                name: "Scorching Ray".to_string(),
+                level: 2,
+                school: MagicSchool::Evocation,
+                casting_time: CastingTime::Action(1),
+                range: SpellRange::Feet(120.0),
+                target: SpellTargetType::Creature,
+                components: vec![SpellComponent::Verbal, SpellComponent::Somatic],
+                duration: SpellDuration::Instantaneous,
+                concentration: false,
+                ritual: false,
+                effect: SpellEffect::Attack {
+                    damage: DiceRoll::new(2, Dice::D6),
+                    damage_type: DamageType::Fire,
+                    beams: 3,
+                },
+                scaling: SpellScaling::SlotBeams,
+                description: "You create three rays of fire and hurl them at targets".to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn target_count(&self, character_level: i32, slot_level: Option<i32>) -> usize {
+        if self.target == SpellTargetType::None {
+            return 0;
+        }
+
+        let count = match &self.effect {
+            SpellEffect::Attack { beams: count, .. }
+            | SpellEffect::Missile { darts: count, .. } => *count,
+            _ => return 1,
+        };
+
+        let count = match self.scaling {
+            SpellScaling::CantripBeams => count * cantrip_tier(character_level),
+            SpellScaling::SlotBeams => count + self.levels_above_base(slot_level),
+            _ => count,
+        };
+
+        count as usize
+    }
+
+    pub fn damage(&self, character_level: i32, slot_level: Option<i32>) -> Option<DiceRoll> {
+        let mut damage = match &self.effect {
+            SpellEffect::Attack { damage, .. } => damage.clone(),
+            SpellEffect::Missile { damage, .. } => damage.clone(),
+            SpellEffect::Save { damage, .. } => damage.clone(),
+            SpellEffect::None => return None,
+        };
+
+        match self.scaling {
+            SpellScaling::CantripDamage => damage.amount *= cantrip_tier(character_level),
+            SpellScaling::SlotDamage => damage.amount += self.levels_above_base(slot_level),
+            _ => {}
+        }
+
+        Some(damage)
+    }
+
+    fn levels_above_base(&self, slot_level: Option<i32>) -> i32 {
+        slot_level
+            .map(|slot_level| i32::max(slot_level - self.level, 0))
+            .unwrap_or(0)
+    }
+
+    pub async fn check_components(
+        &self,
+        db: &dyn Database,
+        character: &Character,
+    ) -> Result<Vec<Violation>, Error> {
+        let mut violations = vec![];
+
+        for component in &self.components {
+            match component {
+                SpellComponent::Verbal => {
+                    let blocking_condition = character
+                        .conditions
+                        .iter()
+                        .find(|condition| condition.prevents_speech());
+
+                    if let Some(condition) = blocking_condition {
+                        violations.push(Violation::CastVerbalComponentBlocked {
+                            request_character_id: character.id,
+                            condition: *condition,
+                        });
+                    }
+                }
+                SpellComponent::Somatic => {
+                    let occupied_hands = character
+                        .equiped_items(db)
+                        .await?
+                        .iter()
+                        .map(|item| item.item_type.hands_occupied())
+                        .sum::<i32>();
+
+                    if occupied_hands >= 2 {
+                        violations.push(Violation::CastSomaticComponentBlocked {
+                            request_character_id: character.id,
+                            occupied_hands,
+                        });
+                    }
+                }
+                SpellComponent::Material {
+                    name,
+                    cost: Some(cost),
+                    ..
+                } => {
+                    if find_material(db, character, name, *cost).await?.is_none() {
+                        violations.push(Violation::CastMaterialComponentMissing {
+                            request_character_id: character.id,
+                            material: name.clone(),
+                            cost: *cost,
+                        });
+                    }
+                }
+                SpellComponent::Material { cost: None, .. } => {
+                    // materials without a cost are covered by a component
+                    // pouch or spellcasting focus
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+// finds the index of the equipment entry that satisfies a costly material
+// component
+async fn find_material(
+    db: &dyn Database,
+    character: &Character,
+    name: &str,
+    cost: i32,
+) -> Result<Option<usize>, Error> {
+    for (index, entry) in character.equipment.iter().enumerate() {
+        if entry.quantity <= 0 {
+            continue;
+        }
+
+        let item =
+            match item::manager::get_item_by_id(db, character.owner.campaign_id(), entry.item_id)
+                .await?
+            {
+                Some(item) => item,
+                None => continue,
+            };
+
+        if item.name.eq_ignore_ascii_case(name) && item.value >= cost {
+            return Ok(Some(index));
+        }
+    }
+
+    Ok(None)
+}
+
+// cantrips grow stronger at character levels 5, 11, and 17
+fn cantrip_tier(character_level: i32) -> i32 {
+    if character_level >= 17 {
+        4
+    } else if character_level >= 11 {
+        3
+    } else if character_level >= 5 {
+        2
+    } else {
+        1
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SpellTargetType {
+    Creature,
+    Position,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum SpellEffect {
+    // a spell attack is made against the target of each beam
+    Attack {
+        damage: DiceRoll,
+        damage_type: DamageType,
+        beams: i32,
+    },
+    // each dart hits its target automatically
+    Missile {
+        damage: DiceRoll,
+        damage_type: DamageType,
+        darts: i32,
+    },
+    // each creature in the area, or the targeted creature, makes a save
+    Save {
+        ability: AbilityType,
+        damage: DiceRoll,
+        damage_type: DamageType,
+        half_on_success: bool,
+        radius: Option<f32>,
+    },
+    None,
+}
+
+impl SpellEffect {
+    pub fn damage_type(&self) -> Option<DamageType> {
+        match self {
+            SpellEffect::Attack { damage_type, .. } => Some(*damage_type),
+            SpellEffect::Missile { damage_type, .. } => Some(*damage_type),
+            SpellEffect::Save { damage_type, .. } => Some(*damage_type),
+            SpellEffect::None => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum SpellScaling {
+    None,
+    CantripDamage,
+    CantripBeams,
+    SlotDamage,
+    SlotBeams,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CastingTime {
+    Action(i32),
+    BonusAction(i32),
+    Reaction(String),
+    Minute(i32),
+    Hour(i32),
+}
+
+impl CastingTime {
+    pub fn fits_in_turn(&self) -> bool {
+        matches!(
+            self,
+            CastingTime::Action(_) | CastingTime::BonusAction(_) | CastingTime::Reaction(_)
+        )
+    }
+
+    // casting a spell as a ritual takes 10 minutes longer than normal
+    pub fn as_ritual(&self) -> CastingTime {
+        match self {
+            CastingTime::Minute(minutes) => CastingTime::Minute(minutes + 10),
+            CastingTime::Hour(hours) => CastingTime::Minute(hours * 60 + 10),
+            _ => CastingTime::Minute(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpellComponent {
+    Verbal,
+    Somatic,
+    Material {
+        name: String,
+        cost: Option<CopperPieces>,
+        consumed: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpellRange {
+    Feet(f32),
+    Touch,
+    Personal,
+}
+
+impl SpellRange {
+    pub fn feet(&self) -> f32 {
+        match self {
+            SpellRange::Feet(feet) => *feet,
+            SpellRange::Touch => 5.0,
+            SpellRange::Personal => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpellDuration {
+    Instantaneous,
+    Round(i32),
+    Minute(i32),
+    Hour(i32),
+    Day(i32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MagicSchool {
+    Abjuration,
+    Conjuration,
+    Divination,
+    Enchantment,
+    Evocation,
+    Illusion,
+    Necromacy,
+    Transmutation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::CampaignId;
+    use crate::character::test::character;
+    use crate::character::{Condition, EquipmentEntry};
+    use crate::database::test::MockDatabase;
+    use crate::item::{Item, ItemId, ItemType, Weapon, WeaponProperty};
+
+    fn item(name: &str, value: CopperPieces, item_type: ItemType) -> Item {
+        Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: name.to_string(),
+            weight: 0,
+            value,
+            item_type,
+            magic: None,
+        }
+    }
+
+    fn carry(items: &[&Item]) -> Vec<EquipmentEntry> {
+        items
+            .iter()
+            .map(|item| EquipmentEntry {
+                equiped: item.item_type.hands_occupied() > 0,
+                quantity: 1,
+                item_id: item.id,
+                container_id: None,
+            })
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn components_are_checked_against_conditions_hands_and_materials() {
+        let spell = Spell {
+            components: vec![
+                SpellComponent::Verbal,
+                SpellComponent::Somatic,
+                SpellComponent::Material {
+                    name: "Diamond".to_string(),
+                    cost: Some(30000),
+                    consumed: true,
+                },
+            ],
+            ..Spell::fetch_spell_by_name("Fire Bolt").unwrap()
+        };
+        let sword = ItemType::Weapon(Weapon {
+            damage_amount: Dice::D8,
+            damage_type: DamageType::Slashing,
+            properties: vec![],
+        });
+        let sword = item("Longsword", 1500, sword);
+        let other_sword = Item {
+            id: ItemId::new(),
+            ..sword.clone()
+        };
+        let greatsword = ItemType::Weapon(Weapon {
+            damage_amount: Dice::D6,
+            damage_type: DamageType::Slashing,
+            properties: vec![WeaponProperty::Heavy, WeaponProperty::TwoHanded],
+        });
+        let greatsword = item("Greatsword", 5000, greatsword);
+        let cheap_diamond = item("Diamond", 10000, ItemType::Gear);
+        let diamond = item("Diamond", 30000, ItemType::Gear);
+
+        let mut db = MockDatabase::new();
+        let items = [
+            sword.clone(),
+            other_sword.clone(),
+            greatsword.clone(),
+            cheap_diamond.clone(),
+            diamond.clone(),
+        ];
+        db.items.on_fetch_item_by_id =
+            Box::new(move |item_id| Ok(items.iter().find(|item| item.id == item_id).cloned()));
+
+        let mut caster = character(CampaignId::new());
+        caster.conditions = vec![Condition::Silenced];
+        caster.equipment = carry(&[&sword, &other_sword, &cheap_diamond]);
+
+        let violations = spell.check_components(&db, &caster).await.unwrap();
+        assert_eq!(violations.len(), 3);
+        assert!(matches!(
+            violations[0],
+            Violation::CastVerbalComponentBlocked {
+                condition: Condition::Silenced,
+                ..
+            }
+        ));
+        assert!(matches!(
+            violations[1],
+            Violation::CastSomaticComponentBlocked {
+                occupied_hands: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            violations[2],
+            Violation::CastMaterialComponentMissing { cost: 30000, .. }
+        ));
+
+        caster.conditions = vec![];
+        caster.equipment = carry(&[&greatsword, &diamond]);
+
+        let violations = spell.check_components(&db, &caster).await.unwrap();
+        assert!(matches!(
+            violations[..],
+            [Violation::CastSomaticComponentBlocked {
+                occupied_hands: 2,
+                ..
+            }]
+        ));
+
+        caster.equipment = carry(&[&sword, &diamond]);
+
+        let violations = spell.check_components(&db, &caster).await.unwrap();
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn cantrip_damage_scales_with_character_level() {
+        let spell = Spell::fetch_spell_by_name("Fire Bolt").unwrap();
+
+        assert_eq!(spell.damage(1, None).unwrap().amount, 1);
+        assert_eq!(spell.damage(5, None).unwrap().amount, 2);
+        assert_eq!(spell.damage(11, None).unwrap().amount, 3);
+        assert_eq!(spell.damage(20, None).unwrap().amount, 4);
+        assert_eq!(spell.target_count(20, None), 1);
+    }
+
+    #[test]
+    fn cantrip_beams_scale_with_character_level() {
+        let spell = Spell::fetch_spell_by_name("Eldritch Blast").unwrap();
+
+        assert_eq!(spell.target_count(4, None), 1);
+        assert_eq!(spell.target_count(5, None), 2);
+        assert_eq!(spell.target_count(16, None), 3);
+        assert_eq!(spell.target_count(17, None), 4);
+        assert_eq!(spell.damage(17, None).unwrap().amount, 1);
+    }
+
+    #[test]
+    fn upcasting_adds_darts_and_damage() {
+        let magic_missile = Spell::fetch_spell_by_name("Magic Missile").unwrap();
+        let fireball = Spell::fetch_spell_by_name("Fireball").unwrap();
+
+        assert_eq!(magic_missile.target_count(1, Some(1)), 3);
+        assert_eq!(magic_missile.target_count(1, Some(3)), 5);
+        assert_eq!(fireball.damage(5, Some(3)).unwrap().amount, 8);
+        assert_eq!(fireball.damage(9, Some(5)).unwrap().amount, 10);
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character::CharacterId;
+use crate::currency::{CopperPieces, Purse};
+use crate::item::{Item, ItemId};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Trade {
+    Buy {
+        item_id: ItemId,
+        quantity: i32,
+        price: CopperPieces,
+    },
+    Sell {
+        item_id: ItemId,
+        quantity: i32,
+        price: CopperPieces,
+    },
+    // coins given without a target go into the party treasury
+    GiveCoins {
+        coins: Purse,
+        target_character_id: Option<CharacterId>,
+    },
+    TakeCoins {
+        coins: Purse,
+    },
+}
+
+pub fn buy_price(item: &Item, quantity: i32) -> CopperPieces {
+    item.value * quantity
+}
+
+// merchants typically pay half of an item's value
+pub fn sell_price(item: &Item, quantity: i32) -> CopperPieces {
+    item.value * quantity / 2
+}
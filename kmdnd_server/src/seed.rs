@@ -1,16 +1,20 @@
 use chrono::Utc;
 
 use crate::campaign::Campaign;
+use crate::character::class::{Class, ClassLevel, Subclass};
+use crate::character::creation::AbilityScoreGeneration;
 use crate::character::race::{Race, RacialTrait};
 use crate::character::{
-    Character, CharacterOwner, CharacterStats, EquipmentEntry, Language, Position, Proficiencies,
-    ToolType,
+    Character, CharacterAbilities, CharacterOwner, CharacterStats, EquipmentEntry, Language,
+    Position, Proficiencies, SpellSlot, ToolType,
 };
+use crate::currency::Purse;
 use crate::database::Database;
-use crate::encounter::{Encounter, EncounterId, EncounterState};
+use crate::encounter::{Encounter, EncounterId, EncounterState, Faction, Participant};
 use crate::error::Error;
 use crate::item::{
-    Armor, ArmorType, DamageType, Dice, Item, ItemId, ItemType, Range, Weapon, WeaponProperty,
+    Armor, ArmorType, Consumable, ConsumableEffect, DamageType, Dice, DiceRoll, Item, ItemId,
+    ItemType, Range, Weapon, WeaponProperty,
 };
 use crate::operation::{AbilityType, SkillType};
 
@@ -27,6 +31,8 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
     let campaign = Campaign {
         id: campaign_id,
         name: "The Green Bean Bunch".to_string(),
+        variant_encumbrance: false,
+        treasury: Purse::default(),
         created_at: now,
         modified_at: now,
     };
@@ -34,6 +40,7 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
     let items = vec![
         Item {
             id: item1_id,
+            campaign_id: None,
             name: "Club".to_string(),
             value: 10,
             weight: 2,
@@ -42,9 +49,11 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
                 damage_type: DamageType::Bludgeoning,
                 properties: vec![WeaponProperty::Light],
             }),
+            magic: None,
         },
         Item {
             id: item2_id,
+            campaign_id: None,
             name: "Shortbow".to_string(),
             value: 2500,
             weight: 2,
@@ -59,9 +68,11 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
                     WeaponProperty::TwoHanded,
                 ],
             }),
+            magic: None,
         },
         Item {
             id: ItemId::new(),
+            campaign_id: None,
             name: "Scale mail".to_string(),
             value: 5000,
             weight: 45,
@@ -71,9 +82,11 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
                 strength_requirement: None,
                 stealth_disadvantage: true,
             }),
+            magic: None,
         },
         Item {
             id: ItemId::new(),
+            campaign_id: None,
             name: "Studded leather".to_string(),
             value: 4500,
             weight: 13,
@@ -83,6 +96,24 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
                 strength_requirement: None,
                 stealth_disadvantage: false,
             }),
+            magic: None,
+        },
+        Item {
+            id: ItemId::new(),
+            campaign_id: None,
+            name: "Potion of healing".to_string(),
+            value: 5000,
+            weight: 0,
+            item_type: ItemType::Consumable(Consumable {
+                effect: ConsumableEffect::Healing {
+                    healing: DiceRoll {
+                        amount: 2,
+                        dice: Dice::D4,
+                        modifier: 2,
+                    },
+                },
+            }),
+            magic: None,
         },
     ];
 
@@ -96,17 +127,25 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
         name: "Mr. Understanding".to_string(),
         created_at: now,
         modified_at: now,
+        ability_scores: Some(CharacterAbilities::default()),
+        ability_score_generation: AbilityScoreGeneration::Unspecified,
         stats: CharacterStats::default(),
+        purse: Purse {
+            gold: 15,
+            ..Purse::default()
+        },
         equipment: vec![
             EquipmentEntry {
                 equiped: true,
                 quantity: 1,
                 item_id: items[0].id,
+                container_id: None,
             },
             EquipmentEntry {
                 equiped: true,
                 quantity: 1,
                 item_id: items[2].id,
+                container_id: None,
             },
         ],
         position: Some(Position {
@@ -116,7 +155,20 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
         }),
         current_hit_points: 10,
         maximum_hit_points: 10,
-        race: Race::HalfOrc,
+        experience: 900,
+        classes: vec![ClassLevel {
+            class: Class::Barbarian,
+            level: 3,
+            subclass: Some(Subclass::PathOfTheBerserker),
+        }],
+        feature_uses: vec![],
+        attuned_items: vec![],
+        item_charges: vec![],
+        expended_hit_dice: vec![],
+        spell_slots: vec![],
+        race: Some(Race::HalfOrc),
+        subrace: None,
+        background: None,
         proficiencies: Proficiencies {
             armor: vec![ArmorType::Light, ArmorType::Medium, ArmorType::Shield],
             tool: vec![],
@@ -135,6 +187,10 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
             RacialTrait::SavageAttacks,
             RacialTrait::Languages(vec![Language::Common, Language::Orc]),
         ],
+        conditions: vec![],
+        exhaustion: 0,
+        monster: None,
+        ai_controlled: false,
     };
 
     let mut character2 = Character {
@@ -143,17 +199,26 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
         name: "The Chi Bee".to_string(),
         created_at: now,
         modified_at: now,
+        ability_scores: Some(CharacterAbilities::default()),
+        ability_score_generation: AbilityScoreGeneration::Unspecified,
         stats: CharacterStats::default(),
+        purse: Purse {
+            gold: 42,
+            silver: 7,
+            ..Purse::default()
+        },
         equipment: vec![
             EquipmentEntry {
                 equiped: true,
                 quantity: 1,
                 item_id: items[1].id,
+                container_id: None,
             },
             EquipmentEntry {
                 equiped: true,
                 quantity: 1,
                 item_id: items[3].id,
+                container_id: None,
             },
         ],
         position: Some(Position {
@@ -163,7 +228,39 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
         }),
         current_hit_points: 10,
         maximum_hit_points: 10,
-        race: Race::Gnome,
+        experience: 6500,
+        classes: vec![ClassLevel {
+            class: Class::Bard,
+            level: 5,
+            subclass: Some(Subclass::CollegeOfLore),
+        }],
+        feature_uses: vec![],
+        attuned_items: vec![],
+        item_charges: vec![],
+        expended_hit_dice: vec![],
+        spell_slots: vec![
+            SpellSlot {
+                level: 1,
+                total: 4,
+                expended: 0,
+                pact_magic: false,
+            },
+            SpellSlot {
+                level: 2,
+                total: 3,
+                expended: 0,
+                pact_magic: false,
+            },
+            SpellSlot {
+                level: 3,
+                total: 2,
+                expended: 0,
+                pact_magic: false,
+            },
+        ],
+        race: Some(Race::Gnome),
+        subrace: None,
+        background: None,
         proficiencies: Proficiencies {
             armor: vec![ArmorType::Light],
             tool: vec![ToolType::Lute, ToolType::Shawm, ToolType::PanFlute],
@@ -179,8 +276,14 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
             RacialTrait::GnomeCunning,
             RacialTrait::Languages(vec![Language::Common, Language::Gnomish]),
         ],
+        conditions: vec![],
+        exhaustion: 0,
+        monster: None,
+        ai_controlled: false,
     };
 
+    db.campaigns().insert_campaign(&campaign).await?;
+
     character1.recalculate_stats(db).await?;
     character2.recalculate_stats(db).await?;
 
@@ -188,12 +291,21 @@ pub async fn seed(db: &dyn Database) -> Result<(), Error> {
         id: EncounterId::new(),
         campaign_id: campaign.id,
         character_ids: vec![character1_id, character2_id],
+        factions: vec![
+            Participant {
+                character_id: character1_id,
+                faction: Faction::Party,
+            },
+            Participant {
+                character_id: character2_id,
+                faction: Faction::Party,
+            },
+        ],
         created_at: now,
         modified_at: now,
         state: EncounterState::Initiative,
     };
 
-    db.campaigns().insert_campaign(&campaign).await?;
     db.characters().insert_character(&character1).await?;
     db.characters().insert_character(&character2).await?;
     db.encounters().insert_encounter(&encounter).await?;
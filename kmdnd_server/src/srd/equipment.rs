@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::character::ToolType;
+use crate::database::Database;
+use crate::error::Error;
+use crate::item::manager;
+use crate::item::{
+    Armor, ArmorType, Consumable, ConsumableEffect, Container, Dice, DiceRoll, ItemFilter,
+    ItemType, Range, Tool, Weapon, WeaponProperty,
+};
+
+use super::{parse_damage_type, parse_dice_roll, ImportReport, SrdCost, SrdReference};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SrdEquipment {
+    name: String,
+    equipment_category: SrdReference,
+    armor_category: Option<String>,
+    armor_class: Option<SrdArmorClass>,
+    str_minimum: Option<i32>,
+    #[serde(default)]
+    stealth_disadvantage: bool,
+    damage: Option<SrdDamage>,
+    two_handed_damage: Option<SrdDamage>,
+    range: Option<SrdRange>,
+    throw_range: Option<SrdRange>,
+    #[serde(default)]
+    properties: Vec<SrdReference>,
+    cost: SrdCost,
+    #[serde(default)]
+    weight: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdArmorClass {
+    base: i32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdDamage {
+    damage_dice: String,
+    damage_type: SrdReference,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdRange {
+    normal: i32,
+    long: Option<i32>,
+}
+
+// the dataset doesn't include capacities, these are from the equipment chapter
+const CONTAINER_CAPACITIES: [(&str, i32); 5] = [
+    ("Backpack", 30),
+    ("Basket", 40),
+    ("Chest", 300),
+    ("Pouch", 6),
+    ("Sack", 30),
+];
+
+const TOOL_TYPES: [(&str, ToolType); 34] = [
+    ("Alchemist's Supplies", ToolType::AlchemistsSupplies),
+    ("Brewer's Supplies", ToolType::BrewersSupplies),
+    ("Calligrapher's Supplies", ToolType::CalligrapherSupplies),
+    ("Carpenter's Tools", ToolType::CarpentersTools),
+    ("Cartographer's Tools", ToolType::CartographersTools),
+    ("Cobbler's Tools", ToolType::CobblersTools),
+    ("Cook's utensils", ToolType::CooksUtensils),
+    ("Glassblower's Tools", ToolType::GlassblowersTools),
+    ("Jeweler's Tools", ToolType::JewelersTools),
+    ("Leatherworker's Tools", ToolType::LeatherworkersTools),
+    ("Mason's Tools", ToolType::MasonsTools),
+    ("Painter's Supplies", ToolType::PainterTupplies),
+    ("Potter's Tools", ToolType::PottersTools),
+    ("Smith's Tools", ToolType::SmithsTools),
+    ("Tinker's Tools", ToolType::TinkersTools),
+    ("Weaver's Tools", ToolType::WeaversTools),
+    ("Woodcarver's Tools", ToolType::WoodcarversTools),
+    ("Disguise Kit", ToolType::DisguiseKit),
+    ("Forgery Kit", ToolType::ForgeryKit),
+    ("Dice Set", ToolType::DiceSet),
+    ("Playing Card Set", ToolType::PlayingCardSet),
+    ("Herbalism Kit", ToolType::HerbalismKit),
+    ("Bagpipes", ToolType::Bagpipes),
+    ("Drum", ToolType::Drum),
+    ("Dulcimer", ToolType::Dulcimer),
+    ("Flute", ToolType::Flute),
+    ("Lute", ToolType::Lute),
+    ("Lyre", ToolType::Lyre),
+    ("Horn", ToolType::Horn),
+    ("Pan flute", ToolType::PanFlute),
+    ("Shawm", ToolType::Shawm),
+    ("Viol", ToolType::Viol),
+    ("Navigator's Tools", ToolType::NavigatorTools),
+    ("Poisoner's Kit", ToolType::PoisonerKit),
+];
+
+// thieves' tools are listed with the adventuring gear rather than the tools
+const THIEVES_TOOLS: &str = "Thieves' Tools";
+
+// items are matched to the global catalog by name so the import can be rerun
+pub async fn import(
+    db: &dyn Database,
+    entries: Vec<SrdEquipment>,
+    report: &mut ImportReport,
+) -> Result<(), Error> {
+    let mut existing: HashMap<String, _> = db
+        .items()
+        .fetch_items_by_filter(None, ItemFilter::default())
+        .await?
+        .into_iter()
+        .map(|item| (item.name.clone(), item))
+        .collect();
+
+    for entry in entries {
+        let item_type = match map_item_type(&entry) {
+            Ok(item_type) => item_type,
+            Err(reason) => {
+                report.unmapped("item", &entry.name, reason);
+                continue;
+            }
+        };
+        let value = match entry.cost.copper_pieces() {
+            Ok(value) => value,
+            Err(reason) => {
+                report.unmapped("item", &entry.name, reason);
+                continue;
+            }
+        };
+        let weight = entry.weight.round() as i32;
+
+        let result = match existing.remove(&entry.name) {
+            Some(item) => {
+                manager::update_item(db, item, entry.name.clone(), weight, value, item_type, None)
+                    .await
+            }
+            None => {
+                manager::create_item(db, None, entry.name.clone(), weight, value, item_type, None)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(_) => report.items_imported += 1,
+            Err(Error::InvalidItem { reason }) => report.unmapped("item", &entry.name, reason),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+fn map_item_type(entry: &SrdEquipment) -> Result<ItemType, String> {
+    let item_type = match entry.equipment_category.index.as_str() {
+        "weapon" => ItemType::Weapon(map_weapon(entry)?),
+        "armor" => ItemType::Armor(map_armor(entry)?),
+        "tools" => ItemType::Tool(Tool {
+            tool_type: map_tool_type(&entry.name)?,
+        }),
+        "adventuring-gear" => map_gear(entry)?,
+        "equipment-packs" => {
+            return Err("equipment packs are bundles of other items".to_string());
+        }
+        category => return Err(format!("no item type for category {}", category)),
+    };
+
+    Ok(item_type)
+}
+
+fn map_weapon(entry: &SrdEquipment) -> Result<Weapon, String> {
+    let damage = entry
+        .damage
+        .as_ref()
+        .ok_or_else(|| "weapon has no damage".to_string())?;
+
+    let mut properties = vec![];
+    for property in &entry.properties {
+        let property = match property.index.as_str() {
+            "ammunition" => WeaponProperty::Ammunition(map_range(entry.range.as_ref())?),
+            "finesse" => WeaponProperty::Finesse,
+            "heavy" => WeaponProperty::Heavy,
+            "light" => WeaponProperty::Light,
+            "loading" => WeaponProperty::Loading,
+            "reach" => WeaponProperty::Reach,
+            "special" => WeaponProperty::Special,
+            "thrown" => WeaponProperty::Thrown(map_range(entry.throw_range.as_ref())?),
+            "two-handed" => WeaponProperty::TwoHanded,
+            "versatile" => {
+                let two_handed_damage = entry
+                    .two_handed_damage
+                    .as_ref()
+                    .ok_or_else(|| "versatile weapon has no two-handed damage".to_string())?;
+                WeaponProperty::Versatile {
+                    two_handed_damage: parse_single_die(&two_handed_damage.damage_dice)?,
+                }
+            }
+            // marks monk weapons, which is a class concern rather than the item's
+            "monk" => continue,
+            index => return Err(format!("unknown weapon property {}", index)),
+        };
+        properties.push(property);
+    }
+
+    let weapon = Weapon {
+        damage_amount: parse_single_die(&damage.damage_dice)?,
+        damage_type: parse_damage_type(&damage.damage_type)?,
+        properties,
+    };
+    weapon.validate()?;
+
+    Ok(weapon)
+}
+
+// weapon damage is a single die with no modifier
+fn parse_single_die(text: &str) -> Result<Dice, String> {
+    match parse_dice_roll(text)? {
+        DiceRoll {
+            amount: 1,
+            dice,
+            modifier: 0,
+        } => Ok(dice),
+        _ => Err(format!("damage {} is not a single die", text)),
+    }
+}
+
+fn map_range(range: Option<&SrdRange>) -> Result<Range, String> {
+    let range = range.ok_or_else(|| "weapon has no range".to_string())?;
+
+    Ok(Range {
+        normal: range.normal,
+        long: range.long.unwrap_or(range.normal),
+    })
+}
+
+fn map_armor(entry: &SrdEquipment) -> Result<Armor, String> {
+    let base = entry
+        .armor_class
+        .as_ref()
+        .ok_or_else(|| "armor has no armor class".to_string())?
+        .base;
+
+    // armor class is stored as the bonus over an unarmored 10, and shields
+    // already add their 2 on top of the armor worn
+    let (armor_type, base_armor_class) = match entry.armor_category.as_deref() {
+        Some("Light") => (ArmorType::Light, base - 10),
+        Some("Medium") => (ArmorType::Medium, base - 10),
+        Some("Heavy") => (ArmorType::Heavy, base - 10),
+        Some("Shield") => (ArmorType::Shield, base - 2),
+        category => return Err(format!("unknown armor category {:?}", category)),
+    };
+
+    let armor = Armor {
+        base_armor_class,
+        armor_type,
+        strength_requirement: entry.str_minimum.filter(|strength| *strength > 0),
+        stealth_disadvantage: entry.stealth_disadvantage,
+    };
+    armor.validate()?;
+
+    Ok(armor)
+}
+
+fn map_tool_type(name: &str) -> Result<ToolType, String> {
+    TOOL_TYPES
+        .iter()
+        .chain(&[(THIEVES_TOOLS, ToolType::TheivesTools)])
+        .find(|(tool_name, _)| tool_name.eq_ignore_ascii_case(name))
+        .map(|(_, tool_type)| *tool_type)
+        .ok_or_else(|| format!("unknown tool {}", name))
+}
+
+fn map_gear(entry: &SrdEquipment) -> Result<ItemType, String> {
+    if entry.name.eq_ignore_ascii_case(THIEVES_TOOLS) {
+        return Ok(ItemType::Tool(Tool {
+            tool_type: ToolType::TheivesTools,
+        }));
+    }
+
+    if let Some((_, capacity)) = CONTAINER_CAPACITIES
+        .iter()
+        .find(|(name, _)| *name == entry.name)
+    {
+        return Ok(ItemType::Container(Container {
+            capacity: *capacity,
+            weightless_contents: false,
+        }));
+    }
+
+    let effect = if entry.name.eq_ignore_ascii_case("Potion of healing") {
+        ConsumableEffect::Healing {
+            healing: DiceRoll {
+                amount: 2,
+                dice: Dice::D4,
+                modifier: 2,
+            },
+        }
+    } else if entry.name.starts_with("Rations") {
+        ConsumableEffect::Mundane
+    } else {
+        return Ok(ItemType::Gear);
+    };
+
+    Ok(ItemType::Consumable(Consumable { effect }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(json: &str) -> SrdEquipment {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn weapons_are_mapped_with_their_properties() {
+        let longsword = entry(
+            r#"{
+                "name": "Longsword",
+                "equipment_category": { "index": "weapon", "name": "Weapon" },
+                "damage": {
+                    "damage_dice": "1d8",
+                    "damage_type": { "index": "slashing", "name": "Slashing" }
+                },
+                "two_handed_damage": {
+                    "damage_dice": "1d10",
+                    "damage_type": { "index": "slashing", "name": "Slashing" }
+                },
+                "properties": [{ "index": "versatile", "name": "Versatile" }],
+                "cost": { "quantity": 15, "unit": "gp" },
+                "weight": 3
+            }"#,
+        );
+
+        let weapon = map_weapon(&longsword).unwrap();
+        assert_eq!(weapon.damage_amount, Dice::D8);
+        assert!(matches!(
+            weapon.properties[..],
+            [WeaponProperty::Versatile {
+                two_handed_damage: Dice::D10
+            }]
+        ));
+
+        let greatsword = entry(
+            r#"{
+                "name": "Greatsword",
+                "equipment_category": { "index": "weapon", "name": "Weapon" },
+                "damage": {
+                    "damage_dice": "2d6",
+                    "damage_type": { "index": "slashing", "name": "Slashing" }
+                },
+                "cost": { "quantity": 50, "unit": "gp" },
+                "weight": 6
+            }"#,
+        );
+
+        assert!(map_weapon(&greatsword).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::currency::{Coin, CopperPieces};
+use crate::database::Database;
+use crate::error::Error;
+use crate::item::{DamageType, Dice, DiceRoll};
+use crate::operation::AbilityType;
+
+mod equipment;
+mod monsters;
+mod spells;
+
+const EQUIPMENT_FILE: &str = "5e-SRD-Equipment.json";
+const SPELLS_FILE: &str = "5e-SRD-Spells.json";
+const MONSTERS_FILE: &str = "5e-SRD-Monsters.json";
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub items_imported: usize,
+    pub spells_imported: usize,
+    pub monsters_imported: usize,
+    pub unmapped: Vec<UnmappedEntry>,
+}
+
+impl ImportReport {
+    fn unmapped(&mut self, kind: &'static str, name: &str, reason: String) {
+        self.unmapped.push(UnmappedEntry {
+            kind,
+            name: name.to_string(),
+            reason,
+        });
+    }
+}
+
+// an entry from the dataset that couldn't be represented and was skipped
+#[derive(Debug)]
+pub struct UnmappedEntry {
+    pub kind: &'static str,
+    pub name: String,
+    pub reason: String,
+}
+
+// imports whichever of the SRD files are found in the directory, entries that
+// can't be mapped are reported rather than failing the whole import
+#[tracing::instrument(skip(db))]
+pub async fn import(db: &dyn Database, directory: &Path) -> Result<ImportReport, Error> {
+    let mut report = ImportReport::default();
+
+    let entries = read_entries(directory, EQUIPMENT_FILE, "item", &mut report)?;
+    equipment::import(db, entries, &mut report).await?;
+
+    let entries = read_entries(directory, SPELLS_FILE, "spell", &mut report)?;
+    spells::import(db, entries, &mut report).await?;
+
+    let entries = read_entries(directory, MONSTERS_FILE, "monster", &mut report)?;
+    monsters::import(db, entries, &mut report).await?;
+
+    Ok(report)
+}
+
+// a missing file is skipped, and entries that don't match the expected shape
+// are reported individually
+fn read_entries<T: DeserializeOwned>(
+    directory: &Path,
+    file: &str,
+    kind: &'static str,
+    report: &mut ImportReport,
+) -> Result<Vec<T>, Error> {
+    let path = directory.join(file);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let values: Vec<Value> =
+        serde_json::from_str(&contents).map_err(|err| Error::ImportFileInvalid {
+            file: path.display().to_string(),
+            reason: err.to_string(),
+        })?;
+
+    let mut entries = vec![];
+    for value in values {
+        let name = value["name"].as_str().unwrap_or("<unnamed>").to_string();
+        match serde_json::from_value(value) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => report.unmapped(kind, &name, err.to_string()),
+        }
+    }
+
+    Ok(entries)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdReference {
+    #[serde(default)]
+    index: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdCost {
+    quantity: i32,
+    unit: String,
+}
+
+impl SrdCost {
+    fn copper_pieces(&self) -> Result<CopperPieces, String> {
+        let coin = match self.unit.as_str() {
+            "cp" => Coin::Copper,
+            "sp" => Coin::Silver,
+            "ep" => Coin::Electrum,
+            "gp" => Coin::Gold,
+            "pp" => Coin::Platinum,
+            unit => return Err(format!("unknown currency {}", unit)),
+        };
+
+        Ok(self.quantity * coin.value())
+    }
+}
+
+// parses damage like "2d6" or "1d4 + 1"
+fn parse_dice_roll(text: &str) -> Result<DiceRoll, String> {
+    let invalid = || format!("damage {} is not a dice roll", text);
+
+    let (roll, modifier) = match text.split_once('+') {
+        Some((roll, modifier)) => (
+            roll.trim(),
+            modifier.trim().parse::<i32>().map_err(|_| invalid())?,
+        ),
+        None => (text.trim(), 0),
+    };
+
+    let (amount, sides) = roll.split_once('d').ok_or_else(invalid)?;
+    let amount = amount.parse::<i32>().map_err(|_| invalid())?;
+    let dice = match sides {
+        "4" => Dice::D4,
+        "6" => Dice::D6,
+        "8" => Dice::D8,
+        "10" => Dice::D10,
+        "12" => Dice::D12,
+        "20" => Dice::D20,
+        _ => return Err(invalid()),
+    };
+
+    Ok(DiceRoll {
+        amount,
+        dice,
+        modifier,
+    })
+}
+
+fn parse_damage_type(damage_type: &SrdReference) -> Result<DamageType, String> {
+    let damage_type = match damage_type.index.as_str() {
+        "acid" => DamageType::Acid,
+        "bludgeoning" => DamageType::Bludgeoning,
+        "cold" => DamageType::Cold,
+        "fire" => DamageType::Fire,
+        "force" => DamageType::Force,
+        "lightning" => DamageType::Lightning,
+        "necrotic" => DamageType::Necrotic,
+        "piercing" => DamageType::Piercing,
+        "poison" => DamageType::Poison,
+        "psychic" => DamageType::Psychic,
+        "radiant" => DamageType::Radiant,
+        "slashing" => DamageType::Slashing,
+        "thunder" => DamageType::Thunder,
+        index => return Err(format!("unknown damage type {}", index)),
+    };
+
+    Ok(damage_type)
+}
+
+// abilities are abbreviated like "dex"
+fn parse_ability(ability: &str) -> Result<AbilityType, String> {
+    let ability = match ability {
+        "str" => AbilityType::Strength,
+        "dex" => AbilityType::Dexterity,
+        "con" => AbilityType::Constitution,
+        "int" => AbilityType::Intelligence,
+        "wis" => AbilityType::Wisdom,
+        "cha" => AbilityType::Charisma,
+        ability => return Err(format!("unknown ability {}", ability)),
+    };
+
+    Ok(ability)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dice_rolls_are_parsed_from_damage_strings() {
+        let roll = parse_dice_roll("2d6").unwrap();
+        assert_eq!((roll.amount, roll.dice, roll.modifier), (2, Dice::D6, 0));
+
+        let roll = parse_dice_roll("1d4 + 1").unwrap();
+        assert_eq!((roll.amount, roll.dice, roll.modifier), (1, Dice::D4, 1));
+
+        assert!(parse_dice_roll("1").is_err());
+        assert!(parse_dice_roll("1d6 + MOD").is_err());
+        assert!(parse_dice_roll("1d7").is_err());
+    }
+}
@@ -0,0 +1,562 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::character::race::Size;
+use crate::character::{CharacterAbilities, Condition};
+use crate::database::Database;
+use crate::error::Error;
+use crate::item::{DamageType, Range};
+use crate::monster::manager;
+use crate::monster::{
+    ChallengeRating, CreatureType, LegendaryAction, LegendaryActions, MonsterAttack, Multiattack,
+    StatBlock,
+};
+use crate::operation::SkillType;
+
+use super::{parse_ability, parse_damage_type, parse_dice_roll, ImportReport, SrdReference};
+
+// the dataset only describes this in the text, but every legendary creature in
+// the SRD can take three
+const LEGENDARY_ACTIONS_PER_ROUND: i32 = 3;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SrdMonster {
+    name: String,
+    size: String,
+    #[serde(rename = "type")]
+    creature_type: String,
+    // a plain number in older versions of the dataset
+    armor_class: Value,
+    hit_points: i32,
+    hit_dice: String,
+    hit_points_roll: Option<String>,
+    #[serde(default)]
+    speed: HashMap<String, Value>,
+    strength: i32,
+    dexterity: i32,
+    constitution: i32,
+    intelligence: i32,
+    wisdom: i32,
+    charisma: i32,
+    #[serde(default)]
+    proficiencies: Vec<SrdProficiency>,
+    #[serde(default)]
+    damage_vulnerabilities: Vec<String>,
+    #[serde(default)]
+    damage_resistances: Vec<String>,
+    #[serde(default)]
+    damage_immunities: Vec<String>,
+    #[serde(default)]
+    condition_immunities: Vec<SrdReference>,
+    challenge_rating: f32,
+    #[serde(default)]
+    actions: Vec<SrdMonsterAction>,
+    #[serde(default)]
+    legendary_actions: Vec<SrdMonsterAction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdProficiency {
+    proficiency: SrdReference,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdMonsterAction {
+    name: String,
+    #[serde(default)]
+    desc: String,
+    attack_bonus: Option<i32>,
+    #[serde(default)]
+    damage: Vec<Value>,
+    // the attacks made as part of a multiattack
+    #[serde(default)]
+    actions: Vec<SrdMultiattackAction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdMultiattackAction {
+    action_name: String,
+    // a string in some entries
+    count: Value,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdDamage {
+    damage_dice: String,
+    damage_type: SrdReference,
+}
+
+// monsters are matched to the global catalog by name so the import can be rerun
+pub async fn import(
+    db: &dyn Database,
+    entries: Vec<SrdMonster>,
+    report: &mut ImportReport,
+) -> Result<(), Error> {
+    let mut existing: HashMap<String, _> = manager::get_monsters(db, None)
+        .await?
+        .into_iter()
+        .map(|monster| (monster.name.clone(), monster))
+        .collect();
+
+    for entry in entries {
+        let stat_block = match map_stat_block(&entry, report) {
+            Ok(stat_block) => stat_block,
+            Err(reason) => {
+                report.unmapped("monster", &entry.name, reason);
+                continue;
+            }
+        };
+
+        let result = match existing.remove(&entry.name) {
+            Some(monster) => {
+                manager::update_monster(db, monster, entry.name.clone(), stat_block).await
+            }
+            None => manager::create_monster(db, None, entry.name.clone(), stat_block).await,
+        };
+
+        match result {
+            Ok(_) => report.monsters_imported += 1,
+            Err(Error::InvalidMonster { reason }) => {
+                report.unmapped("monster", &entry.name, reason)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+// entries that can only be imported in part are reported, but the rest of the
+// stat block is still imported
+fn map_stat_block(entry: &SrdMonster, report: &mut ImportReport) -> Result<StatBlock, String> {
+    let mut saving_throws = vec![];
+    let mut skills = vec![];
+    for proficiency in &entry.proficiencies {
+        let index = proficiency.proficiency.index.as_str();
+        if let Some(ability) = index.strip_prefix("saving-throw-") {
+            saving_throws.push(parse_ability(ability)?);
+        } else if let Some(skill) = index.strip_prefix("skill-") {
+            skills.push(map_skill(skill)?);
+        }
+    }
+
+    // exhaustion is tracked as a level rather than a condition
+    let condition_immunities = entry
+        .condition_immunities
+        .iter()
+        .filter(|condition| condition.index != "exhaustion")
+        .map(map_condition)
+        .collect::<Result<_, _>>()?;
+
+    let attacks: Vec<MonsterAttack> = entry.actions.iter().filter_map(map_attack).collect();
+
+    Ok(StatBlock {
+        size: map_size(&entry.size)?,
+        creature_type: map_creature_type(&entry.creature_type)?,
+        armor_class: map_armor_class(&entry.armor_class)?,
+        hit_dice: map_hit_dice(entry)?,
+        speed: map_speed(&entry.speed)?,
+        ability_scores: CharacterAbilities {
+            strength: entry.strength,
+            dexterity: entry.dexterity,
+            constitution: entry.constitution,
+            intelligence: entry.intelligence,
+            wisdom: entry.wisdom,
+            charisma: entry.charisma,
+        },
+        saving_throws,
+        skills,
+        damage_vulnerabilities: map_damage_types(
+            &entry.name,
+            &entry.damage_vulnerabilities,
+            report,
+        ),
+        damage_resistances: map_damage_types(&entry.name, &entry.damage_resistances, report),
+        damage_immunities: map_damage_types(&entry.name, &entry.damage_immunities, report),
+        condition_immunities,
+        challenge_rating: ChallengeRating(entry.challenge_rating),
+        multiattack: map_multiattack(entry, &attacks),
+        legendary_actions: map_legendary_actions(entry, &attacks),
+        attacks,
+    })
+}
+
+fn map_size(size: &str) -> Result<Size, String> {
+    let size = match size {
+        "Tiny" => Size::Tiny,
+        "Small" => Size::Small,
+        "Medium" => Size::Medium,
+        "Large" => Size::Large,
+        "Huge" => Size::Huge,
+        "Gargantuan" => Size::Gargantuan,
+        size => return Err(format!("unknown size {}", size)),
+    };
+
+    Ok(size)
+}
+
+// swarms are described like "swarm of Tiny beasts"
+fn map_creature_type(creature_type: &str) -> Result<CreatureType, String> {
+    const CREATURE_TYPES: [(&str, CreatureType); 14] = [
+        ("aberration", CreatureType::Aberration),
+        ("beast", CreatureType::Beast),
+        ("celestial", CreatureType::Celestial),
+        ("construct", CreatureType::Construct),
+        ("dragon", CreatureType::Dragon),
+        ("elemental", CreatureType::Elemental),
+        ("fey", CreatureType::Fey),
+        ("fiend", CreatureType::Fiend),
+        ("giant", CreatureType::Giant),
+        ("humanoid", CreatureType::Humanoid),
+        ("monstrosity", CreatureType::Monstrosity),
+        ("ooze", CreatureType::Ooze),
+        ("plant", CreatureType::Plant),
+        ("undead", CreatureType::Undead),
+    ];
+
+    let lowercase = creature_type.to_lowercase();
+    CREATURE_TYPES
+        .iter()
+        .find(|(name, _)| lowercase.contains(name))
+        .map(|(_, creature_type)| *creature_type)
+        .ok_or_else(|| format!("unknown creature type {}", creature_type))
+}
+
+fn map_armor_class(armor_class: &Value) -> Result<i32, String> {
+    let value = match armor_class {
+        Value::Array(values) => values.first().and_then(|value| value["value"].as_i64()),
+        value => value.as_i64(),
+    };
+
+    value
+        .map(|value| value as i32)
+        .ok_or_else(|| "monster has no armor class".to_string())
+}
+
+// the roll includes the constitution bonus, which older versions of the
+// dataset only reflect in the average
+fn map_hit_dice(entry: &SrdMonster) -> Result<crate::item::DiceRoll, String> {
+    if let Some(roll) = &entry.hit_points_roll {
+        return parse_dice_roll(roll);
+    }
+
+    let mut roll = parse_dice_roll(&entry.hit_dice)?;
+    roll.modifier = entry.hit_points - roll.amount * (roll.dice.sides() + 1) / 2;
+
+    Ok(roll)
+}
+
+// only walking speed is tracked, given like "30 ft."
+fn map_speed(speed: &HashMap<String, Value>) -> Result<i32, String> {
+    speed
+        .get("walk")
+        .and_then(|walk| walk.as_str())
+        .and_then(|walk| walk.split(' ').next())
+        .and_then(|feet| feet.parse().ok())
+        .ok_or_else(|| "monster has no walking speed".to_string())
+}
+
+// only plain lists of damage types like "acid, fire, and poison" can be
+// represented, qualified ones like "from nonmagical attacks" are reported
+// instead of being widened to apply in full
+fn map_damage_types(
+    name: &str,
+    descriptions: &[String],
+    report: &mut ImportReport,
+) -> Vec<DamageType> {
+    let mut damage_types = vec![];
+    for description in descriptions {
+        let parsed: Result<Vec<DamageType>, String> = description
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|word| !word.is_empty() && *word != "and")
+            .map(|word| {
+                parse_damage_type(&SrdReference {
+                    index: word.to_lowercase(),
+                })
+            })
+            .collect();
+
+        match parsed {
+            Ok(parsed) => {
+                for damage_type in parsed {
+                    if !damage_types.contains(&damage_type) {
+                        damage_types.push(damage_type);
+                    }
+                }
+            }
+            Err(_) => report.unmapped(
+                "monster",
+                name,
+                format!("qualified damage types \"{}\"", description),
+            ),
+        }
+    }
+
+    damage_types
+}
+
+fn map_condition(condition: &SrdReference) -> Result<Condition, String> {
+    let condition = match condition.index.as_str() {
+        "blinded" => Condition::Blinded,
+        "charmed" => Condition::Charmed,
+        "deafened" => Condition::Deafened,
+        "frightened" => Condition::Frightened,
+        "grappled" => Condition::Grappled,
+        "incapacitated" => Condition::Incapacitated,
+        "invisible" => Condition::Invisible,
+        "paralyzed" => Condition::Paralyzed,
+        "petrified" => Condition::Petrified,
+        "poisoned" => Condition::Poisoned,
+        "prone" => Condition::Prone,
+        "restrained" => Condition::Restrained,
+        "stunned" => Condition::Stunned,
+        "unconscious" => Condition::Unconscious,
+        index => return Err(format!("unknown condition {}", index)),
+    };
+
+    Ok(condition)
+}
+
+fn map_skill(skill: &str) -> Result<SkillType, String> {
+    let skill = match skill {
+        "acrobatics" => SkillType::Acrobatics,
+        "animal-handling" => SkillType::AnimalHandling,
+        "arcana" => SkillType::Arcana,
+        "athletics" => SkillType::Athletics,
+        "deception" => SkillType::Deception,
+        "history" => SkillType::History,
+        "insight" => SkillType::Insight,
+        "intimidation" => SkillType::Intimidation,
+        "investigation" => SkillType::Investigation,
+        "medicine" => SkillType::Medicine,
+        "nature" => SkillType::Nature,
+        "perception" => SkillType::Perception,
+        "performance" => SkillType::Performance,
+        "persuasion" => SkillType::Persuasion,
+        "religion" => SkillType::Religion,
+        "sleight-of-hand" => SkillType::SleightOfHand,
+        "stealth" => SkillType::Stealth,
+        "survival" => SkillType::Survival,
+        skill => return Err(format!("unknown skill {}", skill)),
+    };
+
+    Ok(skill)
+}
+
+// actions without an attack roll, like breath weapons, aren't attacks and are
+// left to the description
+fn map_attack(action: &SrdMonsterAction) -> Option<MonsterAttack> {
+    let attack_bonus = action.attack_bonus?;
+    let damage = action
+        .damage
+        .iter()
+        .find_map(|damage| serde_json::from_value::<SrdDamage>(damage.clone()).ok())?;
+
+    let range = if action.desc.starts_with("Ranged") {
+        parse_feet_after(&action.desc, "range ").map(|(normal, long)| Range {
+            normal,
+            long: long.unwrap_or(normal),
+        })
+    } else {
+        None
+    };
+    let reach = parse_feet_after(&action.desc, "reach ")
+        .map(|(reach, _)| reach as f32)
+        .unwrap_or(5.0);
+
+    Some(MonsterAttack {
+        name: action.name.clone(),
+        attack_bonus,
+        reach,
+        range,
+        damage: parse_dice_roll(&damage.damage_dice).ok()?,
+        damage_type: parse_damage_type(&damage.damage_type).ok()?,
+    })
+}
+
+// parses distances like "reach 10 ft." or "range 80/320 ft."
+fn parse_feet_after(text: &str, prefix: &str) -> Option<(i32, Option<i32>)> {
+    let (_, rest) = text.split_once(prefix)?;
+    let distance = rest.split(' ').next()?;
+
+    match distance.split_once('/') {
+        Some((normal, long)) => Some((normal.parse().ok()?, Some(long.parse().ok()?))),
+        None => Some((distance.parse().ok()?, None)),
+    }
+}
+
+fn map_multiattack(entry: &SrdMonster, attacks: &[MonsterAttack]) -> Option<Multiattack> {
+    let multiattack = entry
+        .actions
+        .iter()
+        .find(|action| action.name == "Multiattack")?;
+
+    let attack_count: i32 = multiattack
+        .actions
+        .iter()
+        .filter(|action| {
+            attacks
+                .iter()
+                .any(|attack| attack.name == action.action_name)
+        })
+        .filter_map(|action| match &action.count {
+            Value::Number(count) => count.as_i64().map(|count| count as i32),
+            Value::String(count) => count.parse().ok(),
+            _ => None,
+        })
+        .sum();
+
+    if attack_count < 2 {
+        return None;
+    }
+
+    Some(Multiattack {
+        attacks: attack_count,
+    })
+}
+
+fn map_legendary_actions(
+    entry: &SrdMonster,
+    attacks: &[MonsterAttack],
+) -> Option<LegendaryActions> {
+    if entry.legendary_actions.is_empty() {
+        return None;
+    }
+
+    let actions = entry
+        .legendary_actions
+        .iter()
+        .map(|action| {
+            // costs are part of the name, like "Wing Attack (Costs 2 Actions)"
+            let (name, cost) = match action.name.split_once(" (Costs ") {
+                Some((name, cost)) => (
+                    name.to_string(),
+                    cost.split(' ').next().and_then(|cost| cost.parse().ok()),
+                ),
+                None => (action.name.clone(), None),
+            };
+
+            let description = action.desc.to_lowercase();
+            let attack = attacks
+                .iter()
+                .find(|attack| {
+                    description.contains(&format!("{} attack", attack.name.to_lowercase()))
+                })
+                .map(|attack| attack.name.clone());
+
+            LegendaryAction {
+                name,
+                cost: cost.unwrap_or(1),
+                attack,
+            }
+        })
+        .collect();
+
+    Some(LegendaryActions {
+        per_round: LEGENDARY_ACTIONS_PER_ROUND,
+        actions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Dice;
+
+    #[test]
+    fn monsters_are_mapped_with_attacks_and_multiattack() {
+        let bugbear: SrdMonster = serde_json::from_str(
+            r#"{
+                "name": "Bugbear",
+                "size": "Medium",
+                "type": "humanoid",
+                "armor_class": [{ "type": "armor", "value": 16 }],
+                "hit_points": 27,
+                "hit_dice": "5d8",
+                "hit_points_roll": "5d8+5",
+                "speed": { "walk": "30 ft." },
+                "strength": 15,
+                "dexterity": 14,
+                "constitution": 13,
+                "intelligence": 8,
+                "wisdom": 11,
+                "charisma": 9,
+                "proficiencies": [
+                    { "value": 6, "proficiency": { "index": "skill-stealth", "name": "Skill: Stealth" } }
+                ],
+                "condition_immunities": [],
+                "challenge_rating": 1,
+                "actions": [
+                    {
+                        "name": "Multiattack",
+                        "desc": "The bugbear makes two attacks.",
+                        "actions": [{ "action_name": "Morningstar", "count": "2", "type": "melee" }]
+                    },
+                    {
+                        "name": "Morningstar",
+                        "desc": "Melee Weapon Attack: +4 to hit, reach 5 ft., one target.",
+                        "attack_bonus": 4,
+                        "damage": [{
+                            "damage_type": { "index": "piercing", "name": "Piercing" },
+                            "damage_dice": "2d8+2"
+                        }]
+                    },
+                    {
+                        "name": "Javelin",
+                        "desc": "Ranged Weapon Attack: +4 to hit, range 30/120 ft., one target.",
+                        "attack_bonus": 4,
+                        "damage": [{
+                            "damage_type": { "index": "piercing", "name": "Piercing" },
+                            "damage_dice": "1d6+2"
+                        }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut report = ImportReport::default();
+        let stat_block = map_stat_block(&bugbear, &mut report).unwrap();
+        assert_eq!(stat_block.armor_class, 16);
+        assert_eq!(stat_block.average_hit_points(), 27);
+        assert_eq!(stat_block.speed, 30);
+        assert_eq!(stat_block.skills, vec![SkillType::Stealth]);
+        assert_eq!(stat_block.multiattack.as_ref().map(|m| m.attacks), Some(2));
+
+        let morningstar = stat_block.attack("Morningstar").unwrap();
+        assert_eq!(morningstar.damage.dice, Dice::D8);
+        assert_eq!(morningstar.damage.modifier, 2);
+        assert!(morningstar.range.is_none());
+
+        let javelin = stat_block.attack("Javelin").unwrap();
+        assert_eq!(javelin.range.map(|range| range.long), Some(120));
+
+        assert!(report.unmapped.is_empty());
+    }
+
+    #[test]
+    fn qualified_damage_types_are_reported_rather_than_widened() {
+        let mut report = ImportReport::default();
+        let damage_types = map_damage_types(
+            "Werewolf",
+            &[
+                "acid, fire, and poison".to_string(),
+                "bludgeoning, piercing, and slashing from nonmagical attacks".to_string(),
+            ],
+            &mut report,
+        );
+
+        assert_eq!(
+            damage_types,
+            vec![DamageType::Acid, DamageType::Fire, DamageType::Poison]
+        );
+        assert_eq!(report.unmapped.len(), 1);
+        assert_eq!(report.unmapped[0].name, "Werewolf");
+
+        let mut speed = HashMap::new();
+        speed.insert("fly".to_string(), Value::String("60 ft.".to_string()));
+        assert!(map_speed(&speed).is_err());
+    }
+}
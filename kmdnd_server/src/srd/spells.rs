@@ -0,0 +1,359 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::database::Database;
+use crate::error::Error;
+use crate::item::DiceRoll;
+use crate::operation::spell::{
+    CastingTime, MagicSchool, Spell, SpellComponent, SpellDuration, SpellEffect, SpellRange,
+    SpellScaling, SpellTargetType,
+};
+
+use super::{
+    parse_ability, parse_damage_type, parse_dice_roll, ImportReport, SrdCost, SrdReference,
+};
+
+const FEET_PER_MILE: f32 = 5280.0;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SrdSpell {
+    name: String,
+    #[serde(default)]
+    desc: Vec<String>,
+    range: String,
+    #[serde(default)]
+    components: Vec<String>,
+    material: Option<String>,
+    #[serde(default)]
+    ritual: bool,
+    duration: String,
+    #[serde(default)]
+    concentration: bool,
+    casting_time: String,
+    level: i32,
+    attack_type: Option<String>,
+    damage: Option<SrdSpellDamage>,
+    dc: Option<SrdDifficultyClass>,
+    area_of_effect: Option<SrdAreaOfEffect>,
+    school: SrdReference,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdSpellDamage {
+    damage_type: Option<SrdReference>,
+    damage_at_slot_level: Option<BTreeMap<i32, String>>,
+    damage_at_character_level: Option<BTreeMap<i32, String>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdDifficultyClass {
+    dc_type: SrdReference,
+    dc_success: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SrdAreaOfEffect {
+    size: f32,
+}
+
+pub async fn import(
+    db: &dyn Database,
+    entries: Vec<SrdSpell>,
+    report: &mut ImportReport,
+) -> Result<(), Error> {
+    for entry in entries {
+        match map_spell(&entry) {
+            Ok(spell) => {
+                db.spells().upsert_spell(&spell).await?;
+                report.spells_imported += 1;
+            }
+            Err(reason) => report.unmapped("spell", &entry.name, reason),
+        }
+    }
+
+    Ok(())
+}
+
+fn map_spell(entry: &SrdSpell) -> Result<Spell, String> {
+    let range = map_range(&entry.range)?;
+    let (effect, scaling) = map_effect(entry)?;
+
+    let target = match (&effect, &entry.area_of_effect) {
+        (SpellEffect::None, _) => SpellTargetType::None,
+        (_, Some(_)) => SpellTargetType::Position,
+        (_, None) => SpellTargetType::Creature,
+    };
+
+    Ok(Spell {
+        name: entry.name.clone(),
+        level: entry.level,
+        school: map_school(&entry.school)?,
+        casting_time: map_casting_time(&entry.casting_time)?,
+        range,
+        target,
+        components: map_components(entry)?,
+        duration: map_duration(&entry.duration)?,
+        concentration: entry.concentration,
+        ritual: entry.ritual,
+        effect,
+        scaling,
+        description: entry.desc.join("\n"),
+    })
+}
+
+fn map_school(school: &SrdReference) -> Result<MagicSchool, String> {
+    let school = match school.index.as_str() {
+        "abjuration" => MagicSchool::Abjuration,
+        "conjuration" => MagicSchool::Conjuration,
+        "divination" => MagicSchool::Divination,
+        "enchantment" => MagicSchool::Enchantment,
+        "evocation" => MagicSchool::Evocation,
+        "illusion" => MagicSchool::Illusion,
+        "necromancy" => MagicSchool::Necromacy,
+        "transmutation" => MagicSchool::Transmutation,
+        index => return Err(format!("unknown school {}", index)),
+    };
+
+    Ok(school)
+}
+
+// splits text like "10 minutes" into the amount and its unit
+fn parse_quantity(text: &str) -> Option<(i32, &str)> {
+    let (amount, unit) = text.trim().split_once(' ')?;
+    let amount = amount.parse().ok()?;
+
+    Some((amount, unit.trim_end_matches('s')))
+}
+
+fn map_casting_time(text: &str) -> Result<CastingTime, String> {
+    let casting_time = match parse_quantity(text) {
+        Some((amount, "action")) => CastingTime::Action(amount),
+        Some((amount, "bonus action")) => CastingTime::BonusAction(amount),
+        // the trigger is only part of the description
+        Some((_, "reaction")) => CastingTime::Reaction(text.to_string()),
+        Some((amount, "minute")) => CastingTime::Minute(amount),
+        Some((amount, "hour")) => CastingTime::Hour(amount),
+        _ => return Err(format!("unknown casting time {}", text)),
+    };
+
+    Ok(casting_time)
+}
+
+fn map_range(text: &str) -> Result<SpellRange, String> {
+    // areas centered on the caster are described as "Self (15-foot cone)"
+    if text == "Self" || text.starts_with("Self (") {
+        return Ok(SpellRange::Personal);
+    }
+
+    let range = match parse_quantity(text) {
+        _ if text == "Touch" => SpellRange::Touch,
+        Some((amount, "feet")) => SpellRange::Feet(amount as f32),
+        Some((amount, "mile")) => SpellRange::Feet(amount as f32 * FEET_PER_MILE),
+        _ => return Err(format!("unknown range {}", text)),
+    };
+
+    Ok(range)
+}
+
+fn map_duration(text: &str) -> Result<SpellDuration, String> {
+    if text == "Instantaneous" {
+        return Ok(SpellDuration::Instantaneous);
+    }
+
+    let duration = match parse_quantity(text.trim_start_matches("Up to ")) {
+        Some((amount, "round")) => SpellDuration::Round(amount),
+        Some((amount, "minute")) => SpellDuration::Minute(amount),
+        Some((amount, "hour")) => SpellDuration::Hour(amount),
+        Some((amount, "day")) => SpellDuration::Day(amount),
+        _ => return Err(format!("unknown duration {}", text)),
+    };
+
+    Ok(duration)
+}
+
+fn map_components(entry: &SrdSpell) -> Result<Vec<SpellComponent>, String> {
+    entry
+        .components
+        .iter()
+        .map(|component| match component.as_str() {
+            "V" => Ok(SpellComponent::Verbal),
+            "S" => Ok(SpellComponent::Somatic),
+            "M" => {
+                let name = entry.material.clone().unwrap_or_default();
+                Ok(SpellComponent::Material {
+                    cost: parse_material_cost(&name)?,
+                    consumed: name.contains("consume"),
+                    name,
+                })
+            }
+            component => Err(format!("unknown component {}", component)),
+        })
+        .collect()
+}
+
+// costly materials are described like "a diamond worth at least 300 gp"
+fn parse_material_cost(text: &str) -> Result<Option<i32>, String> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ')')
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    for (index, word) in words.iter().enumerate() {
+        if !matches!(*word, "cp" | "sp" | "ep" | "gp" | "pp") || index == 0 {
+            continue;
+        }
+        if let Ok(quantity) = words[index - 1].replace(',', "").parse() {
+            let cost = SrdCost {
+                quantity,
+                unit: word.to_string(),
+            };
+            return cost.copper_pieces().map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+fn map_effect(entry: &SrdSpell) -> Result<(SpellEffect, SpellScaling), String> {
+    let damage = match &entry.damage {
+        Some(damage) => damage,
+        None => return Ok((SpellEffect::None, SpellScaling::None)),
+    };
+
+    let damage_type = damage
+        .damage_type
+        .as_ref()
+        .ok_or_else(|| "damage has no damage type".to_string())
+        .and_then(parse_damage_type)?;
+    let (damage, scaling) = map_damage(entry, damage)?;
+
+    let effect = if entry.attack_type.is_some() {
+        SpellEffect::Attack {
+            damage,
+            damage_type,
+            beams: 1,
+        }
+    } else if let Some(dc) = &entry.dc {
+        SpellEffect::Save {
+            ability: parse_ability(&dc.dc_type.index)?,
+            damage,
+            damage_type,
+            half_on_success: dc.dc_success.as_deref() == Some("half"),
+            radius: entry.area_of_effect.as_ref().map(|area| area.size),
+        }
+    } else {
+        return Err("damage is dealt without an attack or a save".to_string());
+    };
+
+    Ok((effect, scaling))
+}
+
+// scaling adds a die per slot level, or a multiple of the dice per cantrip
+// tier, anything else can't be represented
+fn map_damage(
+    entry: &SrdSpell,
+    damage: &SrdSpellDamage,
+) -> Result<(DiceRoll, SpellScaling), String> {
+    if let Some(levels) = &damage.damage_at_slot_level {
+        let base = levels
+            .get(&entry.level)
+            .ok_or_else(|| "damage has no base slot level".to_string())
+            .and_then(|text| parse_dice_roll(text))?;
+
+        let scaling = match levels.get(&(entry.level + 1)) {
+            None => SpellScaling::None,
+            Some(text) => {
+                let next = parse_dice_roll(text)?;
+                if next.amount == base.amount && next.modifier == base.modifier {
+                    SpellScaling::None
+                } else if next.amount == base.amount + 1 && next.modifier == base.modifier {
+                    SpellScaling::SlotDamage
+                } else {
+                    return Err(format!("damage scales to {} in an unsupported way", text));
+                }
+            }
+        };
+
+        return Ok((base, scaling));
+    }
+
+    if let Some(levels) = &damage.damage_at_character_level {
+        let base = levels
+            .get(&1)
+            .ok_or_else(|| "damage has no base character level".to_string())
+            .and_then(|text| parse_dice_roll(text))?;
+
+        for (tier, level) in [(2, 5), (3, 11), (4, 17)] {
+            if let Some(text) = levels.get(&level) {
+                if parse_dice_roll(text)?.amount != base.amount * tier {
+                    return Err(format!("damage scales to {} in an unsupported way", text));
+                }
+            }
+        }
+
+        return Ok((base, SpellScaling::CantripDamage));
+    }
+
+    Err("damage has no dice".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::{DamageType, Dice};
+    use crate::operation::AbilityType;
+
+    #[test]
+    fn save_spells_are_mapped_with_slot_scaling() {
+        let fireball: SrdSpell = serde_json::from_str(
+            r#"{
+                "name": "Fireball",
+                "desc": ["A bright streak flashes from your pointing finger."],
+                "range": "150 feet",
+                "components": ["V", "S", "M"],
+                "material": "A tiny ball of bat guano and sulfur.",
+                "ritual": false,
+                "duration": "Instantaneous",
+                "concentration": false,
+                "casting_time": "1 action",
+                "level": 3,
+                "damage": {
+                    "damage_type": { "index": "fire", "name": "Fire" },
+                    "damage_at_slot_level": { "3": "8d6", "4": "9d6", "5": "10d6" }
+                },
+                "dc": {
+                    "dc_type": { "index": "dex", "name": "DEX" },
+                    "dc_success": "half"
+                },
+                "area_of_effect": { "type": "sphere", "size": 20 },
+                "school": { "index": "evocation", "name": "Evocation" }
+            }"#,
+        )
+        .unwrap();
+
+        let spell = map_spell(&fireball).unwrap();
+        assert!(matches!(spell.range, SpellRange::Feet(range) if range == 150.0));
+        assert!(matches!(spell.target, SpellTargetType::Position));
+        assert!(matches!(spell.scaling, SpellScaling::SlotDamage));
+        assert!(matches!(
+            spell.effect,
+            SpellEffect::Save {
+                ability: AbilityType::Dexterity,
+                damage: DiceRoll {
+                    amount: 8,
+                    dice: Dice::D6,
+                    modifier: 0
+                },
+                damage_type: DamageType::Fire,
+                half_on_success: true,
+                radius: Some(_),
+            }
+        ));
+
+        assert_eq!(
+            parse_material_cost("a diamond worth at least 300 gp, which the spell consumes"),
+            Ok(Some(30000))
+        );
+    }
+}
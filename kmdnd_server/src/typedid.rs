@@ -93,6 +93,7 @@ impl<T: TypedIdMarker> From<TypedId<T>> for Bson {
     }
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone, Debug)]
 pub enum TypedIdParseError {
     InvalidFormat,
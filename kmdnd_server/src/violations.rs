@@ -1,4 +1,6 @@
-use crate::character::{CharacterId, Position};
+use crate::character::class::LimitedFeature;
+use crate::character::{CharacterId, Condition, Position};
+use crate::operation::spell::CastingTime;
 
 use serde::{Deserialize, Serialize};
 
@@ -17,10 +19,58 @@ pub enum Violation {
         attack_range: f32,
         current_range: f32,
     },
+    AttackCountExceeded {
+        request_character_id: CharacterId,
+        maximum_attacks: i32,
+        request_attacks: i32,
+    },
+    ActionCountExceeded {
+        request_character_id: CharacterId,
+        maximum_actions: i32,
+    },
+    LegendaryActionOnOwnTurn {
+        request_character_id: CharacterId,
+    },
+    LegendaryActionsExhausted {
+        request_character_id: CharacterId,
+        maximum_actions: i32,
+        current_actions: i32,
+        request_actions: i32,
+    },
+    FeatureUsesExhausted {
+        request_character_id: CharacterId,
+        feature: LimitedFeature,
+        maximum_uses: i32,
+    },
     CastNotInRange {
         request_character_id: CharacterId,
         target_position: Position,
         spell_range: f32,
         current_range: f32,
     },
+    CastingTimeExceedsTurn {
+        request_character_id: CharacterId,
+        casting_time: CastingTime,
+    },
+    CastVerbalComponentBlocked {
+        request_character_id: CharacterId,
+        condition: Condition,
+    },
+    CastSomaticComponentBlocked {
+        request_character_id: CharacterId,
+        occupied_hands: i32,
+    },
+    CastSpellSlotUnavailable {
+        request_character_id: CharacterId,
+        slot_level: i32,
+    },
+    CastMaterialComponentMissing {
+        request_character_id: CharacterId,
+        material: String,
+        cost: i32,
+    },
+    FriendlyFire {
+        request_character_id: CharacterId,
+        target_character_id: CharacterId,
+    },
 }
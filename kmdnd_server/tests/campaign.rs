@@ -7,6 +7,7 @@ async fn create_campaign() {
 
     let body = CreateCampaignBody {
         name: "The Green Bean Brigade".into(),
+        variant_encumbrance: false,
     };
     let client = Client::default();
     let campaign: CampaignBody = client